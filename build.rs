@@ -0,0 +1,27 @@
+// Generates one test per line of answers.txt, see src/answers.rs
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    println!("cargo:rerun-if-changed=answers.txt");
+    let answers = fs::read_to_string("answers.txt").unwrap();
+
+    let mut tests = String::new();
+    for line in answers.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.splitn(3, ' ').collect();
+        if let [day, part, answer] = fields[..] {
+            tests.push_str(&format!(
+                "#[test]\nfn day{:0>2}_part{}() {{\n    check_answer({}, {}, {:?});\n}}\n\n",
+                day, part, day, part, answer
+            ));
+        }
+    }
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("answer_tests.rs");
+    fs::write(out, tests).unwrap();
+}
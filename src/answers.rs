@@ -0,0 +1,179 @@
+// Expected answers for the puzzle inputs, kept in answers.txt
+// Inputs are read at runtime so a checkout without them still builds;
+// the runner's --check mode and the generated tests both skip missing ones.
+use std::fs;
+use std::path::PathBuf;
+
+use crate::log::log;
+use crate::{Input, Solution};
+
+const ANSWERS: &str = include_str!("../answers.txt");
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Expected {
+    pub day: u32,
+    pub part: u32,
+    pub answer: String,
+}
+
+// one `day part answer` per line, # starts a comment
+pub fn parse(contents: &str) -> Result<Vec<Expected>, String> {
+    let mut result = Vec::new();
+    for (idx, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.splitn(3, ' ').collect();
+        let parsed = match fields[..] {
+            [day, part, answer] => day
+                .parse()
+                .and_then(|day| part.parse().map(|part| (day, part, answer))),
+            _ => {
+                return Err(format!(
+                    "answers line {}: expected 'day part answer', got '{}'",
+                    idx + 1,
+                    line
+                ))
+            }
+        };
+        match parsed {
+            Ok((day, part, answer)) => result.push(Expected {
+                day,
+                part,
+                answer: answer.to_string(),
+            }),
+            Err(_) => {
+                return Err(format!(
+                    "answers line {}: day and part must be numbers, got '{}'",
+                    idx + 1,
+                    line
+                ))
+            }
+        }
+    }
+    Ok(result)
+}
+
+pub fn expected() -> Vec<Expected> {
+    parse(ANSWERS).unwrap()
+}
+
+pub fn expected_answer(day: u32, part: u32) -> Option<String> {
+    expected()
+        .into_iter()
+        .find(|e| e.day == day && e.part == part)
+        .map(|e| e.answer)
+}
+
+// src/aocDD/input of this checkout
+pub fn input_path(day: u32) -> PathBuf {
+    [
+        env!("CARGO_MANIFEST_DIR"),
+        "src",
+        &format!("aoc{:02}", day),
+        "input",
+    ]
+    .iter()
+    .collect()
+}
+
+// the day's puzzle input, None with a message if this machine doesn't have it
+pub fn puzzle_input(day: u32) -> Option<String> {
+    let path = input_path(day);
+    match fs::read_to_string(&path) {
+        Ok(input) => Some(input),
+        Err(_) => {
+            log!(Warn, "skipping day {}: {} is missing", day, path.display());
+            None
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Check {
+    Correct,
+    Wrong(String),
+    // interactive days can't run unattended
+    Skipped(String),
+}
+
+pub fn check(solution: &dyn Solution, part: u32, input: &str, expected: &str) -> Check {
+    if solution.input() == Input::Interactive {
+        return Check::Skipped(String::from("reads commands from stdin"));
+    }
+    match solution.solve(part, input) {
+        Some(Ok(answer)) if answer.to_string() == expected => Check::Correct,
+        Some(Ok(answer)) => Check::Wrong(answer.to_string()),
+        Some(Err(e)) => Check::Wrong(format!("invalid input, {}", e)),
+        None => Check::Wrong(format!("day {} has no part {}", solution.day(), part)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let parsed = parse("# comment\n\n9 1 3507134798\n8 2 CJZHR\n").unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                Expected {
+                    day: 9,
+                    part: 1,
+                    answer: String::from("3507134798")
+                },
+                Expected {
+                    day: 8,
+                    part: 2,
+                    answer: String::from("CJZHR")
+                },
+            ]
+        );
+        assert!(parse("9 1").is_err());
+        assert!(parse("nine 1 5").is_err());
+        assert_eq!(expected_answer(4, 2), Some(String::from("316")));
+    }
+
+    fn check_answer(day: u32, part: u32, expected: &str) {
+        let solution = match crate::solution(day) {
+            Some(solution) => solution,
+            None => {
+                log!(Warn, "skipping day {}: compiled out", day);
+                return;
+            }
+        };
+        let input = if solution.input() == Input::None {
+            String::new()
+        } else {
+            match puzzle_input(day) {
+                Some(input) => input,
+                None => return,
+            }
+        };
+        match check(solution, part, &input, expected) {
+            Check::Correct => {}
+            Check::Wrong(got) => panic!(
+                "day {} part {}: expected {}, got {}",
+                day, part, expected, got
+            ),
+            Check::Skipped(reason) => log!(Warn, "skipping day {}: {}", day, reason),
+        }
+    }
+
+    // --check and these tests solve every day in one process, so a day
+    // mustn't leave anything running that throws off the next
+    #[test]
+    fn day23_twice() {
+        for _ in 0..2 {
+            for part in 1..=2 {
+                check_answer(23, part, &expected_answer(23, part).unwrap());
+            }
+        }
+    }
+
+    // day01_part1, day01_part2, .. one per line of answers.txt
+    include!(concat!(env!("OUT_DIR"), "/answer_tests.rs"));
+}
@@ -1,27 +1,37 @@
-fn read_ints(input: &str) -> Vec<i32> {
+use crate::parse::ParseError;
+
+// one mass per line, blank lines are ignored
+fn read_ints(input: &str) -> Result<Vec<i32>, ParseError> {
     let mut result = Vec::new();
 
-    for line in input.lines() {
-        result.push(line.trim().parse::<i32>().unwrap());
+    for (idx, line) in input.lines().enumerate() {
+        let mass = line.trim();
+        if mass.is_empty() {
+            continue;
+        }
+        match mass.parse() {
+            Ok(mass) => result.push(mass),
+            Err(_) => return Err(ParseError::within(idx, line, mass, "a module mass")),
+        }
     }
-    result
+    Ok(result)
 }
 
 fn fuel_required(mass: i32) -> i32 {
     mass / 3 - 2
 }
 
-pub fn solve_first(input: &str) -> i32 {
-    let masses = read_ints(input);
+pub fn solve_first(input: &str) -> Result<i32, ParseError> {
+    let masses = read_ints(input)?;
     let mut result = 0;
     for mass in masses {
         result += fuel_required(mass);
     }
-    result
+    Ok(result)
 }
 
-pub fn solve_second(input: &str) -> i32 {
-    let masses = read_ints(input);
+pub fn solve_second(input: &str) -> Result<i32, ParseError> {
+    let masses = read_ints(input)?;
     let mut result = 0;
     for mass in masses {
         let mut required = fuel_required(mass);
@@ -34,7 +44,7 @@ pub fn solve_second(input: &str) -> i32 {
             result += required;
         }
     }
-    result
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -42,14 +52,19 @@ mod test {
     use super::*;
 
     #[test]
-    fn test_first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 3315383);
+    fn examples() {
+        assert_eq!(
+            solve_first("12\n14\n1969\n100756\n"),
+            Ok(2 + 2 + 654 + 33583)
+        );
+        assert_eq!(solve_second("14\n\n1969\n100756"), Ok(2 + 966 + 50346));
     }
 
     #[test]
-    fn test_second() {
-        let input = include_str!("input");
-        assert_eq!(solve_second(input), 4970206);
+    fn parse_error() {
+        assert_eq!(
+            read_ints("12\n  14 kg\n"),
+            Err(ParseError::new(2, 3, "14 kg", "a module mass"))
+        );
     }
 }
@@ -1,9 +1,8 @@
-fn read_codes(input: &str) -> Vec<i32> {
-    let mut result = Vec::new();
-    for number_str in input.trim().split(',') {
-        result.push(number_str.parse().unwrap())
-    }
-    result
+use crate::intcode::{self, solver::GoalSeek};
+use crate::parse::{self, ParseError};
+
+fn read_codes(input: &str) -> Result<Vec<i32>, ParseError> {
+    parse::integers(input)
 }
 
 pub fn naive_run(mut memory: Vec<i32>, noun: i32, verb: i32) -> i32 {
@@ -28,7 +27,8 @@ pub fn naive_run(mut memory: Vec<i32>, noun: i32, verb: i32) -> i32 {
                 let result_pos = memory[position + 3] as usize;
                 memory[result_pos] = value1 * value2;
             }
-            99 | _ => {
+            // 99 halts, and so does anything unknown
+            _ => {
                 break;
             }
         }
@@ -38,21 +38,29 @@ pub fn naive_run(mut memory: Vec<i32>, noun: i32, verb: i32) -> i32 {
     memory[0]
 }
 
-pub fn solve_first(input: &str) -> i32 {
-    let memory = read_codes(input);
-    naive_run(memory, 12, 2)
+pub fn solve_first(input: &str) -> Result<i32, ParseError> {
+    let memory = read_codes(input)?;
+    Ok(naive_run(memory, 12, 2))
 }
 
-pub fn solve_second(input: &str) -> i32 {
-    let memory = read_codes(input);
-    for noun in 0..memory.len() {
-        for verb in 0..memory.len() {
-            if naive_run(memory.clone(), noun as i32, verb as i32) == 19690720 {
-                return (noun * 100 + verb) as i32;
-            }
-        }
+pub fn solve_second(input: &str) -> Result<i32, ParseError> {
+    let memory = intcode::read_codes(input)?;
+    let solutions = GoalSeek::new(memory)
+        .vary(1, 0..=99)
+        .vary(2, 0..=99)
+        .solve(|outcome| outcome.memory[0] == 19690720)
+        .map_err(|e| {
+            ParseError::new(1, 1, "", &format!("a program with a noun and verb, {}", e))
+        })?;
+    match solutions.first().map(|v| v.as_slice()) {
+        Some(&[noun, verb]) => Ok((noun * 100 + verb) as i32),
+        _ => Err(ParseError::new(
+            1,
+            1,
+            "",
+            "a noun and verb that give 19690720",
+        )),
     }
-    -1
 }
 
 #[cfg(test)]
@@ -60,14 +68,19 @@ mod test {
     use super::*;
 
     #[test]
-    fn test_first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 7210630);
-    }
-
-    #[test]
-    fn test_second() {
-        let input = include_str!("input");
-        assert_eq!(solve_second(input), 3892);
+    fn second_errors() {
+        assert_eq!(
+            solve_second("1,0").unwrap_err(),
+            ParseError::new(
+                1,
+                1,
+                "",
+                "a program with a noun and verb, address 2 is outside the program's 2 cells"
+            )
+        );
+        assert_eq!(
+            solve_second("99,0,0").unwrap_err(),
+            ParseError::new(1, 1, "", "a noun and verb that give 19690720")
+        );
     }
 }
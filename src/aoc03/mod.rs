@@ -1,14 +1,27 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
 
-fn read_wire(input: &str) -> HashMap<(i32, i32), i32> {
+use crate::parse::ParseError;
+use crate::random::Rng;
+
+const SEGMENT: &str = "a direction U, D, L or R followed by a distance";
+
+// the steps a wire takes to reach each position it passes through
+type Wire = HashMap<(i32, i32), i32>;
+
+// positions the wire on line_idx passes through, with the steps it took to get there
+fn read_wire(line_idx: usize, line: &str) -> Result<Wire, ParseError> {
     let mut result = HashMap::new();
 
     let mut position = (0, 0);
     let mut steps = 0;
-    for wire in input.split_terminator(|c| c == ',' || c == '\n') {
-        let num = wire[1..].parse::<i32>().unwrap();
-        match wire.chars().nth(0).unwrap() {
+    for wire in line.split(',') {
+        let wire = wire.trim();
+        let num = match wire.get(1..).map(|n| n.parse::<i32>()) {
+            Some(Ok(num)) => num,
+            _ => return Err(ParseError::within(line_idx, line, wire, SEGMENT)),
+        };
+        match wire.chars().next().unwrap() {
             'U' => {
                 let next_position = (position.0, position.1 + num);
                 for y in position.1 + 1..=next_position.1 {
@@ -41,19 +54,27 @@ fn read_wire(input: &str) -> HashMap<(i32, i32), i32> {
                 }
                 position = next_position;
             }
-            _ => {
-                continue;
-            }
+            _ => return Err(ParseError::within(line_idx, line, wire, SEGMENT)),
         }
     }
 
-    result
+    Ok(result)
 }
 
-pub fn solve_first(input: &str) -> i32 {
-    let mut lines = input.lines();
-    let w1: HashSet<(i32, i32)> = read_wire(lines.next().unwrap()).keys().copied().collect();
-    let w2: HashSet<(i32, i32)> = read_wire(lines.next().unwrap()).keys().copied().collect();
+// the first two lines, one wire each
+fn read_wires(input: &str) -> Result<[Wire; 2], ParseError> {
+    let mut lines = input.lines().enumerate();
+    let mut next = || match lines.next() {
+        Some((idx, line)) => read_wire(idx, line),
+        None => Err(ParseError::new(input.lines().count() + 1, 1, "", "a wire")),
+    };
+    Ok([next()?, next()?])
+}
+
+pub fn solve_first(input: &str) -> Result<i32, ParseError> {
+    let [map1, map2] = read_wires(input)?;
+    let w1: HashSet<(i32, i32)> = map1.keys().copied().collect();
+    let w2: HashSet<(i32, i32)> = map2.keys().copied().collect();
     let mut result = i32::max_value();
     for position in w1.intersection(&w2) {
         let distance = position.0.abs() + position.1.abs();
@@ -61,13 +82,11 @@ pub fn solve_first(input: &str) -> i32 {
             result = distance;
         }
     }
-    result
+    Ok(result)
 }
 
-pub fn solve_second(input: &str) -> i32 {
-    let mut lines = input.lines();
-    let map1 = read_wire(lines.next().unwrap());
-    let map2 = read_wire(lines.next().unwrap());
+pub fn solve_second(input: &str) -> Result<i32, ParseError> {
+    let [map1, map2] = read_wires(input)?;
 
     let set1: HashSet<(i32, i32)> = map1.keys().copied().collect();
     let set2: HashSet<(i32, i32)> = map2.keys().copied().collect();
@@ -80,7 +99,28 @@ pub fn solve_second(input: &str) -> i32 {
             min_steps = position_steps;
         }
     }
-    min_steps
+    Ok(min_steps)
+}
+
+// two wires of `size` random segments after a fixed start that makes them
+// cross at (x, 0) for some x in 1..99, so both parts always have an answer
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let right = rng.range(2..=99);
+    let up = rng.range(1..=99);
+    let mut first = vec![format!("R{}", right)];
+    let mut second = vec![
+        format!("U{}", up),
+        format!("R{}", rng.range(1..=right - 1)),
+        format!("D{}", up + rng.range(1..=99)),
+    ];
+    for wire in [&mut first, &mut second].iter_mut() {
+        for _ in 0..size {
+            let direction = rng.pick(&['U', 'D', 'L', 'R']);
+            wire.push(format!("{}{}", direction, rng.range(1..=99)));
+        }
+    }
+    format!("{}\n{}\n", first.join(","), second.join(","))
 }
 
 #[cfg(test)]
@@ -88,14 +128,37 @@ mod test {
     use super::*;
 
     #[test]
-    fn test_first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 225);
+    fn test_wires() {
+        let input = "R8,U5,L5,D3\nU7,R6,D4,L4\n";
+        assert_eq!(solve_first(input), Ok(6));
+        assert_eq!(solve_second(input), Ok(30));
+    }
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(generate(10, 3), generate(10, 3));
+        for seed in 0..5 {
+            let input = generate(30, seed);
+            let closest = solve_first(&input).unwrap();
+            assert!(closest > 0 && closest < 99);
+            // a crossing is at least its distance away along both wires
+            assert!(solve_second(&input).unwrap() >= 2 * closest);
+        }
     }
 
     #[test]
-    fn test_second() {
-        let input = include_str!("input");
-        assert_eq!(solve_second(input), 35194);
+    fn test_parse_error() {
+        assert_eq!(
+            solve_first("R8,U5,L5,D3\nU7,X6,D4,L4\n"),
+            Err(ParseError::new(2, 4, "X6", SEGMENT))
+        );
+        assert_eq!(
+            solve_first("R8,U5,L5,D3\nU7,R,D4\n"),
+            Err(ParseError::new(2, 4, "R", SEGMENT))
+        );
+        assert_eq!(
+            solve_first("R8,U5\n"),
+            Err(ParseError::new(2, 1, "", "a wire"))
+        );
     }
 }
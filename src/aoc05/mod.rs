@@ -1,19 +1,7 @@
-fn read_codes(input: &str) -> Vec<i32> {
-    let mut result = Vec::new();
-
-    for number_str in input.trim().split(',') {
-        match number_str.parse() {
-            Ok(num) => result.push(num),
-            Err(e) => {
-                println!(
-                    "Error parsing number, input was: {}, error was: {}",
-                    number_str, e
-                );
-                panic!();
-            }
-        }
-    }
-    result
+use crate::parse::{self, ParseError};
+
+fn read_codes(input: &str) -> Result<Vec<i32>, ParseError> {
+    parse::integers(input)
 }
 
 // returns (A, B, C, DE)
@@ -43,7 +31,13 @@ fn save_value(memory: &mut [i32], index: usize, mode: i32, value: i32) {
 }
 
 // returns (instruction ran, pointer)
-fn run_instruction(mut memory: &mut [i32], pointer: usize) -> (i32, usize) {
+// every input instruction reads `input`, the system ID to test
+fn run_instruction(
+    mut memory: &mut [i32],
+    pointer: usize,
+    input: i32,
+    output: &mut Vec<i32>,
+) -> (i32, usize) {
     let code = memory[pointer];
     let (arg3_mode, arg2_mode, arg1_mode, op) = decode_op(code);
     let next_pointer = match op {
@@ -59,13 +53,11 @@ fn run_instruction(mut memory: &mut [i32], pointer: usize) -> (i32, usize) {
             pointer + 4
         }
         3 => {
-            // read number from stdin, hacked together to always set 1 or 5 since we're already reading everything from stdin
-            // fixing this requires switching program to read input from file and read stdin here
-            save_value(&mut memory, pointer + 1, arg1_mode, 5);
+            save_value(memory, pointer + 1, arg1_mode, input);
             pointer + 2
         }
         4 => {
-            println!("{}", load_value(memory, pointer + 1, arg1_mode));
+            output.push(load_value(memory, pointer + 1, arg1_mode));
             pointer + 2
         }
         5 => {
@@ -108,25 +100,39 @@ fn run_instruction(mut memory: &mut [i32], pointer: usize) -> (i32, usize) {
     (op, next_pointer)
 }
 
-fn intcode_computer(mut memory: Vec<i32>) {
+// returns everything the program output
+fn intcode_computer(mut memory: Vec<i32>, input: i32) -> Vec<i32> {
+    let mut output = Vec::new();
     let mut instruction_pointer = 0;
     while instruction_pointer < memory.len() {
-        let (op, next_pointer) = run_instruction(&mut memory, instruction_pointer);
+        let (op, next_pointer) =
+            run_instruction(&mut memory, instruction_pointer, input, &mut output);
         instruction_pointer = next_pointer;
         if op == 99 {
             break;
         }
     }
+    output
 }
 
-pub fn solve_first(input: &str) {
-    let mem = read_codes(input);
-    intcode_computer(mem);
+// the diagnostic code is the last output, the ones before it are test results
+fn diagnostic_code(input: &str, system_id: i32) -> Result<i32, ParseError> {
+    let mem = read_codes(input)?;
+    let output = intcode_computer(mem, system_id);
+    output
+        .last()
+        .copied()
+        .ok_or_else(|| ParseError::new(1, 1, "", "a program that outputs a diagnostic code"))
 }
 
-pub fn solve_second(input: &str) {
-    let mem = read_codes(input);
-    intcode_computer(mem);
+pub fn solve_first(input: &str) -> Result<i32, ParseError> {
+    // air conditioner unit
+    diagnostic_code(input, 1)
+}
+
+pub fn solve_second(input: &str) -> Result<i32, ParseError> {
+    // thermal radiator controller
+    diagnostic_code(input, 5)
 }
 
 #[cfg(test)]
@@ -172,9 +178,23 @@ mod test {
     #[test]
     fn test_instruction() {
         let mut memory = vec![1101, 100, -1, 4, 0];
-        let (op, incr) = run_instruction(&mut memory, 0);
+        let (op, incr) = run_instruction(&mut memory, 0, 0, &mut Vec::new());
         assert!(op == 1);
         assert!(incr == 4);
         assert!(memory[4] == 99);
     }
+
+    #[test]
+    fn no_output() {
+        assert_eq!(
+            solve_first("3,0,99"),
+            Err(ParseError::new(
+                1,
+                1,
+                "",
+                "a program that outputs a diagnostic code"
+            ))
+        );
+        assert_eq!(solve_first("3,0,4,0,99"), Ok(1));
+    }
 }
@@ -1,18 +1,26 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-fn read_orbit(input: &str) -> HashMap<String, String> {
-    let mut edges = HashMap::new();
+use crate::parse::ParseError;
+use crate::random::Rng;
 
-    for line in input.lines() {
-        let mut split_it = line.split(')');
-        let left = split_it.next().unwrap();
-        let right = split_it.next().unwrap();
+fn read_orbit(input: &str) -> Result<HashMap<String, String>, ParseError> {
+    let mut edges = HashMap::new();
 
-        // right orbits left
-        edges.insert(right.to_owned(), left.to_owned());
+    for (idx, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match line.split(')').collect::<Vec<_>>()[..] {
+            [left, right] if !left.is_empty() && !right.is_empty() => {
+                // right orbits left
+                edges.insert(right.to_owned(), left.to_owned());
+            }
+            _ => return Err(ParseError::within(idx, line, line, "two objects as A)B")),
+        }
     }
 
-    edges
+    Ok(edges)
 }
 
 fn count_orbits(edges: HashMap<String, String>) -> i32 {
@@ -39,9 +47,9 @@ fn visit(edges: &HashMap<String, String>, from: &str, memo: &mut HashMap<String,
     }
 }
 
-pub fn solve_first(input: &str) -> i32 {
-    let edges = read_orbit(input);
-    count_orbits(edges)
+pub fn solve_first(input: &str) -> Result<i32, ParseError> {
+    let edges = read_orbit(input)?;
+    Ok(count_orbits(edges))
 }
 
 fn paths(edges: &HashMap<String, String>, start: &str) -> Vec<String> {
@@ -56,16 +64,48 @@ fn paths(edges: &HashMap<String, String>, start: &str) -> Vec<String> {
     result
 }
 
-pub fn solve_second(input: &str) -> i32 {
-    let edges = read_orbit(input);
+pub fn solve_second(input: &str) -> Result<i32, ParseError> {
+    let edges = read_orbit(input)?;
     let mut paths_you = paths(&edges, "YOU");
     let mut paths_san = paths(&edges, "SAN");
 
-    while paths_you.last() == paths_san.last() {
+    // drop the orbits both paths share, the last of them is where they meet
+    while let (Some(you), Some(san)) = (paths_you.last(), paths_san.last()) {
+        if you != san {
+            break;
+        }
         paths_you.pop();
         paths_san.pop();
     }
-    (paths_san.len() + paths_you.len()) as i32
+    Ok((paths_san.len() + paths_you.len()) as i32)
+}
+
+// `size` objects orbiting COM, each around a random earlier object, plus YOU
+// and SAN; names are three random characters and the lines come in random order
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let alphabet: Vec<char> = ('A'..='Z').chain('0'..='9').collect();
+    let mut names = vec![String::from("COM")];
+    let mut used: HashSet<String> = names.iter().cloned().collect();
+    used.insert(String::from("YOU"));
+    used.insert(String::from("SAN"));
+
+    let mut lines = Vec::new();
+    while names.len() <= size {
+        let name: String = (0..3).map(|_| *rng.pick(&alphabet)).collect();
+        if used.insert(name.clone()) {
+            lines.push(format!("{}){}", rng.pick(&names), name));
+            names.push(name);
+        }
+    }
+    for &name in ["YOU", "SAN"].iter() {
+        lines.push(format!("{}){}", rng.pick(&names), name));
+    }
+
+    for i in (1..lines.len()).rev() {
+        lines.swap(i, rng.below(i + 1));
+    }
+    lines.join("\n") + "\n"
 }
 
 #[cfg(test)]
@@ -75,27 +115,42 @@ mod test {
     #[test]
     fn test_read() {
         let input = "A)B\nB)C\n";
-        let edges = read_orbit(input);
+        let edges = read_orbit(input).unwrap();
         assert_eq!(edges.get("B"), Some(&"A".to_owned()));
         assert_eq!(edges.get("C"), Some(&"B".to_owned()));
+
+        assert_eq!(
+            read_orbit("A)B\nB C\n"),
+            Err(ParseError::new(2, 1, "B C", "two objects as A)B"))
+        );
     }
 
     #[test]
-    fn test_count() {
-        let input = "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L";
-        let edges = read_orbit(input);
-        assert_eq!(count_orbits(edges), 42);
+    fn test_generate() {
+        assert_eq!(generate(20, 6), generate(20, 6));
+        for seed in 0..5 {
+            let input = generate(200, seed);
+            assert_eq!(read_orbit(&input).unwrap().len(), 202);
+            // every object orbits COM at least, YOU and SAN orbit something
+            assert!(solve_first(&input).unwrap() >= 202);
+            assert!(solve_second(&input).unwrap() <= 2 * 200);
+        }
+        // YOU and SAN can only orbit COM
+        assert_eq!(solve_second(&generate(0, 0)).unwrap(), 0);
     }
 
     #[test]
-    fn test_first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 241064);
+    fn test_transfers() {
+        let input = "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L\nK)YOU\nI)SAN";
+        assert_eq!(solve_second(input).unwrap(), 4);
+        // orbiting the same object takes no transfers
+        assert_eq!(solve_second("COM)A\nA)YOU\nA)SAN").unwrap(), 0);
     }
 
     #[test]
-    fn test_second() {
-        let input = include_str!("input");
-        assert_eq!(solve_second(input), 418);
+    fn test_count() {
+        let input = "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L";
+        let edges = read_orbit(input).unwrap();
+        assert_eq!(count_orbits(edges), 42);
     }
 }
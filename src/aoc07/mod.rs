@@ -1,23 +1,11 @@
-use crossbeam::channel::unbounded;
+use crossbeam::channel::{bounded, unbounded};
 use itertools::Itertools;
 use std::thread;
 
-fn read_codes(input: &str) -> Vec<i32> {
-    let mut result = Vec::new();
-
-    for number_str in input.split(',') {
-        match number_str.parse() {
-            Ok(num) => result.push(num),
-            Err(e) => {
-                println!(
-                    "Error parsing number, input was: {}, error was: {}",
-                    number_str, e
-                );
-                panic!();
-            }
-        }
-    }
-    result
+use crate::parse::{self, ParseError};
+
+fn read_codes(input: &str) -> Result<Vec<i32>, ParseError> {
+    parse::integers(input)
 }
 
 // returns (A, B, C, DE)
@@ -145,40 +133,139 @@ impl Amplifier {
     }
 }
 
-pub fn solve_first(memory_input: &str) -> i32 {
-    let mem = read_codes(memory_input);
-    let mut max = i32::min_value();
+// progress report sent by the phase search every `PROGRESS_INTERVAL` permutations
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    pub evaluated: usize,
+    pub total: usize,
+    pub best: Option<(Vec<i32>, i32)>,
+}
+
+const PROGRESS_INTERVAL: usize = 1000;
+
+pub struct PhaseSearch {
+    phases: Vec<i32>,
+    amplifiers: usize,
+    workers: usize,
+    progress: Option<crossbeam::Sender<Progress>>,
+}
 
-    for permutation in (0..5).permutations(5) {
-        let permutation_result = amplifier_circuit(mem.clone(), permutation);
-        if permutation_result > max {
-            max = permutation_result;
+impl PhaseSearch {
+    // searches every arrangement of `amplifiers` distinct phases out of the given alphabet
+    pub fn new(phases: Vec<i32>, amplifiers: usize) -> Self {
+        let workers = thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        PhaseSearch {
+            phases,
+            amplifiers,
+            workers,
+            progress: None,
         }
     }
-    max
-}
 
-pub fn solve_second(memory_input: &str) -> i32 {
-    let mem = read_codes(memory_input);
-    let mut max = i32::min_value();
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    pub fn progress(mut self, sender: crossbeam::Sender<Progress>) -> Self {
+        self.progress = Some(sender);
+        self
+    }
 
-    for permutation in (5..10).permutations(5) {
-        let permutation_result = amplifier_circuit(mem.clone(), permutation);
-        if permutation_result > max {
-            max = permutation_result;
+    // number of permutations that will be evaluated, n! / (n - k)!
+    pub fn total(&self) -> usize {
+        if self.amplifiers == 0 || self.amplifiers > self.phases.len() {
+            return 0;
         }
+        let n = self.phases.len();
+        (n - self.amplifiers + 1..=n).product()
     }
-    max
+
+    // returns (phase settings, signal) of the best permutation, the smallest
+    // settings among equal signals, or None if there are no amplifiers or
+    // fewer phases than amplifiers
+    pub fn run(&self, memory: &[i32]) -> Option<(Vec<i32>, i32)> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+        // bounded so the producer doesn't materialize every permutation up front
+        let (work_sender, work_receiver) = bounded::<Vec<i32>>(self.workers * 4);
+        let (result_sender, result_receiver) = unbounded::<(Vec<i32>, i32)>();
+
+        crossbeam::scope(|scope| {
+            for _ in 0..self.workers {
+                let work = work_receiver.clone();
+                let results = result_sender.clone();
+                scope.spawn(move |_| {
+                    for permutation in work.iter() {
+                        let signal = amplifier_circuit(memory.to_vec(), permutation.clone());
+                        results.send((permutation, signal)).unwrap();
+                    }
+                });
+            }
+            // workers hold their own clones, drop ours so the result channel closes with them
+            drop(work_receiver);
+            drop(result_sender);
+
+            let phases = &self.phases;
+            let amplifiers = self.amplifiers;
+            scope.spawn(move |_| {
+                for permutation in phases.iter().copied().permutations(amplifiers) {
+                    work_sender.send(permutation).unwrap();
+                }
+            });
+
+            let mut best: Option<(Vec<i32>, i32)> = None;
+            let mut evaluated = 0;
+            for (permutation, signal) in result_receiver.iter() {
+                evaluated += 1;
+                // workers finish in any order, so ties go to the permutation
+                if best.as_ref().is_none_or(|(best_permutation, best_signal)| {
+                    (signal, best_permutation) > (*best_signal, &permutation)
+                }) {
+                    best = Some((permutation, signal));
+                }
+                if let Some(progress) = &self.progress {
+                    if evaluated % PROGRESS_INTERVAL == 0 || evaluated == total {
+                        // the listener may have gone away, that shouldn't stop the search
+                        let _ = progress.send(Progress {
+                            evaluated,
+                            total,
+                            best: best.clone(),
+                        });
+                    }
+                }
+            }
+            best
+        })
+        .expect("panic on phase search worker")
+    }
+}
+
+pub fn solve_first(memory_input: &str) -> Result<i32, ParseError> {
+    let mem = read_codes(memory_input)?;
+    let (_, signal) = PhaseSearch::new((0..5).collect(), 5).run(&mem).unwrap();
+    Ok(signal)
+}
+
+pub fn solve_second(memory_input: &str) -> Result<i32, ParseError> {
+    let mem = read_codes(memory_input)?;
+    let (_, signal) = PhaseSearch::new((5..10).collect(), 5).run(&mem).unwrap();
+    Ok(signal)
 }
 
 fn amplifier_circuit(memory: Vec<i32>, amplifier_signals: Vec<i32>) -> i32 {
     // create all channels and set up initial input
     let mut senders: Vec<crossbeam::Sender<i32>> = Vec::new();
     let mut receivers: Vec<crossbeam::Receiver<i32>> = Vec::new();
-    for i in 0..5 {
+    let amplifiers = amplifier_signals.len();
+    for &signal in amplifier_signals.iter() {
         let (sender, receiver) = unbounded();
         // send each amplifier its corresponding signal
-        sender.send(amplifier_signals[i]).unwrap();
+        sender.send(signal).unwrap();
 
         senders.push(sender);
         receivers.push(receiver);
@@ -188,12 +275,12 @@ fn amplifier_circuit(memory: Vec<i32>, amplifier_signals: Vec<i32>) -> i32 {
 
     let mut children = Vec::new();
     // create tasks
-    for id in 0..5 {
+    for id in 0..amplifiers {
         let amplifier_mem = memory.clone();
         let amp_receiver = receivers[id].clone();
         // amplifier output is connected to the next amplifier's input
         // the last amplifier output is connected to amplifier 0
-        let amp_sender = senders[(id + 1) % 5].clone();
+        let amp_sender = senders[(id + 1) % amplifiers].clone();
         let child = thread::spawn(move || {
             let mut amp = Amplifier::new(amplifier_mem, amp_receiver, amp_sender);
             amp.run();
@@ -234,26 +321,70 @@ mod test {
     fn test_feedback1() {
         let input =
             "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5";
-        let result = solve_second(input);
+        let result = solve_second(input).unwrap();
         assert_eq!(result, 139629729);
     }
 
     #[test]
     fn test_feedback2() {
         let input = "3,52,1001,52,-5,52,3,53,1,52,56,54,1007,54,5,55,1005,55,26,1001,54,-5,54,1105,1,12,1,53,54,53,1008,54,0,55,1001,55,1,55,2,53,55,53,4,53,1001,56,-1,56,1005,56,6,99,0,0,0,0,10";
-        let result = solve_second(input);
+        let result = solve_second(input).unwrap();
 
         assert_eq!(result, 18216);
     }
 
     #[test]
-    fn test_first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 67023);
+    fn test_phase_settings() {
+        let memory = read_codes("3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0").unwrap();
+        let result = PhaseSearch::new((0..5).collect(), 5)
+            .workers(2)
+            .run(&memory);
+        assert_eq!(result, Some((vec![4, 3, 2, 1, 0], 43210)));
+
+        let memory = read_codes(
+            "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5",
+        )
+        .unwrap();
+        let result = PhaseSearch::new((5..10).collect(), 5).run(&memory);
+        assert_eq!(result, Some((vec![9, 8, 7, 6, 5], 139629729)));
     }
+
     #[test]
-    fn test_second() {
-        let input = include_str!("input");
-        assert_eq!(solve_second(input), 7818398);
+    fn test_phase_alphabet() {
+        // three amplifiers picking from seven phases
+        let memory = read_codes("3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0").unwrap();
+        let search = PhaseSearch::new((0..7).collect(), 3);
+        assert_eq!(search.total(), 210);
+        assert_eq!(search.run(&memory), Some((vec![6, 5, 4], 654)));
+
+        assert_eq!(PhaseSearch::new(vec![0, 1], 3).run(&memory), None);
+        assert_eq!(PhaseSearch::new(vec![0, 1], 0).total(), 0);
+        assert_eq!(PhaseSearch::new(vec![0, 1], 0).run(&memory), None);
+    }
+
+    #[test]
+    fn test_phase_ties() {
+        // passes its input on whatever the phase, so every permutation ties
+        let memory = read_codes("3,7,3,8,4,8,99,0,0").unwrap();
+        for _ in 0..10 {
+            let search = PhaseSearch::new(vec![2, 0, 1], 3).workers(3);
+            assert_eq!(search.run(&memory), Some((vec![0, 1, 2], 0)));
+        }
+    }
+
+    #[test]
+    fn test_progress() {
+        let memory = read_codes("3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0").unwrap();
+        let (sender, receiver) = unbounded();
+        let search = PhaseSearch::new((0..7).collect(), 5).progress(sender);
+        let best = search.run(&memory);
+
+        let reports: Vec<Progress> = receiver.try_iter().collect();
+        assert_eq!(reports.len(), 3);
+        assert_eq!(reports[0].evaluated, 1000);
+        let last = reports.last().unwrap();
+        assert_eq!(last.evaluated, 2520);
+        assert_eq!(last.total, 2520);
+        assert_eq!(last.best, best);
     }
 }
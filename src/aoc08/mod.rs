@@ -1,3 +1,20 @@
+use crate::ocr;
+use crate::params::{at_least, params, Checked};
+use crate::parse::ParseError;
+use crate::render::Picture;
+use crate::Bitmap;
+
+params! {
+    pub struct Params {
+        width: usize = 25,
+        height: usize = 6,
+    }
+    check(params) {
+        at_least("width", params.width, 1)?;
+        at_least("height", params.height, 1)
+    }
+}
+
 struct Image {
     data: Vec<u8>,
     width: usize,
@@ -5,18 +22,32 @@ struct Image {
 }
 
 impl Image {
-    fn new(input: &str, width: usize, height: usize) -> Image {
-        let data: Vec<u8> = input
-            .trim()
-            .chars()
-            .filter_map(|c| c.to_digit(10))
-            .map(|n| n as u8)
-            .collect();
-        Image {
+    // the digits of whole layers `width` by `height`
+    fn new(input: &str, width: usize, height: usize) -> Result<Image, ParseError> {
+        let line = input.trim();
+        let layer = format!("whole layers of {}x{} digits", width, height);
+        let mut data = Vec::new();
+        for (column, c) in line.chars().enumerate() {
+            match c.to_digit(10) {
+                Some(n) => data.push(n as u8),
+                None => return Err(ParseError::new(1, column + 1, &c.to_string(), &layer)),
+            }
+        }
+        let size = width * height;
+        if size == 0 || data.is_empty() || data.len() % size != 0 {
+            let rest: String = line.chars().skip(data.len() / size.max(1) * size).collect();
+            return Err(ParseError::new(
+                1,
+                data.len() - rest.len() + 1,
+                &rest,
+                &layer,
+            ));
+        }
+        Ok(Image {
             data,
             width,
             height,
-        }
+        })
     }
 
     fn layers(&self) -> std::slice::Chunks<u8> {
@@ -68,22 +99,45 @@ impl Image {
         result
     }
 
-    fn print(&self) {
-        let i = self.final_image();
-        for line in i.chunks(self.width) {
-            println!("{:?}", line);
+    fn to_bitmap(&self) -> Bitmap {
+        let pixels = self.final_image().iter().map(|&p| p == 1).collect();
+        Bitmap {
+            width: self.width,
+            height: self.height,
+            pixels,
         }
     }
 }
 
-pub fn solve_first(input: &str) -> i32 {
-    let img = Image::new(input, 25, 6);
-    img.solve()
+pub fn solve_first_with(input: &str, params: &Params) -> Checked<i32> {
+    params.validate()?;
+    Ok(Image::new(input, params.width, params.height).map(|img| img.solve()))
+}
+
+pub fn solve_first(input: &str) -> Result<i32, ParseError> {
+    solve_first_with(input, &Params::default()).expect("the defaults are valid")
 }
 
-pub fn solve_second(input: &str) {
-    let img = Image::new(input, 25, 6);
-    img.print();
+// the decoded message, white pixels are lit
+pub fn message_with(input: &str, params: &Params) -> Checked<Bitmap> {
+    params.validate()?;
+    Ok(Image::new(input, params.width, params.height).map(|img| img.to_bitmap()))
+}
+
+pub fn message(input: &str) -> Result<Bitmap, ParseError> {
+    message_with(input, &Params::default()).expect("the defaults are valid")
+}
+
+pub fn solve_second_with(input: &str, params: &Params) -> Checked<String> {
+    Ok(message_with(input, params)?.and_then(|message| ocr::read(&message)))
+}
+
+pub fn solve_second(input: &str) -> Result<String, ParseError> {
+    solve_second_with(input, &Params::default()).expect("the defaults are valid")
+}
+
+pub fn render(input: &str) -> Result<Picture, ParseError> {
+    Ok(Picture::from(&message(input)?))
 }
 
 #[cfg(test)]
@@ -93,7 +147,7 @@ mod test {
     #[test]
     fn test_new() {
         let input = "123456789012";
-        let image = Image::new(input, 3, 2);
+        let image = Image::new(input, 3, 2).unwrap();
         let mut layers = image.layers();
 
         assert_eq!(layers.next().unwrap(), &[1, 2, 3, 4, 5, 6]);
@@ -103,20 +157,47 @@ mod test {
     #[test]
     fn test_find() {
         let input = "103050789012";
-        let image = Image::new(input, 3, 2);
+        let image = Image::new(input, 3, 2).unwrap();
         assert_eq!(image.find_low_corrupt(), &[7, 8, 9, 0, 1, 2]);
     }
 
     #[test]
     fn test_solve() {
         let input = "111202000000";
-        let image = Image::new(input, 3, 2);
+        let image = Image::new(input, 3, 2).unwrap();
         assert_eq!(image.solve(), 6);
     }
 
     #[test]
-    fn test_first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 1560);
+    fn bad_params() {
+        let params = Params {
+            width: 0,
+            ..Params::default()
+        };
+        assert_eq!(
+            solve_first_with("123456", &params),
+            Err(String::from(
+                "invalid value '0' for width, expected at least 1"
+            ))
+        );
+        assert!(solve_second_with("123456", &params).is_err());
+    }
+
+    #[test]
+    fn parse_error() {
+        let layer = "whole layers of 3x2 digits";
+        assert!(Image::new("123456\n", 3, 2).is_ok());
+        assert_eq!(
+            Image::new("12345x", 3, 2).err(),
+            Some(ParseError::new(1, 6, "x", layer))
+        );
+        assert_eq!(
+            Image::new("12345678", 3, 2).err(),
+            Some(ParseError::new(1, 7, "78", layer))
+        );
+        assert_eq!(
+            Image::new("", 3, 2).err(),
+            Some(ParseError::new(1, 1, "", layer))
+        );
     }
 }
@@ -1,21 +1,9 @@
 use crossbeam::channel::unbounded;
 
-fn read_codes(input: &str) -> Vec<i64> {
-    let mut result = Vec::new();
-
-    for number_str in input.trim().split(',') {
-        match number_str.parse() {
-            Ok(num) => result.push(num),
-            Err(e) => {
-                println!(
-                    "Error parsing number, input was: {}, error was: {}",
-                    number_str, e
-                );
-                panic!();
-            }
-        }
-    }
-    result
+use crate::parse::{self, ParseError};
+
+fn read_codes(input: &str) -> Result<Vec<i64>, ParseError> {
+    parse::integers(input)
 }
 
 // returns (A, B, C, DE)
@@ -209,39 +197,76 @@ fn single_intcode(memory: Vec<i64>, input: Vec<i64>) -> Vec<i64> {
     output
 }
 
-pub fn solve_first(input: &str) -> i64 {
-    let memory = read_codes(input);
+pub fn solve_first(input: &str) -> Result<i64, ParseError> {
+    let memory = read_codes(input)?;
     let input = vec![1];
-    *single_intcode(memory, input).first().unwrap()
+    Ok(*single_intcode(memory, input).first().unwrap())
 }
 
-pub fn solve_second(input: &str) -> i64 {
-    let memory = read_codes(input);
+pub fn solve_second(input: &str) -> Result<i64, ParseError> {
+    let memory = read_codes(input)?;
     let input = vec![2];
-    *single_intcode(memory, input).first().unwrap()
+    Ok(*single_intcode(memory, input).first().unwrap())
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::intcode::conformance::assert_conforms;
+    use crate::intcode::{Status, Vm};
 
-    #[test]
-    fn test_single_intcode() {
-        let input = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
-        let memory = read_codes(input);
-        let output = single_intcode(memory.clone(), Vec::new());
-        assert_eq!(memory, output);
+    // day 9's interpreter with the other ends of its channels
+    struct Day09 {
+        intcode: Intcode,
+        input: crossbeam::Sender<i64>,
+        output: crossbeam::Receiver<i64>,
+    }
+
+    impl Vm for Day09 {
+        fn load(memory: Vec<i64>) -> Self {
+            let (input, receive_input) = unbounded();
+            let (send_output, output) = unbounded();
+            Day09 {
+                intcode: Intcode::new(memory, receive_input, send_output),
+                input,
+                output,
+            }
+        }
+        fn push_input(&mut self, value: i64) {
+            self.input.send(value).unwrap();
+        }
+        // Intcode::run blocks on an empty channel, so this stops in front of
+        // an input instruction with nothing queued instead
+        fn run(&mut self) -> Status {
+            while self.intcode.ptr < self.intcode.memory.len() {
+                let op = decode_op(self.intcode.memory[self.intcode.ptr]).3;
+                if op == 3 && self.input.is_empty() {
+                    return Status::NeedsInput;
+                }
+                if self.intcode.run_instruction() == 99 {
+                    break;
+                }
+            }
+            Status::Halted
+        }
+        fn take_output(&mut self) -> Vec<i64> {
+            self.output.try_iter().collect()
+        }
+        fn memory(&self) -> &[i64] {
+            &self.intcode.memory
+        }
     }
 
     #[test]
-    fn test_first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 3507134798);
+    fn conformance() {
+        assert_conforms::<Day09>();
     }
 
     #[test]
-    fn test_second() {
-        let input = include_str!("input");
-        assert_eq!(solve_second(input), 84513);
+    fn test_single_intcode() {
+        let input = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+        let memory = read_codes(input).unwrap();
+        let output = single_intcode(memory.clone(), Vec::new());
+        assert_eq!(memory, output);
     }
 }
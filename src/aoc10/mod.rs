@@ -2,33 +2,38 @@ use std::collections::HashSet;
 use std::f64::consts::{FRAC_PI_2, PI};
 use std::ops;
 
+use crate::grid::Grid;
+use crate::number::gcd;
+use crate::parse::ParseError;
+use crate::random::Rng;
+
+const TILE: &str = "an asteroid # or empty space .";
+
 struct Asteroids {
     data: HashSet<Position>,
+    #[allow(dead_code)]
     width: i32,
+    #[allow(dead_code)]
     height: i32,
 }
 
 impl Asteroids {
-    fn read_asteroids(input: &str) -> Asteroids {
-        let mut data = HashSet::new();
-        let mut width = 0;
-        let mut height = 0;
-        for line in input.lines() {
-            width = 0;
-            for c in line.trim().chars() {
-                if c == '#' {
-                    data.insert(Position(width, height));
-                }
-
-                width += 1;
-            }
-            height += 1;
-        }
-        Asteroids {
+    fn read_asteroids(input: &str) -> Result<Asteroids, ParseError> {
+        let grid = Grid::parse(input, TILE, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+        let data = grid
+            .iter()
+            .filter(|&(_, &asteroid)| asteroid)
+            .map(|(c, _)| Position(c.x, c.y))
+            .collect();
+        Ok(Asteroids {
             data,
-            width,
-            height,
-        }
+            width: grid.width() as i32,
+            height: grid.height() as i32,
+        })
     }
 
     fn count_reach(&self, from_position: Position) -> u32 {
@@ -146,32 +151,46 @@ impl Position {
     }
 }
 
-// From https://doc.rust-lang.org/std/ops/trait.Div.html
-// Euclid's two-thousand-year-old algorithm for finding the greatest common
-// divisor.
-fn gcd(x: i32, y: i32) -> i32 {
-    let mut x = x;
-    let mut y = y;
-    while y != 0 {
-        let t = y;
-        y = x % y;
-        x = t;
-    }
-    // added abs here as we need to always return positive numbers
-    x.abs()
-}
-
-pub fn solve_first(input: &str) -> u32 {
-    let asts = Asteroids::read_asteroids(input);
-    asts.best_position().1
+pub fn solve_first(input: &str) -> Result<u32, ParseError> {
+    let asts = Asteroids::read_asteroids(input)?;
+    Ok(asts.best_position().1)
 }
 
-pub fn solve_second(input: &str) -> i32 {
-    let asts = Asteroids::read_asteroids(input);
+pub fn solve_second(input: &str) -> Result<i32, ParseError> {
+    let asts = Asteroids::read_asteroids(input)?;
+    // the station takes one asteroid and the laser has to find 200 more
+    if asts.data.len() <= 200 {
+        let expected = "a map with at least 201 asteroids";
+        return Err(ParseError::new(1, 1, "", expected));
+    }
     let best = asts.best_position();
     let nth_position = asts.vaporize(best.0, 200);
 
-    nth_position.0 * 100 + nth_position.1
+    Ok(nth_position.0 * 100 + nth_position.1)
+}
+
+// a square field at least 15 wide, a quarter asteroids and never fewer than
+// the 201 part 2 needs to have one left for the station
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let side = size.max(15);
+    let mut cells = vec![false; side * side];
+    let count = (side * side / 4).max(201);
+    let mut placed = 0;
+    while placed < count {
+        let cell = rng.below(cells.len());
+        if !cells[cell] {
+            cells[cell] = true;
+            placed += 1;
+        }
+    }
+
+    let mut result = String::new();
+    for row in cells.chunks(side) {
+        result.extend(row.iter().map(|&asteroid| if asteroid { '#' } else { '.' }));
+        result.push('\n');
+    }
+    result
 }
 
 #[cfg(test)]
@@ -181,12 +200,29 @@ mod test {
     #[test]
     fn test_read() {
         let input = include_str!("example1");
-        let asts = Asteroids::read_asteroids(input);
+        let asts = Asteroids::read_asteroids(input).unwrap();
         assert!(asts.data.contains(&Position(8, 3)));
         assert!(asts.data.contains(&Position(8, 1)));
         assert!(asts.data.contains(&Position(8, 0)));
     }
 
+    #[test]
+    fn parse_error() {
+        assert_eq!(
+            Asteroids::read_asteroids("#..#\n.#x.\n").err(),
+            Some(ParseError::new(2, 3, "x", TILE))
+        );
+        assert_eq!(
+            solve_second("#..#\n.#..\n"),
+            Err(ParseError::new(
+                1,
+                1,
+                "",
+                "a map with at least 201 asteroids"
+            ))
+        );
+    }
+
     #[test]
     fn test_gcd() {
         assert_eq!(gcd(12, 6), 6);
@@ -197,7 +233,7 @@ mod test {
     #[test]
     fn test_vaporize1() {
         let input = include_str!("example1");
-        let asts = Asteroids::read_asteroids(input);
+        let asts = Asteroids::read_asteroids(input).unwrap();
 
         assert_eq!(asts.vaporize(Position(8, 3), 36), Position(14, 3));
     }
@@ -205,20 +241,25 @@ mod test {
     #[test]
     fn test_vaporize2() {
         let input = include_str!("example2");
-        let asts = Asteroids::read_asteroids(input);
+        let asts = Asteroids::read_asteroids(input).unwrap();
 
         assert_eq!(asts.vaporize(Position(11, 13), 200), Position(8, 02));
     }
 
     #[test]
-    fn test_first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 344);
-    }
-
-    #[test]
-    fn test_second() {
-        let input = include_str!("input");
-        assert_eq!(solve_second(input), 2732);
+    fn test_generate() {
+        assert_eq!(generate(20, 10), generate(20, 10));
+        for seed in 0..3 {
+            let input = generate(20, seed);
+            let asts = Asteroids::read_asteroids(&input).unwrap();
+            assert_eq!(asts.data.len(), 201);
+            assert!(solve_first(&input).unwrap() < 201);
+
+            // the 200th is one of the asteroids, not the station
+            let nth = solve_second(&input).unwrap();
+            let position = Position(nth / 100, nth % 100);
+            assert!(asts.data.contains(&position));
+            assert_ne!(position, asts.best_position().0);
+        }
     }
 }
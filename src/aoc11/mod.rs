@@ -12,68 +12,23 @@
 */
 use std::collections::BTreeMap;
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
-struct Coordinate {
-    x: i32,
-    y: i32,
-}
-
-impl Coordinate {
-    fn new(x: i32, y: i32) -> Coordinate {
-        Coordinate { x, y }
-    }
+use crate::grid::{Bounds, Coordinate, Direction};
+use crate::ocr;
+use crate::parse::{self, ParseError};
+use crate::record::Recorder;
+use crate::render::{Cell, Picture, Rgb};
+use crate::Bitmap;
 
-    fn walk(&mut self, dir: Direction) {
-        match dir {
-            Direction::Left => {
-                self.x -= 1;
-            }
-            Direction::Right => {
-                self.x += 1;
-            }
-            Direction::Up => {
-                self.y -= 1;
-            }
-            Direction::Down => {
-                self.y += 1;
-            }
-        }
-    }
-}
-#[derive(Clone, Copy)]
-enum Direction {
-    Left,
-    Right,
-    Up,
-    Down,
-}
 #[derive(Clone, Copy)]
 enum Color {
     Black,
     White,
 }
 
-impl Direction {
-    fn rotate_left(&self) -> Direction {
-        match &self {
-            Direction::Left => Direction::Down,
-            Direction::Right => Direction::Up,
-            Direction::Up => Direction::Left,
-            Direction::Down => Direction::Right,
-        }
-    }
-    fn rotate_right(&self) -> Direction {
-        match &self {
-            Direction::Left => Direction::Up,
-            Direction::Right => Direction::Down,
-            Direction::Up => Direction::Right,
-            Direction::Down => Direction::Left,
-        }
-    }
-}
-
 struct PaintingRobot {
     board: BTreeMap<Coordinate, Color>,
+    // every panel painted, in order, so the painting can be replayed
+    paints: Vec<(Coordinate, Color)>,
     position: Coordinate,
     direction: Direction,
     paint_next: bool,
@@ -83,6 +38,7 @@ impl PaintingRobot {
     fn new() -> PaintingRobot {
         PaintingRobot {
             board: BTreeMap::new(),
+            paints: Vec::new(),
             position: Coordinate::new(0, 0),
             direction: Direction::Up,
             paint_next: true,
@@ -99,30 +55,26 @@ impl PaintingRobot {
 
     fn paint(&mut self, color: Color) {
         self.board.insert(self.position, color);
+        self.paints.push((self.position, color));
     }
 
     fn rotate_walk(&mut self, value: i64) {
         match value {
             0 => {
-                self.direction = self.direction.rotate_left();
-                self.position.walk(self.direction);
+                self.direction = self.direction.turn_left();
+                self.position = self.position.step(self.direction);
             }
             1 => {
-                self.direction = self.direction.rotate_right();
-                self.position.walk(self.direction);
+                self.direction = self.direction.turn_right();
+                self.position = self.position.step(self.direction);
             }
             _ => panic!("Unexpected walk value"),
         }
     }
 }
 
-fn read_codes(input: &str) -> Vec<i64> {
-    let mut result = Vec::new();
-
-    for number_str in input.trim().split(',') {
-        result.push(number_str.parse().unwrap());
-    }
-    result
+fn read_codes(input: &str) -> Result<Vec<i64>, ParseError> {
+    parse::integers(input)
 }
 
 // returns (A, B, C, DE)
@@ -328,59 +280,81 @@ impl Bus for PaintingRobot {
     }
 }
 
-pub fn solve_first(input: &str) -> usize {
-    let memory = read_codes(input);
+pub fn solve_first(input: &str) -> Result<usize, ParseError> {
+    let memory = read_codes(input)?;
     let robot = PaintingRobot::new();
     let mut intcode = Intcode::new(memory, robot);
     intcode.run();
-    //to_image(&intcode.bus.board, "first.png");
-    intcode.bus.board.len()
+    Ok(intcode.bus.board.len())
 }
 
-// doesn't output anything, but creates an image with the result
-pub fn solve_second(input: &str) {
-    let memory = read_codes(input);
+// hull painted starting from a white panel
+fn paint_registration(input: &str) -> Result<PaintingRobot, ParseError> {
+    let memory = read_codes(input)?;
     let mut robot = PaintingRobot::new();
     robot.paint(Color::White);
     let mut intcode = Intcode::new(memory, robot);
     intcode.run();
-
-    to_image(&intcode.bus.board, "second.png");
+    Ok(intcode.bus)
 }
 
-fn to_image(tree: &BTreeMap<Coordinate, Color>, name: &str) {
-    let mut top_left = Coordinate::new(0, 0);
-    let mut bottom_right = Coordinate::new(0, 0);
+// the registration identifier, white panels are lit
+pub fn registration(input: &str) -> Result<Bitmap, ParseError> {
+    Ok(to_bitmap(&paint_registration(input)?.board))
+}
 
-    for coord in tree.keys() {
-        top_left.x = i32::min(top_left.x, coord.x);
-        top_left.y = i32::min(top_left.y, coord.y);
+pub fn solve_second(input: &str) -> Result<String, ParseError> {
+    ocr::read(&registration(input)?)
+}
 
-        bottom_right.x = i32::max(bottom_right.x, coord.x);
-        bottom_right.y = i32::max(bottom_right.y, coord.y);
+fn panel(color: Option<&Color>) -> Cell {
+    match color {
+        Some(Color::White) => Cell::new('#', Rgb::WHITE),
+        Some(Color::Black) => Cell::new('.', Rgb::BLACK),
+        None => Cell::new(' ', Rgb::BLACK),
     }
+}
 
-    let width = (bottom_right.x - top_left.x + 1) as u32;
-    let height = (bottom_right.y - top_left.y + 1) as u32;
+// the hull after painting the registration, white panels are lit
+pub fn render(input: &str) -> Result<Picture, ParseError> {
+    let board = paint_registration(input)?.board;
+    Ok(Picture::from_sparse(&board, panel))
+}
 
-    let mut img_buff = image::ImageBuffer::new(width, height);
+// replays the registration painting one panel at a time,
+// the robot is drawn over the panel it just painted
+pub fn record(input: &str, recorder: &mut Recorder) -> Result<(), ParseError> {
+    let robot = paint_registration(input)?;
+    let bounds = Bounds::of(robot.board.keys().copied()).unwrap();
+
+    let mut board = BTreeMap::new();
+    for &(position, color) in robot.paints.iter() {
+        board.insert(position, color);
+        recorder.offer(|| {
+            let mut picture = Picture::from_sparse_in(&board, bounds, panel);
+            let x = (position.x - bounds.min.x) as usize;
+            let y = (position.y - bounds.min.y) as usize;
+            picture.set(x, y, Cell::new('@', Rgb::RED));
+            picture
+        });
+        if recorder.is_full() {
+            break;
+        }
+    }
+    Ok(())
+}
 
-    let black = image::Rgb([0, 0, 0]);
-    let white = image::Rgb([255, 255, 255]);
+fn to_bitmap(tree: &BTreeMap<Coordinate, Color>) -> Bitmap {
+    let bounds = Bounds::of(tree.keys().copied())
+        .unwrap_or_else(|| Bounds::new(Coordinate::new(0, 0), Coordinate::new(0, 0)));
 
+    let mut bitmap = Bitmap::new(bounds.width(), bounds.height());
     for (coord, color) in tree {
-        let pixel = match color {
-            Color::Black => black,
-            Color::White => white,
-        };
-
-        let x = (coord.x - top_left.x) as u32;
-        let y = (coord.y - top_left.y) as u32;
-
-        img_buff.put_pixel(x, y, pixel);
+        let x = (coord.x - bounds.min.x) as usize;
+        let y = (coord.y - bounds.min.y) as usize;
+        bitmap.set(x, y, matches!(color, Color::White));
     }
-
-    img_buff.save(name).unwrap();
+    bitmap
 }
 
 #[cfg(test)]
@@ -424,10 +398,4 @@ mod test {
         robot.paint(Color::White);
         assert_eq!(robot.board.len(), 4);
     }
-
-    #[test]
-    fn test_first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 2478);
-    }
 }
@@ -1,7 +1,18 @@
 use regex::Regex;
 use std::cmp::Ordering;
 
-#[derive(Debug, Clone, Copy)]
+use crate::number;
+use crate::params::{params, Checked};
+use crate::parse::ParseError;
+
+params! {
+    pub struct Params {
+        // part 1 only, part 2 runs until the moons repeat
+        steps: usize = 1000,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Vec3 {
     x: i32,
     y: i32,
@@ -13,7 +24,7 @@ impl Vec3 {
         Vec3 { x, y, z }
     }
 }
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Moon {
     position: Vec3,
     velocity: Vec3,
@@ -51,19 +62,35 @@ impl Moon {
     }
 }
 
-fn read_moons(input: &str) -> Vec<Moon> {
+fn read_moons(input: &str) -> Result<Vec<Moon>, ParseError> {
     let mut result = Vec::with_capacity(4);
     // <x=(number), y=(number), z=(number)>
-    let moon_regex = Regex::new(r"<x=(-?\d+), y=(-?\d+), z=(-?\d+)>").unwrap();
-
-    for cap in moon_regex.captures_iter(input) {
-        let x = cap[1].parse().unwrap();
-        let y = cap[2].parse().unwrap();
-        let z = cap[3].parse().unwrap();
+    let moon_regex = Regex::new(r"^<x=(-?\d+), y=(-?\d+), z=(-?\d+)>$").unwrap();
 
-        result.push(Moon::new(Vec3::new(x, y, z), Vec3::new(0, 0, 0)));
+    for (idx, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let position = moon_regex.captures(line).and_then(|cap| {
+            let x = cap[1].parse().ok()?;
+            let y = cap[2].parse().ok()?;
+            let z = cap[3].parse().ok()?;
+            Some(Vec3::new(x, y, z))
+        });
+        match position {
+            Some(position) => result.push(Moon::new(position, Vec3::new(0, 0, 0))),
+            None => {
+                return Err(ParseError::within(
+                    idx,
+                    line,
+                    line,
+                    "a position as <x=X, y=Y, z=Z>",
+                ))
+            }
+        }
     }
-    result
+    Ok(result)
 }
 
 fn step(moons: &mut Vec<Moon>) {
@@ -91,10 +118,16 @@ fn steps(moons: &mut Vec<Moon>, n: usize) {
     }
 }
 
-pub fn solve_first(input: &str) -> i32 {
-    let mut moons = read_moons(input);
-    steps(&mut moons, 1000);
-    count_energy(&moons)
+pub fn solve_first_with(input: &str, params: &Params) -> Checked<i32> {
+    params.validate()?;
+    Ok(read_moons(input).map(|mut moons| {
+        steps(&mut moons, params.steps);
+        count_energy(&moons)
+    }))
+}
+
+pub fn solve_first(input: &str) -> Result<i32, ParseError> {
+    solve_first_with(input, &Params::default()).expect("the defaults are valid")
 }
 
 fn velocity_diff(positions: &[i32]) -> Vec<i32> {
@@ -108,7 +141,11 @@ fn velocity_diff(positions: &[i32]) -> Vec<i32> {
     result
 }
 
+// The steps back to the start are the same backwards as forwards, so the
+// axis is back twice as long after its velocities are first all zero,
+// unless it's back already.
 fn find_steps_axis(mut positions: Vec<i32>) -> u64 {
+    let start = positions.clone();
     let mut velocities = vec![0; positions.len()];
     let velocities_end = velocities.clone();
 
@@ -128,47 +165,48 @@ fn find_steps_axis(mut positions: Vec<i32>) -> u64 {
             break;
         }
     }
-    steps * 2
-}
-
-// From https://doc.rust-lang.org/std/ops/trait.Div.html
-// Euclid's two-thousand-year-old algorithm for finding the greatest common
-// divisor.
-fn gcd(x: u64, y: u64) -> u64 {
-    let mut x = x;
-    let mut y = y;
-    while y != 0 {
-        let t = y;
-        y = x % y;
-        x = t;
+    if positions == start {
+        steps
+    } else {
+        steps * 2
     }
-    x
-}
-
-fn lcm(a: u64, b: u64) -> u64 {
-    a * b / gcd(a, b)
-}
-
-fn lcm3(a: u64, b: u64, c: u64) -> u64 {
-    lcm(a, lcm(b, c))
 }
 
-pub fn solve_second(input: &str) -> u64 {
-    let moons = read_moons(input);
+// steps until the moons are back where they started, the axes move
+// independently so it's the lcm of when each one repeats
+fn period(moons: &[Moon]) -> u64 {
     let x = find_steps_axis(moons.iter().map(|m| m.position.x).collect());
     let y = find_steps_axis(moons.iter().map(|m| m.position.y).collect());
     let z = find_steps_axis(moons.iter().map(|m| m.position.z).collect());
-    lcm3(x, y, z)
+    number::lcm_all(&[x, y, z])
+}
+
+pub fn solve_second(input: &str) -> Result<u64, ParseError> {
+    let moons = read_moons(input)?;
+    Ok(period(&moons))
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::random::Rng;
+
+    // every moon stepped together until they're all back, None past `limit`
+    fn naive_period(moons: &[Moon], limit: u64) -> Option<u64> {
+        let mut current = moons.to_vec();
+        for n in 1..=limit {
+            step(&mut current);
+            if current == moons {
+                return Some(n);
+            }
+        }
+        None
+    }
 
     #[test]
     fn test_example1() {
         let input = include_str!("example1");
-        let mut moons = read_moons(input);
+        let mut moons = read_moons(input).unwrap();
         steps(&mut moons, 10);
         assert_eq!(count_energy(&moons), 179);
     }
@@ -176,20 +214,40 @@ mod test {
     #[test]
     fn test_example2() {
         let input = include_str!("example2");
-        let mut moons = read_moons(input);
+        let mut moons = read_moons(input).unwrap();
         steps(&mut moons, 100);
         assert_eq!(count_energy(&moons), 1940);
     }
 
     #[test]
-    fn test_first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 6849);
+    fn test_period() {
+        let limit = 100_000;
+        let mut rng = Rng::new(12);
+        let mut checked = 0;
+        for _ in 0..300 {
+            let moons: Vec<Moon> = (0..rng.range(1..=4))
+                .map(|_| {
+                    let mut coordinate = || rng.range(-2..=2) as i32;
+                    let position = Vec3::new(coordinate(), coordinate(), coordinate());
+                    Moon::new(position, Vec3::new(0, 0, 0))
+                })
+                .collect();
+            let expected = period(&moons);
+            if expected <= limit {
+                assert_eq!(naive_period(&moons, limit), Some(expected), "{:?}", moons);
+                checked += 1;
+            } else {
+                assert_eq!(naive_period(&moons, limit), None, "{:?}", moons);
+            }
+        }
+        assert!(checked > 100);
     }
 
     #[test]
-    fn test_second() {
-        let input = include_str!("input");
-        assert_eq!(solve_second(input), 356658899375688);
+    fn test_parse_error() {
+        assert_eq!(
+            read_moons("<x=-1, y=0, z=2>\n<x=2, y=-10>\n").unwrap_err(),
+            ParseError::new(2, 1, "<x=2, y=-10>", "a position as <x=X, y=Y, z=Z>")
+        );
     }
 }
@@ -1,11 +1,17 @@
 use std::collections::BTreeMap;
 
+use crate::grid::{Bounds, Coordinate};
+use crate::live::Session;
+use crate::parse::{self, ParseError};
+use crate::record::Recorder;
+use crate::render::{Cell, Picture, Rgb};
+
 trait Bus {
     fn input(&self) -> i64;
     fn output(&mut self, v: i64);
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone, Copy)]
 enum Tile {
     Empty,
     Wall,
@@ -28,7 +34,9 @@ impl Tile {
 }
 
 struct ArcadeCabinet {
-    tiles: BTreeMap<(i32, i32), Tile>,
+    tiles: BTreeMap<Coordinate, Tile>,
+    // every tile drawn, in order, so the game can be replayed
+    updates: Vec<(Coordinate, Tile)>,
     output: Vec<i64>,
     score: i64,
 }
@@ -37,13 +45,15 @@ impl ArcadeCabinet {
     fn new() -> Self {
         ArcadeCabinet {
             tiles: BTreeMap::new(),
+            updates: Vec::new(),
             output: Vec::new(),
             score: 0,
         }
     }
 
     fn add_tile(&mut self, x: i32, y: i32, tile: Tile) {
-        self.tiles.insert((x, y), tile);
+        self.tiles.insert(Coordinate::new(x, y), tile);
+        self.updates.push((Coordinate::new(x, y), tile));
     }
 }
 
@@ -53,7 +63,7 @@ impl Bus for ArcadeCabinet {
         let paddle = self
             .tiles
             .iter()
-            .find_map(|(k, t)| if *t == Tile::Paddle { Some(k.0) } else { None });
+            .find_map(|(k, t)| if *t == Tile::Paddle { Some(k.x) } else { None });
 
         let paddle_pos = paddle.expect("Could not find paddle in tile list.");
 
@@ -61,7 +71,7 @@ impl Bus for ArcadeCabinet {
         let ball = self
             .tiles
             .iter()
-            .find_map(|(k, t)| if *t == Tile::Ball { Some(k.0) } else { None });
+            .find_map(|(k, t)| if *t == Tile::Ball { Some(k.x) } else { None });
 
         let ball_pos = ball.expect("Could not find ball in tile list.");
 
@@ -86,13 +96,8 @@ impl Bus for ArcadeCabinet {
     }
 }
 
-fn read_codes(input: &str) -> Vec<i64> {
-    let mut result = Vec::new();
-
-    for number_str in input.trim().split(',') {
-        result.push(number_str.parse().unwrap());
-    }
-    result
+fn read_codes(input: &str) -> Result<Vec<i64>, ParseError> {
+    parse::integers(input)
 }
 
 // returns (A, B, C, DE)
@@ -271,41 +276,110 @@ where
     }
 }
 
-pub fn solve_first(input: &str) -> usize {
-    let memory = read_codes(input);
+// the screen once the game has drawn itself, without any quarters in
+fn screen(input: &str) -> Result<BTreeMap<Coordinate, Tile>, ParseError> {
+    let memory = read_codes(input)?;
     let arcade = ArcadeCabinet::new();
     let mut intcode = Intcode::new(memory, arcade);
     intcode.run();
-    intcode
-        .bus
-        .tiles
+    Ok(intcode.bus.tiles)
+}
+
+pub fn solve_first(input: &str) -> Result<usize, ParseError> {
+    Ok(screen(input)?
         .values()
         .filter(|t| **t == Tile::Block)
-        .count()
+        .count())
+}
+
+fn cell(tile: Option<&Tile>) -> Cell {
+    match tile {
+        Some(Tile::Wall) => Cell::new('#', Rgb::GREY),
+        Some(Tile::Block) => Cell::new('=', Rgb::BLUE),
+        Some(Tile::Paddle) => Cell::new('-', Rgb::WHITE),
+        Some(Tile::Ball) => Cell::new('o', Rgb::YELLOW),
+        Some(Tile::Empty) | None => Cell::new(' ', Rgb::BLACK),
+    }
+}
+
+pub fn render(input: &str) -> Result<Picture, ParseError> {
+    Ok(Picture::from_sparse(&screen(input)?, cell))
 }
 
-pub fn solve_second(input: &str) -> i64 {
-    let mut memory = read_codes(input);
+fn play(input: &str) -> Result<ArcadeCabinet, ParseError> {
+    let mut memory = read_codes(input)?;
     memory[0] = 2;
     let arcade = ArcadeCabinet::new();
     let mut intcode = Intcode::new(memory, arcade);
     intcode.run();
-    intcode.bus.score
+    Ok(intcode.bus)
+}
+
+// replays the game with quarters in, one frame each time the ball moves
+pub fn record(input: &str, recorder: &mut Recorder) -> Result<(), ParseError> {
+    let arcade = play(input)?;
+    let bounds = Bounds::of(arcade.tiles.keys().copied()).unwrap();
+
+    let mut screen = BTreeMap::new();
+    for &(coord, tile) in arcade.updates.iter() {
+        screen.insert(coord, tile);
+        if tile == Tile::Ball {
+            recorder.offer(|| Picture::from_sparse_in(&screen, bounds, cell));
+            if recorder.is_full() {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+// the game with quarters in, one step each time the ball moves
+struct Game {
+    intcode: Intcode<ArcadeCabinet>,
+    halted: bool,
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+impl Session for Game {
+    fn step(&mut self) -> bool {
+        if self.halted {
+            return false;
+        }
+        let drawn = self.intcode.bus.updates.len();
+        while self.intcode.ptr < self.intcode.memory.len() {
+            match self.intcode.run_instruction() {
+                99 => break,
+                4 if self.intcode.bus.updates.len() > drawn => {
+                    if let Some(&(_, Tile::Ball)) = self.intcode.bus.updates.last() {
+                        return true;
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.halted = true;
+        true
+    }
 
-    #[test]
-    fn test_first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 312);
+    fn picture(&self) -> Picture {
+        Picture::from_sparse(&self.intcode.bus.tiles, cell)
     }
 
-    #[test]
-    fn test_second() {
-        let input = include_str!("input");
-        assert_eq!(solve_second(input), 15909);
+    fn status(&self) -> String {
+        let arcade = &self.intcode.bus;
+        let blocks = arcade.tiles.values().filter(|t| **t == Tile::Block).count();
+        format!("score {}, {} blocks left", arcade.score, blocks)
     }
 }
+
+pub fn live(input: &str) -> Result<Box<dyn Session>, ParseError> {
+    let mut memory = read_codes(input)?;
+    memory[0] = 2;
+    Ok(Box::new(Game {
+        intcode: Intcode::new(memory, ArcadeCabinet::new()),
+        halted: false,
+    }))
+}
+
+pub fn solve_second(input: &str) -> Result<i64, ParseError> {
+    Ok(play(input)?.score)
+}
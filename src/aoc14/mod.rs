@@ -1,5 +1,18 @@
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use crate::params::{params, Checked};
+use crate::parse::ParseError;
+use crate::random::Rng;
+
+const INGREDIENT: &str = "a quantity and a chemical, e.g. 7 ORE";
+
+params! {
+    pub struct Params {
+        // the ore in the cargo hold for part 2
+        ore: u64 = 1_000_000_000_000,
+    }
+}
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 struct Ingredient {
@@ -8,11 +21,19 @@ struct Ingredient {
 }
 
 impl Ingredient {
-    fn parse(quantity: &str, name: &str) -> Ingredient {
-        Ingredient {
-            name: name.into(),
-            quantity: quantity.parse().unwrap(),
-        }
+    // <quantity> <name>, line and line_idx only locate errors
+    fn parse(line_idx: usize, line: &str, text: &str) -> Result<Ingredient, ParseError> {
+        let ingredient_regex = Regex::new(r"^(\d+) (\w+)$").unwrap();
+        let text = text.trim();
+        ingredient_regex
+            .captures(text)
+            .and_then(|cap| {
+                Some(Ingredient {
+                    name: cap[2].into(),
+                    quantity: cap[1].parse().ok()?,
+                })
+            })
+            .ok_or_else(|| ParseError::within(line_idx, line, text, INGREDIENT))
     }
 
     fn new(quantity: u64, name: String) -> Ingredient {
@@ -35,31 +56,50 @@ impl Reaction {
     }
 }
 
-fn read_reactions(input: &str) -> HashMap<String, Reaction> {
+// every chemical that's needed has to be made by some reaction, and so does FUEL
+fn read_reactions(input: &str) -> Result<HashMap<String, Reaction>, ParseError> {
     let mut reactions = HashMap::new();
-    // split left => right
-    let line_regex = Regex::new(r"(?m)^(.+?)=> (.+?)$").unwrap();
-    // parse <quantity> <ingredient>
-    let ingredient_regex = Regex::new(r"(\d+) (\w+)").unwrap();
+    // (line index, line, ingredient) for every chemical a reaction needs
+    let mut needed = Vec::new();
+
+    for (idx, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        // split left => right
+        let (left, right) = match line.split("=>").collect::<Vec<_>>()[..] {
+            [left, right] => (left, right),
+            _ => {
+                return Err(ParseError::within(
+                    idx,
+                    line,
+                    line.trim(),
+                    "ingredients => product",
+                ))
+            }
+        };
 
-    for line in line_regex.captures_iter(input) {
         let mut requires = Vec::new();
-        let left = &line[1];
-        let right = &line[2];
-
-        for ingredient_capture in ingredient_regex.captures_iter(left) {
-            requires.push(Ingredient::parse(
-                &ingredient_capture[1],
-                &ingredient_capture[2],
-            ));
+        for ingredient in left.split(',') {
+            requires.push(Ingredient::parse(idx, line, ingredient)?);
+            needed.push((idx, line, ingredient.trim()));
         }
-        let produces_capture = ingredient_regex.captures(right).unwrap();
-        let produces = Ingredient::parse(&produces_capture[1], &produces_capture[2]);
+        let produces = Ingredient::parse(idx, line, right)?;
 
         reactions.insert(produces.name.clone(), Reaction::new(produces, requires));
     }
 
-    reactions
+    for (idx, line, ingredient) in needed {
+        let name = ingredient.rsplit(' ').next().unwrap_or(ingredient);
+        if name != "ORE" && !reactions.contains_key(name) {
+            let expected = "ORE or a chemical that a reaction makes";
+            return Err(ParseError::within(idx, line, ingredient, expected));
+        }
+    }
+    if !reactions.contains_key("FUEL") {
+        return Err(ParseError::new(1, 1, "", "a reaction that makes FUEL"));
+    }
+    Ok(reactions)
 }
 
 // When attempting to use leftovers, there are three possible scenarios:
@@ -122,17 +162,22 @@ fn fuel_cost(reactions: &HashMap<String, Reaction>, fuel_ammount: u64) -> u64 {
     ore
 }
 
-pub fn solve_first(input: &str) -> u64 {
-    let reactions = read_reactions(input);
+pub fn solve_first(input: &str) -> Result<u64, ParseError> {
+    let reactions = read_reactions(input)?;
 
-    fuel_cost(&reactions, 1)
+    Ok(fuel_cost(&reactions, 1))
 }
 
 // perform a binary search that finds the highest ammount of fuel that can be generated from target ore
 fn max_fuel(reactions: &HashMap<String, Reaction>, target: u64) -> u64 {
     let cost_one = fuel_cost(&reactions, 1);
     let mut fuel_left = target / cost_one;
-    let mut fuel_right = fuel_left * 2;
+    let mut fuel_right = (fuel_left * 2).max(1);
+    // leftovers can make fuel in bulk much cheaper than one at a time
+    while fuel_cost(reactions, fuel_right) < target {
+        fuel_left = fuel_right;
+        fuel_right *= 2;
+    }
     while fuel_right - fuel_left > 1 {
         let fuel = (fuel_left + fuel_right) / 2;
         let cost = fuel_cost(&reactions, fuel);
@@ -148,40 +193,141 @@ fn max_fuel(reactions: &HashMap<String, Reaction>, target: u64) -> u64 {
     fuel_left
 }
 
-pub fn solve_second(input: &str) -> u64 {
-    let reactions = read_reactions(input);
-    max_fuel(&reactions, 1_000_000_000_000)
+pub fn solve_second_with(input: &str, params: &Params) -> Checked<u64> {
+    params.validate()?;
+    Ok(read_reactions(input).map(|reactions| max_fuel(&reactions, params.ore)))
+}
+
+pub fn solve_second(input: &str) -> Result<u64, ParseError> {
+    solve_second_with(input, &Params::default()).expect("the defaults are valid")
+}
+// `size` chemicals besides ORE and FUEL, each made from up to three earlier
+// chemicals or ORE, and all of them needed for FUEL. A reaction never needs
+// more of its chemical inputs in total than it makes, so the amounts stay
+// small enough for part 2 not to overflow however long the chains get.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let letters: Vec<char> = ('A'..='Z').collect();
+    let mut taken: HashSet<String> = ["ORE", "FUEL"].iter().map(|&n| n.to_owned()).collect();
+    let mut names: Vec<String> = Vec::new();
+    // made but not needed by anything yet
+    let mut unused: Vec<usize> = Vec::new();
+    let mut lines = Vec::new();
+
+    while names.len() < size {
+        let length = rng.range(1..=4) as usize;
+        let name: String = (0..length).map(|_| *rng.pick(&letters)).collect();
+        if !taken.insert(name.clone()) {
+            continue;
+        }
+
+        // indices into names, None is ORE
+        let mut inputs: Vec<Option<usize>> = Vec::new();
+        if !unused.is_empty() {
+            inputs.push(Some(unused.swap_remove(rng.below(unused.len()))));
+        }
+        let count = (rng.range(1..=3) as usize).min(names.len() + 1);
+        while inputs.len() < count {
+            let pick = rng.below(names.len() + 1);
+            let input = if pick == names.len() {
+                None
+            } else {
+                Some(pick)
+            };
+            if !inputs.contains(&input) {
+                inputs.push(input);
+                unused.retain(|&u| Some(u) != input);
+            }
+        }
+
+        let chemicals = inputs.iter().filter(|input| input.is_some()).count() as i64;
+        let produces = rng.range(1..=9) * chemicals.max(1);
+        let mut ingredients = Vec::new();
+        for input in inputs {
+            ingredients.push(match input {
+                None => format!("{} ORE", rng.range(1..=200)),
+                Some(i) => format!("{} {}", rng.range(1..=produces / chemicals), names[i]),
+            });
+        }
+        lines.push(format!(
+            "{} => {} {}",
+            ingredients.join(", "),
+            produces,
+            name
+        ));
+        unused.push(names.len());
+        names.push(name);
+    }
+
+    let mut ingredients: Vec<String> = unused
+        .iter()
+        .map(|&i| format!("{} {}", rng.range(1..=9), names[i]))
+        .collect();
+    if ingredients.is_empty() {
+        ingredients.push(format!("{} ORE", rng.range(1..=200)));
+    }
+    lines.push(format!("{} => 1 FUEL", ingredients.join(", ")));
+
+    for i in (1..lines.len()).rev() {
+        lines.swap(i, rng.below(i + 1));
+    }
+    lines.join("\n") + "\n"
 }
+
 #[cfg(test)]
 mod test {
     use super::*;
     #[test]
     fn test_example3_2() {
         let input = include_str!("example3");
-        assert_eq!(solve_second(input), 82892753);
+        assert_eq!(solve_second(input), Ok(82892753));
     }
 
     #[test]
     fn test_example4_2() {
         let input = include_str!("example4");
-        assert_eq!(solve_second(input), 5586022);
+        assert_eq!(solve_second(input), Ok(5586022));
     }
 
     #[test]
     fn test_example5_2() {
         let input = include_str!("example5");
-        assert_eq!(solve_second(input), 460664);
+        assert_eq!(solve_second(input), Ok(460664));
     }
 
     #[test]
-    fn test_first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 278404);
+    fn test_generate() {
+        assert_eq!(generate(10, 14), generate(10, 14));
+        let target = 1_000_000_000_000;
+        for seed in 0..5 {
+            let input = generate(40, seed);
+            let reactions = read_reactions(&input).unwrap();
+            assert_eq!(reactions.len(), 41);
+
+            let fuel = solve_second(&input).unwrap();
+            assert!(fuel >= target / solve_first(&input).unwrap());
+            assert!(fuel_cost(&reactions, fuel) < target);
+            assert!(fuel_cost(&reactions, fuel + 1) >= target);
+        }
     }
 
     #[test]
-    fn test_second() {
-        let input = include_str!("input");
-        assert_eq!(solve_second(input), 4436981);
+    fn test_parse_error() {
+        assert_eq!(
+            solve_first("7 A, 1 E => 1 FUEL\n10 ORE => ten A\n").unwrap_err(),
+            ParseError::new(2, 11, "ten A", INGREDIENT)
+        );
+        assert_eq!(
+            solve_first("10 ORE, 1 B\n").unwrap_err(),
+            ParseError::new(1, 1, "10 ORE, 1 B", "ingredients => product")
+        );
+        assert_eq!(
+            solve_first("10 ORE => 1 A\n7 A, 1 E => 1 FUEL\n").unwrap_err(),
+            ParseError::new(2, 6, "1 E", "ORE or a chemical that a reaction makes")
+        );
+        assert_eq!(
+            solve_second("10 ORE => 1 A\n").unwrap_err(),
+            ParseError::new(1, 1, "", "a reaction that makes FUEL")
+        );
     }
 }
@@ -10,24 +10,22 @@
     1. Explore
     2. BFS max depth starting at goal
 */
-use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
-
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum Direction {
-    North,
-    South,
-    West,
-    East,
-}
-
-impl Direction {
-    fn to_intcode(&self) -> i64 {
-        match self {
-            Direction::North => 1,
-            Direction::South => 2,
-            Direction::West => 3,
-            Direction::East => 4,
-        }
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use crate::grid::{Bounds, Coordinate, Direction};
+use crate::live::Session;
+use crate::parse::{self, ParseError};
+use crate::record::Recorder;
+use crate::render::{Cell, Picture, Rgb};
+use crate::search::{self, Paths};
+
+// movement command the droid's program reads
+fn movement_command(direction: Direction) -> i64 {
+    match direction {
+        Direction::Up => 1,
+        Direction::Down => 2,
+        Direction::Left => 3,
+        Direction::Right => 4,
     }
 }
 
@@ -49,46 +47,11 @@ impl Tile {
     }
 }
 
-#[derive(PartialOrd, Ord, PartialEq, Eq, Hash, Debug, Clone, Copy)]
-struct Coordinate {
-    x: i32,
-    y: i32,
-}
-
-impl Coordinate {
-    fn new(x: i32, y: i32) -> Self {
-        Coordinate { x, y }
-    }
-    fn movement(&self, m: Direction) -> Coordinate {
-        let mut result = self.clone();
-        match m {
-            Direction::North => result.y -= 1,
-            Direction::South => result.y += 1,
-            Direction::West => result.x -= 1,
-            Direction::East => result.x += 1,
-        };
-        result
-    }
-
-    fn calculate_direction(&self, to: Coordinate) -> Direction {
-        if self.x < to.x {
-            Direction::East
-        } else if self.x > to.x {
-            Direction::West
-        } else if self.y < to.y {
-            Direction::South
-        } else if self.y > to.y {
-            Direction::North
-        } else {
-            panic!("Coordinate::calculate_direction() called between two same coordinates.");
-        }
-    }
-}
-
 struct RepairDroid {
     direction: Direction,                    // direction robot is trying to move
     position: Coordinate,                    // current position
     known_tiles: BTreeMap<Coordinate, Tile>, // tiles which robot has knowledge about
+    discovered: Vec<(Coordinate, Tile)>,     // every move result in order, for replaying
     planned_path: VecDeque<Coordinate>,
     finished_exploring: bool,
 }
@@ -105,9 +68,10 @@ impl RepairDroid {
         planned_path.push_front(next_pos);
 
         RepairDroid {
-            direction: Direction::North,
+            direction: Direction::Up,
             position: starting_pos,
             known_tiles: tiles,
+            discovered: Vec::new(),
             planned_path,
             finished_exploring: false,
         }
@@ -124,11 +88,12 @@ impl RepairDroid {
     }
 
     fn move_result(&mut self, tile: Tile) {
-        let pos = self.position.movement(self.direction);
+        let pos = self.position.step(self.direction);
         if tile != Tile::Wall {
             self.position = pos;
         }
         self.known_tiles.insert(pos, tile);
+        self.discovered.push((pos, tile));
 
         // plan new path
         if self.planned_path.is_empty() {
@@ -148,9 +113,11 @@ impl RepairDroid {
     }
 
     fn next_direction(&mut self) -> Direction {
+        let next = self.planned_path.pop_front().unwrap();
         self.direction = self
             .position
-            .calculate_direction(self.planned_path.pop_front().unwrap());
+            .direction_to(next)
+            .expect("planned path steps to an adjacent tile");
         self.direction
     }
 
@@ -164,6 +131,7 @@ impl RepairDroid {
 }
 
 // breadth-first search
+// doesn't include start position in path
 fn bfs<F>(
     known_tiles: &BTreeMap<Coordinate, Tile>,
     start: Coordinate,
@@ -172,124 +140,207 @@ fn bfs<F>(
 where
     F: Fn(Coordinate, Option<&Tile>) -> bool,
 {
-    let directions = vec![
-        Direction::North,
-        Direction::East,
-        Direction::South,
-        Direction::West,
-    ];
-    let mut positions = VecDeque::new();
-    positions.push_back(start);
-    // keep track of visited tiles
-    let mut visited = HashSet::new();
-    visited.insert(start);
-
-    let mut came_from = HashMap::new();
-
-    while let Some(current) = positions.pop_front() {
-        let current_tile = known_tiles.get(&current);
-        // if current matches objective, path to it
-        if objective(current, current_tile) {
-            return Some(reconstruct_path(came_from, current));
-        }
-
-        // if position is not a wall, generate its children
-        if current_tile != Some(&Tile::Wall) {
-            for &dir in directions.iter() {
-                let neighbor = current.movement(dir);
-                if visited.contains(&neighbor) {
-                    continue;
-                }
-                visited.insert(neighbor);
-                positions.push_back(neighbor);
-                came_from.insert(neighbor, current);
+    let found = search::bfs(
+        start,
+        Paths::Track,
+        |&current| {
+            // if position is not a wall, generate its children
+            if known_tiles.get(&current) == Some(&Tile::Wall) {
+                Vec::new()
+            } else {
+                current.neighbours().to_vec()
             }
-        }
-    }
-    None
+        },
+        |&current| objective(current, known_tiles.get(&current)),
+    );
+    found.path().map(|path| path.into_iter().skip(1).collect())
 }
 
-// doesn't include start position in path
-fn reconstruct_path(
-    came_from: HashMap<Coordinate, Coordinate>,
-    mut position: Coordinate,
-) -> VecDeque<Coordinate> {
-    let mut total_path = VecDeque::new();
-    while let Some(&current) = came_from.get(&position) {
-        total_path.push_front(position);
-        position = current;
+// moves the droid once, false if there was nothing left to explore
+fn explore_step(intcode: &mut Intcode, robot: &mut RepairDroid) -> bool {
+    if intcode.finished || robot.finished_exploring {
+        return false;
     }
-    total_path
+    intcode
+        .input
+        .push_back(movement_command(robot.next_direction()));
+    intcode.run_until_output();
+    robot.move_result(Tile::parse(intcode.output.pop_front().unwrap()));
+    true
 }
 
 fn explore(intcode: &mut Intcode, robot: &mut RepairDroid) {
-    while !intcode.finished && !robot.finished_exploring {
-        intcode.input.push_back(robot.next_direction().to_intcode());
-        intcode.run_until_output();
-        robot.move_result(Tile::parse(intcode.output.pop_front().unwrap()));
-    }
+    while explore_step(intcode, robot) {}
 }
 
-pub fn solve_first(input: &str) -> usize {
-    let memory = read_codes(input);
+// a droid that has mapped the whole area
+fn explored(input: &str) -> Result<RepairDroid, ParseError> {
+    let memory = read_codes(input)?;
     let mut intcode = Intcode::new(memory);
     let mut robot = RepairDroid::new();
     explore(&mut intcode, &mut robot);
-    robot.path_to_goal().unwrap().len()
+    Ok(robot)
 }
 
-// should not be called with undiscovered tiles as it can only walk empty tiles
-// finds max depth of bfs search starting from a position
-fn bfs_depth(tiles: &BTreeMap<Coordinate, Tile>, start: Coordinate) -> usize {
-    let mut visited = HashSet::new();
-    visited.insert(start);
-    let mut current_depth = VecDeque::new();
-    current_depth.push_back(start);
-    let mut next_depth = VecDeque::new();
-
-    let mut depth = 0;
-
-    let directions = vec![
-        Direction::North,
-        Direction::East,
-        Direction::South,
-        Direction::West,
-    ];
-
-    while !current_depth.is_empty() {
-        while let Some(current) = current_depth.pop_front() {
-            for &dir in directions.iter() {
-                let neighbor = current.movement(dir);
-                if !visited.contains(&neighbor) {
-                    visited.insert(neighbor);
-                    if tiles.get(&neighbor) == Some(&Tile::Empty) {
-                        next_depth.push_back(neighbor);
-                    }
-                }
-            }
+pub fn solve_first(input: &str) -> Result<usize, ParseError> {
+    let robot = explored(input)?;
+    Ok(robot.path_to_goal().unwrap().len())
+}
+
+fn glyph(tile: &Tile) -> char {
+    match tile {
+        Tile::Wall => '#',
+        Tile::Empty => '.',
+        Tile::Goal => 'O',
+    }
+}
+
+fn cell(glyph: Option<&char>) -> Cell {
+    match glyph {
+        Some('#') => Cell::new('#', Rgb::GREY),
+        Some('.') => Cell::new('.', Rgb::WHITE),
+        Some('+') => Cell::new('+', Rgb::GREEN),
+        Some('O') => Cell::new('O', Rgb::BLUE),
+        Some('S') => Cell::new('S', Rgb::YELLOW),
+        Some('D') => Cell::new('D', Rgb::RED),
+        _ => Cell::new(' ', Rgb::BLACK),
+    }
+}
+
+// the explored area, with the shortest path from the start to the oxygen system
+pub fn render(input: &str) -> Result<Picture, ParseError> {
+    let robot = explored(input)?;
+    let mut view: BTreeMap<Coordinate, char> = robot
+        .known_tiles
+        .iter()
+        .map(|(&coord, tile)| (coord, glyph(tile)))
+        .collect();
+    let path = robot.path_to_goal().unwrap_or_default();
+    for &coord in path.iter().take(path.len().saturating_sub(1)) {
+        view.insert(coord, '+');
+    }
+    view.insert(Coordinate::new(0, 0), 'S');
+
+    Ok(Picture::from_sparse(&view, cell))
+}
+
+// the view within bounds, with the droid drawn over its tile
+fn with_droid(view: &BTreeMap<Coordinate, char>, bounds: Bounds, droid: Coordinate) -> Picture {
+    let mut picture = Picture::from_sparse_in(view, bounds, cell);
+    let x = (droid.x - bounds.min.x) as usize;
+    let y = (droid.y - bounds.min.y) as usize;
+    picture.set(x, y, cell(Some(&'D')));
+    picture
+}
+
+// replays the droid exploring one move at a time,
+// then oxygen filling the area one minute at a time
+pub fn record(input: &str, recorder: &mut Recorder) -> Result<(), ParseError> {
+    let robot = explored(input)?;
+    let bounds = Bounds::of(robot.known_tiles.keys().copied()).unwrap();
+
+    let mut view = BTreeMap::new();
+    let mut position = Coordinate::new(0, 0);
+    view.insert(position, glyph(&Tile::Empty));
+    for &(coord, tile) in robot.discovered.iter() {
+        view.insert(coord, glyph(&tile));
+        if tile != Tile::Wall {
+            position = coord;
+        }
+        recorder.offer(|| with_droid(&view, bounds, position));
+        if recorder.is_full() {
+            return Ok(());
         }
-        depth += 1;
-        std::mem::swap(&mut current_depth, &mut next_depth);
     }
 
-    depth - 1
+    let minutes = oxygen_minutes(&robot.known_tiles, robot.goal().unwrap());
+    let last = minutes.values().copied().max().unwrap_or(0);
+    for minute in 0..=last {
+        for (&coord, _) in minutes.iter().filter(|(_, &m)| m == minute) {
+            view.insert(coord, 'O');
+        }
+        recorder.offer(|| Picture::from_sparse_in(&view, bounds, cell));
+        if recorder.is_full() {
+            break;
+        }
+    }
+    Ok(())
 }
 
-pub fn solve_second(input: &str) -> usize {
-    let memory = read_codes(input);
-    let mut intcode = Intcode::new(memory);
-    let mut robot = RepairDroid::new();
-    explore(&mut intcode, &mut robot);
-    bfs_depth(&robot.known_tiles, robot.goal().unwrap())
+// the droid exploring, one step per move
+struct Exploration {
+    intcode: Intcode,
+    robot: RepairDroid,
 }
 
-fn read_codes(input: &str) -> Vec<i64> {
-    let mut result = Vec::new();
+impl Session for Exploration {
+    fn step(&mut self) -> bool {
+        explore_step(&mut self.intcode, &mut self.robot)
+    }
 
-    for number_str in input.trim().split(',') {
-        result.push(number_str.parse().unwrap());
+    fn picture(&self) -> Picture {
+        let tiles = &self.robot.known_tiles;
+        let view: BTreeMap<Coordinate, char> = tiles
+            .iter()
+            .map(|(&coord, tile)| (coord, glyph(tile)))
+            .collect();
+        let bounds = Bounds::of(tiles.keys().copied()).unwrap();
+        with_droid(&view, bounds, self.robot.position)
     }
-    result
+
+    fn status(&self) -> String {
+        let moves = self.robot.discovered.len();
+        match self.robot.goal() {
+            Some(goal) if self.robot.finished_exploring => format!(
+                "{} moves, area mapped, oxygen fills it in {} minutes",
+                moves,
+                bfs_depth(&self.robot.known_tiles, goal)
+            ),
+            Some(goal) => format!("{} moves, oxygen system at {}, {}", moves, goal.x, goal.y),
+            None => format!("{} moves, oxygen system not found yet", moves),
+        }
+    }
+}
+
+pub fn live(input: &str) -> Result<Box<dyn Session>, ParseError> {
+    Ok(Box::new(Exploration {
+        intcode: Intcode::new(read_codes(input)?),
+        robot: RepairDroid::new(),
+    }))
+}
+
+// should not be called with undiscovered tiles as it can only walk empty tiles
+// minutes for oxygen to reach each tile, the depth of a bfs search from a position
+fn oxygen_minutes(
+    tiles: &BTreeMap<Coordinate, Tile>,
+    start: Coordinate,
+) -> HashMap<Coordinate, usize> {
+    let found = search::bfs(
+        start,
+        Paths::Skip,
+        |&current| {
+            let mut next = current.neighbours().to_vec();
+            next.retain(|neighbor| tiles.get(neighbor) == Some(&Tile::Empty));
+            next
+        },
+        |_| false,
+    );
+    found.costs
+}
+
+// finds max depth of bfs search starting from a position
+fn bfs_depth(tiles: &BTreeMap<Coordinate, Tile>, start: Coordinate) -> usize {
+    let minutes = oxygen_minutes(tiles, start);
+    minutes.values().copied().max().unwrap_or(0)
+}
+
+pub fn solve_second(input: &str) -> Result<usize, ParseError> {
+    let robot = explored(input)?;
+    Ok(bfs_depth(&robot.known_tiles, robot.goal().unwrap()))
+}
+
+fn read_codes(input: &str) -> Result<Vec<i64>, ParseError> {
+    parse::integers(input)
 }
 
 // returns (A, B, C, DE)
@@ -555,16 +606,4 @@ mod test {
 
         assert_eq!(bfs_depth(&tiles, Coordinate::new(2, 3)), 4);
     }
-
-    #[test]
-    fn test_first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 294);
-    }
-
-    #[test]
-    fn test_second() {
-        let input = include_str!("input");
-        assert_eq!(solve_second(input), 388);
-    }
 }
@@ -1,11 +1,33 @@
-fn read_numbers(input: &str) -> Vec<i32> {
-    let mut result = Vec::new();
+use crate::params::{at_least, params, Checked};
+use crate::parse::ParseError;
+
+const SIGNAL: &str = "a signal of at least 8 digits";
 
-    for c in input.trim().chars() {
-        result.push(c.to_digit(10).unwrap() as i32);
+params! {
+    pub struct Params {
+        phases: usize = 100,
+        // part 2 only
+        repeat: usize = 10_000,
     }
+    check(params) {
+        at_least("repeat", params.repeat, 1)
+    }
+}
 
-    result
+fn read_numbers(input: &str) -> Result<Vec<i32>, ParseError> {
+    let line = input.trim();
+    let mut result = Vec::new();
+
+    for (column, c) in line.chars().enumerate() {
+        match c.to_digit(10) {
+            Some(digit) => result.push(digit as i32),
+            None => return Err(ParseError::new(1, column + 1, &c.to_string(), SIGNAL)),
+        }
+    }
+    if result.len() < 8 {
+        return Err(ParseError::new(1, 1, line, SIGNAL));
+    }
+    Ok(result)
 }
 
 fn apply_phase(mut numbers: Vec<i32>, phases: usize) -> Vec<i32> {
@@ -33,24 +55,36 @@ fn fold_to_number(numbers: &[i32]) -> i32 {
         .fold(0, |total, current| total * 10 + current)
 }
 
-pub fn solve_first(input: &str) -> i32 {
-    let numbers = read_numbers(input);
-    let result = apply_phase(numbers, 100);
+pub fn solve_first_with(input: &str, params: &Params) -> Checked<i32> {
+    params.validate()?;
+    Ok(read_numbers(input).map(|numbers| {
+        let result = apply_phase(numbers, params.phases);
+        fold_to_number(&result[0..8])
+    }))
+}
 
-    fold_to_number(&result[0..8])
+pub fn solve_first(input: &str) -> Result<i32, ParseError> {
+    solve_first_with(input, &Params::default()).expect("the defaults are valid")
 }
 
-pub fn solve_second(input: &str) -> i32 {
-    let numbers = read_numbers(input);
-    let start = fold_to_number(&numbers[0..7]) as usize;
-    let end = numbers.len() * 10_000;
+// The digits from `offset` on after `phases` phases of the signal repeated
+// `repeat` times. In the second half a digit's pattern is zeros up to it and
+// ones from it to the end, so each new digit is the sum of the digits from
+// it on, mod 10.
+fn decode_tail(numbers: &[i32], repeat: usize, offset: usize, phases: usize) -> Vec<i32> {
+    let end = numbers.len() * repeat;
+    assert!(
+        2 * offset + 1 >= end,
+        "offset {} is in the first half",
+        offset
+    );
 
     let mut current = Vec::new();
-    for i in start..end {
+    for i in offset..end {
         current.push(numbers[i % numbers.len()]);
     }
 
-    for _ in 0..100 {
+    for _ in 0..phases {
         let mut sums = Vec::new();
         let mut total = 0;
         sums.push(0);
@@ -64,31 +98,88 @@ pub fn solve_second(input: &str) -> i32 {
             current[i] = value % 10;
         }
     }
-    fold_to_number(&current[0..8])
+    current
+}
+
+// the eight digits at the offset the first seven give
+fn read_message(input: &str, params: &Params) -> Result<i32, ParseError> {
+    let numbers = read_numbers(input)?;
+    let offset = fold_to_number(&numbers[0..7]) as usize;
+    let end = numbers.len() * params.repeat;
+    // only the second half can be decoded quickly, and 8 digits have to fit
+    if 2 * offset + 1 < end || offset + 8 > end {
+        let text: String = input.trim().chars().take(7).collect();
+        return Err(ParseError::new(
+            1,
+            1,
+            &text,
+            "a message offset in the second half of the repeated signal",
+        ));
+    }
+    let current = decode_tail(&numbers, params.repeat, offset, params.phases);
+    Ok(fold_to_number(&current[0..8]))
+}
+
+pub fn solve_second_with(input: &str, params: &Params) -> Checked<i32> {
+    params.validate()?;
+    Ok(read_message(input, params))
+}
+
+pub fn solve_second(input: &str) -> Result<i32, ParseError> {
+    solve_second_with(input, &Params::default()).expect("the defaults are valid")
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::random::Rng;
+
+    #[test]
+    fn example_phases() {
+        let params = Params {
+            phases: 4,
+            ..Params::default()
+        };
+        assert_eq!(solve_first_with("12345678", &params), Ok(Ok(1029498)));
+        let params = Params {
+            repeat: 0,
+            ..Params::default()
+        };
+        assert!(solve_second_with("12345678", &params).is_err());
+    }
+
+    #[test]
+    fn parse_error() {
+        assert_eq!(
+            read_numbers("1234x678\n"),
+            Err(ParseError::new(1, 5, "x", SIGNAL))
+        );
+        assert_eq!(
+            read_numbers("1234567"),
+            Err(ParseError::new(1, 1, "1234567", SIGNAL))
+        );
+        // an offset in the first half
+        assert!(solve_second("12345678").is_err());
+    }
 
     #[test]
     fn example1() {
         let input = "80871224585914546619083218645595";
-        let first = solve_first(input);
+        let first = solve_first(input).unwrap();
         let expected = 24176176;
         assert_eq!(first, expected);
     }
     #[test]
     fn example2() {
         let input = "19617804207202209144916044189917";
-        let first = solve_first(input);
+        let first = solve_first(input).unwrap();
         let expected = 73745418;
         assert_eq!(first, expected);
     }
     #[test]
     fn example3() {
         let input = "69317163492948606335995924319873";
-        let first = solve_first(input);
+        let first = solve_first(input).unwrap();
         let expected = 52432133;
         assert_eq!(first, expected);
     }
@@ -96,7 +187,7 @@ mod test {
     #[test]
     fn example4() {
         let input = "03036732577212944063491565474664";
-        let second = solve_second(input);
+        let second = solve_second(input).unwrap();
         let expected = 84462026;
         assert_eq!(second, expected);
     }
@@ -104,7 +195,7 @@ mod test {
     #[test]
     fn example5() {
         let input = "02935109699940807407585447034323";
-        let second = solve_second(input);
+        let second = solve_second(input).unwrap();
         let expected = 78725270;
         assert_eq!(second, expected);
     }
@@ -112,24 +203,35 @@ mod test {
     #[test]
     fn example6() {
         let input = "03081770884921959731165446850517";
-        let second = solve_second(input);
+        let second = solve_second(input).unwrap();
         let expected = 53553731;
         assert_eq!(second, expected);
     }
 
     #[test]
-    fn first() {
-        let input = include_str!("input");
-        let result = solve_first(input);
-        let expected = 63483758;
-        assert_eq!(result, expected);
-    }
-
-    #[test]
-    fn second() {
-        let input = include_str!("input");
-        let result = solve_second(input);
-        let expected = 96099551;
-        assert_eq!(result, expected);
+    fn test_decode_tail() {
+        let mut rng = Rng::new(16);
+        for _ in 0..200 {
+            let numbers: Vec<i32> = (0..rng.range(1..=12))
+                .map(|_| rng.range(0..=9) as i32)
+                .collect();
+            let repeat = rng.range(1..=20) as usize;
+            let end = numbers.len() * repeat;
+            let offset = rng.range(end as i64 / 2..=end as i64 - 1) as usize;
+            let phases = rng.range(0..=12) as usize;
+
+            // the full transform of the whole signal
+            let signal: Vec<i32> = numbers.iter().copied().cycle().take(end).collect();
+            let full = apply_phase(signal, phases);
+            assert_eq!(
+                decode_tail(&numbers, repeat, offset, phases),
+                &full[offset..],
+                "{:?} x {} from {} after {} phases",
+                numbers,
+                repeat,
+                offset,
+                phases
+            );
+        }
     }
 }
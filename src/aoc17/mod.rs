@@ -1,43 +1,9 @@
-use std::collections::{BTreeSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
-#[derive(PartialEq, Eq, Ord, PartialOrd, Clone, Copy, Debug)]
-struct Coordinate {
-    y: i32, // derive(PartialOrd) uses order here -- sort by y.then(x)
-    x: i32,
-}
-
-impl Coordinate {
-    fn new(x: i32, y: i32) -> Self {
-        Coordinate { y, x }
-    }
-
-    fn next(&mut self) {
-        self.x += 1;
-    }
-
-    fn next_newline(&mut self) {
-        self.x = 0;
-        self.y += 1;
-    }
-
-    fn neighbours(&self) -> Vec<Coordinate> {
-        vec![
-            Coordinate::new(self.x - 1, self.y),
-            Coordinate::new(self.x, self.y - 1),
-            Coordinate::new(self.x, self.y + 1),
-            Coordinate::new(self.x + 1, self.y),
-        ]
-    }
-
-    fn move_direction(&self, dir: &Direction) -> Coordinate {
-        match dir {
-            Direction::Left => Coordinate::new(self.x - 1, self.y),
-            Direction::Right => Coordinate::new(self.x + 1, self.y),
-            Direction::Up => Coordinate::new(self.x, self.y - 1),
-            Direction::Down => Coordinate::new(self.x, self.y + 1),
-        }
-    }
-}
+use crate::grid::{Coordinate, Direction};
+use crate::live::Session;
+use crate::parse::{self, ParseError};
+use crate::render::{Cell, Picture, Rgb};
 
 // maps a board
 struct AsciiBot {
@@ -45,35 +11,7 @@ struct AsciiBot {
     board: BTreeSet<Coordinate>,
     start: Coordinate,
     start_dir: Direction,
-    s: String,
-}
-#[derive(Debug)]
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
-}
-
-impl Direction {
-    fn turn_left(&self) -> Direction {
-        match self {
-            Direction::Up => Direction::Left,
-            Direction::Right => Direction::Up,
-            Direction::Down => Direction::Right,
-            Direction::Left => Direction::Down,
-        }
-    }
-    fn turn_right(&self) -> Direction {
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Right => Direction::Down,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-        }
-    }
 }
-
 struct Board {
     tiles: BTreeSet<Coordinate>,
     start_pos: Coordinate,
@@ -106,20 +44,18 @@ impl AsciiBot {
             board: BTreeSet::new(),
             start: Coordinate::new(0, 0),
             start_dir: Direction::Right,
-            s: String::new(),
         }
     }
 
     fn next_input(&mut self, value: i64) {
         let value_char = std::char::from_u32(value as u32).unwrap();
-        self.s.push(value_char);
         match value_char {
             '\n' => {
-                self.next_coord.next_newline();
+                self.next_coord = Coordinate::new(0, self.next_coord.y + 1);
             }
             '.' => {
                 // empty
-                self.next_coord.next();
+                self.next_coord.x += 1;
             }
             _ => {
                 // '>' | '<' | '^' | 'v' | '#'
@@ -127,7 +63,7 @@ impl AsciiBot {
                 if value_char != '#' {
                     self.start = self.next_coord;
                 }
-                self.next_coord.next();
+                self.next_coord.x += 1;
             }
         }
 
@@ -141,7 +77,6 @@ impl AsciiBot {
     }
 
     fn get_board(self) -> Board {
-        println!("{}", self.s);
         Board {
             tiles: self.board,
             start_pos: self.start,
@@ -150,8 +85,8 @@ impl AsciiBot {
     }
 }
 
-fn read_ascii(input: &str) -> Board {
-    let memory = read_codes(input);
+fn read_ascii(input: &str) -> Result<Board, ParseError> {
+    let memory = read_codes(input)?;
     let mut intcode = Intcode::new(memory);
     let mut ascii = AsciiBot::new();
     intcode.run_until_output();
@@ -159,17 +94,102 @@ fn read_ascii(input: &str) -> Board {
         ascii.next_input(intcode.output.pop_front().unwrap());
         intcode.run_until_output();
     }
-    ascii.get_board()
+    Ok(ascii.get_board())
 }
 
-pub fn solve_first(input: &str) -> i32 {
-    let board = read_ascii(input);
+// the scaffold, with the vacuum robot facing its start direction if it's been seen
+fn camera(tiles: &BTreeSet<Coordinate>, robot: Option<(Coordinate, Direction)>) -> Picture {
+    let mut view: BTreeMap<Coordinate, char> = tiles.iter().map(|&coord| (coord, '#')).collect();
+    if let Some((position, direction)) = robot {
+        let glyph = match direction {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        };
+        view.insert(position, glyph);
+    }
+
+    Picture::from_sparse(&view, |glyph| match glyph {
+        Some('#') => Cell::new('#', Rgb::GREY),
+        Some(&robot) => Cell::new(robot, Rgb::YELLOW),
+        None => Cell::new('.', Rgb::BLACK),
+    })
+}
+
+// the camera view
+pub fn render(input: &str) -> Result<Picture, ParseError> {
+    let board = read_ascii(input)?;
+    Ok(camera(
+        &board.tiles,
+        Some((board.start_pos, board.start_dir)),
+    ))
+}
+
+// the camera view coming in, one step per row
+struct Camera {
+    intcode: Intcode,
+    ascii: AsciiBot,
+    robot_seen: bool,
+}
+
+impl Session for Camera {
+    fn step(&mut self) -> bool {
+        if self.intcode.finished {
+            return false;
+        }
+        loop {
+            self.intcode.run_until_output();
+            match self.intcode.output.pop_front() {
+                Some(value) => {
+                    self.ascii.next_input(value);
+                    match std::char::from_u32(value as u32) {
+                        Some('\n') => break,
+                        Some('^') | Some('>') | Some('v') | Some('<') => self.robot_seen = true,
+                        _ => {}
+                    }
+                }
+                None => break,
+            }
+        }
+        true
+    }
+
+    fn picture(&self) -> Picture {
+        let robot = if self.robot_seen {
+            Some((self.ascii.start, self.ascii.start_dir))
+        } else {
+            None
+        };
+        camera(&self.ascii.board, robot)
+    }
+
+    fn status(&self) -> String {
+        format!(
+            "{} rows, {} scaffold tiles",
+            self.ascii.next_coord.y,
+            self.ascii.board.len()
+        )
+    }
+}
+
+pub fn live(input: &str) -> Result<Box<dyn Session>, ParseError> {
+    Ok(Box::new(Camera {
+        intcode: Intcode::new(read_codes(input)?),
+        ascii: AsciiBot::new(),
+        robot_seen: false,
+    }))
+}
+
+pub fn solve_first(input: &str) -> Result<i32, ParseError> {
+    let board = read_ascii(input)?;
     let mut result = 0;
     for coord in board.intersections() {
         result += (coord.x * coord.y) as i32;
     }
-    result
+    Ok(result)
 }
+#[allow(dead_code)]
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum Movement {
     Forward,
@@ -177,6 +197,7 @@ enum Movement {
     Right,
 }
 
+#[allow(dead_code)]
 fn plan_path(board: Board) -> Vec<Movement> {
     let mut result = Vec::new();
 
@@ -184,17 +205,17 @@ fn plan_path(board: Board) -> Vec<Movement> {
     let mut current_dir = board.start_dir;
 
     loop {
-        let forward_pos = current_pos.move_direction(&current_dir);
+        let forward_pos = current_pos.step(current_dir);
         // move forward whenever possible
         if board.tiles.contains(&forward_pos) {
             result.push(Movement::Forward);
             current_pos = forward_pos;
         } else {
             let right_dir = current_dir.turn_right();
-            let right_pos = current_pos.move_direction(&right_dir);
+            let right_pos = current_pos.step(right_dir);
 
             let left_dir = current_dir.turn_left();
-            let left_pos = current_pos.move_direction(&left_dir);
+            let left_pos = current_pos.step(left_dir);
 
             // move right first if possible, move left only if moving right isn't possible
             if board.tiles.contains(&right_pos) {
@@ -218,6 +239,7 @@ fn plan_path(board: Board) -> Vec<Movement> {
     result
 }
 
+#[allow(dead_code)]
 fn compact_string(mut path: &str) -> String {
     let mut result = String::new();
 
@@ -231,6 +253,7 @@ fn compact_string(mut path: &str) -> String {
     result
 }
 // returns str left to parse, result of parsed str
+#[allow(dead_code)]
 fn parse_until_turn(path: &str) -> (&str, String) {
     let mut count = 0;
     let mut length = 0;
@@ -256,6 +279,7 @@ fn parse_until_turn(path: &str) -> (&str, String) {
     (&path[length..], result)
 }
 
+#[allow(dead_code)]
 fn path_to_string(path: &[Movement]) -> String {
     let mut result = String::new();
     for mov in path {
@@ -276,8 +300,8 @@ fn string_to_values(input: &str) -> VecDeque<i64> {
     result
 }
 
-pub fn solve_second(input: &str) -> i64 {
-    let mut memory = read_codes(input);
+pub fn solve_second(input: &str) -> Result<i64, ParseError> {
+    let mut memory = read_codes(input)?;
     memory[0] = 2;
     let mut intcode = Intcode::new(memory);
 
@@ -305,9 +329,10 @@ pub fn solve_second(input: &str) -> i64 {
     while !intcode.finished {
         intcode.run_until_output();
     }
-    intcode.output.pop_back().unwrap()
+    Ok(intcode.output.pop_back().unwrap())
 }
 
+#[allow(dead_code)]
 fn board_from_string(input: &str) -> Board {
     let mut ascii = AsciiBot::new();
     for c in input.chars() {
@@ -318,13 +343,8 @@ fn board_from_string(input: &str) -> Board {
 
 // ------ INTCODE (same as day 15) -----------
 
-fn read_codes(input: &str) -> Vec<i64> {
-    let mut result = Vec::new();
-
-    for number_str in input.trim().split(',') {
-        result.push(number_str.parse().unwrap());
-    }
-    result
+fn read_codes(input: &str) -> Result<Vec<i64>, ParseError> {
+    parse::integers(input)
 }
 
 // returns (A, B, C, DE)
@@ -518,18 +538,4 @@ mod test {
         let expected = "5,R";
         assert_eq!(compact_string(input), expected);
     }
-
-    #[test]
-    fn first() {
-        let input = include_str!("input");
-        let expected = 2788;
-        assert_eq!(solve_first(input), expected);
-    }
-
-    #[test]
-    fn second() {
-        let input = include_str!("input");
-        let expected = 761085;
-        assert_eq!(solve_second(input), expected);
-    }
 }
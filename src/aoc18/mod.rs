@@ -1,19 +1,10 @@
-use std::cmp::Ordering;
-use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque};
-
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-struct Coordinate(i32, i32);
-
-impl Coordinate {
-    fn neighbours(&self) -> [Coordinate; 4] {
-        [
-            Coordinate(self.0 - 1, self.1),
-            Coordinate(self.0 + 1, self.1),
-            Coordinate(self.0, self.1 - 1),
-            Coordinate(self.0, self.1 + 1),
-        ]
-    }
-}
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::grid::{self, Coordinate, Grid};
+use crate::parse::ParseError;
+use crate::random::Rng;
+use crate::render::{Cell, Picture, Rgb};
+use crate::search::{self, Paths};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum Tile {
@@ -22,31 +13,45 @@ enum Tile {
     Node(char),
 }
 
-fn parse_grid(input: &str) -> HashMap<Coordinate, Tile> {
-    let mut grid = HashMap::new();
-    let mut height = 0;
-    for line in input.trim().lines() {
-        let mut width = 0;
-        for c in line.chars() {
-            let tile = match c {
-                '#' => Tile::Wall,
-                '.' => Tile::Empty,
-                _ => Tile::Node(c),
-            };
-            grid.insert(Coordinate(width, height), tile);
-            width += 1;
-        }
-        height += 1;
+fn parse_grid(input: &str) -> Result<Grid<Tile>, ParseError> {
+    Grid::parse(
+        input.trim(),
+        "a wall #, open passage ., entrance @, key or door",
+        |c| match c {
+            '#' => Some(Tile::Wall),
+            '.' => Some(Tile::Empty),
+            '@' => Some(Tile::Node(c)),
+            _ if c.is_ascii_alphabetic() => Some(Tile::Node(c)),
+            _ => None,
+        },
+    )
+}
+
+// where the one robot starts, Err if there's no entrance or more than one
+fn entrance(grid: &Grid<Tile>) -> Result<Coordinate, ParseError> {
+    let mut entrances = grid
+        .iter()
+        .filter(|&(_, &tile)| tile == Tile::Node('@'))
+        .map(|(c, _)| c);
+    let found = entrances
+        .next()
+        .ok_or_else(|| ParseError::new(1, 1, "", "a map with an entrance @"))?;
+    match entrances.next() {
+        Some(c) => Err(at(c, "a single entrance @")),
+        None => Ok(found),
     }
-    grid
+}
+
+fn at(c: Coordinate, expected: &str) -> ParseError {
+    ParseError::new(c.y as usize + 1, c.x as usize + 1, "@", expected)
 }
 
 // build a graph from a grid
-fn graph(grid: &HashMap<Coordinate, Tile>) -> HashMap<char, HashMap<char, usize>> {
+fn graph(grid: &Grid<Tile>) -> HashMap<char, HashMap<char, usize>> {
     let mut graph = HashMap::new();
     for (coord, tile) in grid.iter() {
         if let Tile::Node(c) = tile {
-            let pos_edges = reachable_from(grid, *coord);
+            let pos_edges = reachable_from(grid, coord);
             graph.insert(*c, pos_edges);
         }
     }
@@ -55,330 +60,254 @@ fn graph(grid: &HashMap<Coordinate, Tile>) -> HashMap<char, HashMap<char, usize>
 }
 
 // returns vertices reachable from a coordinate
-fn reachable_from(grid: &HashMap<Coordinate, Tile>, coord: Coordinate) -> HashMap<char, usize> {
-    let mut visited = HashSet::new();
-    let mut result = HashMap::new();
-
-    let mut queue = VecDeque::new();
-    queue.push_back((coord, 0));
-
-    visited.insert(coord);
-    while let Some((current, steps)) = queue.pop_front() {
-        for neighbour in &current.neighbours() {
-            if let Some(tile) = grid.get(neighbour) {
-                if !visited.contains(neighbour) {
-                    visited.insert(*neighbour);
-                    match tile {
-                        Tile::Empty => {
-                            queue.push_back((*neighbour, steps + 1));
-                        }
-                        Tile::Node(c) => {
-                            result.insert(*c, steps + 1);
-                        }
-                        Tile::Wall => {}
-                    }
-                }
-            }
-        }
-    }
-    result
-}
-#[derive(PartialEq, Eq)]
-struct State {
-    steps: usize,
-    node: char,
-    keys: BTreeSet<char>,
-}
-
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other
-            .steps
-            .cmp(&self.steps)
-            .then(self.keys.len().cmp(&other.keys.len()))
-    }
-}
+// paths only run through open passages, any other node ends them
+fn reachable_from(grid: &Grid<Tile>, start: Coordinate) -> HashMap<char, usize> {
+    let found = search::bfs(
+        start,
+        Paths::Skip,
+        |&current| {
+            let open = current == start || grid.get(current) == Some(&Tile::Empty);
+            grid.neighbours(current)
+                .filter(|&(_, &tile)| open && tile != Tile::Wall)
+                .map(|(next, _)| next)
+                .collect::<Vec<_>>()
+        },
+        |_| false,
+    );
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+    found
+        .costs
+        .into_iter()
+        .filter(|&(coord, _)| coord != start)
+        .filter_map(|(coord, steps)| match grid.get(coord) {
+            Some(&Tile::Node(c)) => Some((c, steps)),
+            _ => None,
+        })
+        .collect()
 }
 
 fn search(graph: HashMap<char, HashMap<char, usize>>, start: char) -> usize {
-    let mut priority_queue = BinaryHeap::new();
     let key_count = graph.iter().filter(|(k, _)| k.is_lowercase()).count();
 
-    // keep track of best cost at (robot_position, keys collected)
-    let mut distances: HashMap<(char, BTreeSet<char>), usize> = HashMap::new();
-    distances.insert((start, BTreeSet::new()), 0);
-
-    let start = State {
-        steps: 0,
-        node: start,
-        keys: BTreeSet::new(),
-    };
-
-    priority_queue.push(start);
-
-    // search keys cache, avoid recomputing search keys for the same (position, keys collected)
-    let mut cache: HashMap<(char, BTreeSet<char>), Vec<(char, usize)>> = HashMap::new();
-
-    while let Some(current) = priority_queue.pop() {
-        if current.keys.len() == key_count {
-            return current.steps;
-        }
-
-        if let Some(&best_steps) = distances.get(&(current.node, current.keys.clone())) {
-            if current.steps > best_steps {
-                continue;
-            }
-        }
-
-        let cache_key = (current.node, current.keys.clone());
-
-        let cached_entry = cache
-            .entry(cache_key)
-            .or_insert_with(|| search_keys(&graph, &current.keys, current.node));
-
-        for &(next_node, cost) in cached_entry.iter() {
-            let mut next_keys = current.keys.clone();
-            next_keys.insert(next_node);
-            let next_steps = current.steps + cost;
-
-            let distances_entry = distances
-                .entry((next_node, next_keys.clone()))
-                .or_insert(usize::max_value());
-
-            if next_steps < *distances_entry {
-                *distances_entry = next_steps;
-
-                let next_state = State {
-                    steps: current.steps + cost,
-                    node: next_node,
-                    keys: next_keys,
-                };
+    // states are (robot position, keys collected)
+    let found = search::dijkstra(
+        (start, BTreeSet::new()),
+        Paths::Skip,
+        |(node, keys): &(char, BTreeSet<char>)| {
+            search_keys(&graph, keys, *node)
+                .into_iter()
+                .map(|(next_node, cost)| {
+                    let mut next_keys = keys.clone();
+                    next_keys.insert(next_node);
+                    ((next_node, next_keys), cost)
+                })
+                .collect::<Vec<_>>()
+        },
+        |(_, keys)| keys.len() == key_count,
+    );
 
-                priority_queue.push(next_state);
-            }
-        }
-    }
     // no path found
-    usize::max_value()
-}
-
-#[derive(PartialEq, Eq)]
-struct DijkstraState {
-    cost: usize,
-    current: char,
+    found.goal.map_or(usize::MAX, |(_, steps)| steps)
 }
 
-impl Ord for DijkstraState {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other
-            .cost
-            .cmp(&self.cost)
-            .then_with(|| self.current.cmp(&other.current))
-    }
-}
-
-impl PartialOrd for DijkstraState {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-// adapted from https://doc.rust-lang.org/std/collections/binary_heap/index.html
 // dijkstra search for reachable new keys from start node
+// new keys end a path, doors need their key
 fn search_keys(
     graph: &HashMap<char, HashMap<char, usize>>,
     keys: &BTreeSet<char>,
     start: char,
 ) -> Vec<(char, usize)> {
-    // dist[node] = current shortest distance from `start` to `node`
-    let mut dist = HashMap::new();
-    for &key in graph.keys() {
-        dist.insert(key, usize::max_value());
-    }
-
-    let mut heap = BinaryHeap::new();
-
-    *dist.get_mut(&start).unwrap() = 0;
-    heap.push(DijkstraState {
-        cost: 0,
-        current: start,
-    });
-    // keep track of which new keys we can reach
-    let mut reach = HashSet::new();
-
-    while let Some(DijkstraState { cost, current }) = heap.pop() {
-        if current.is_lowercase() && !keys.contains(&current) {
-            reach.insert(current);
-            continue;
-        }
-
-        // Important as we may have already found a better way
-        if cost > dist[&current] {
-            continue;
-        }
-
-        for (&next_node, &next_cost) in graph.get(&current).unwrap().iter() {
-            // check if we have permission to pass
-            if next_node.is_uppercase() && !keys.contains(&next_node.to_ascii_lowercase()) {
-                continue;
+    let is_new_key = |node: char| node.is_lowercase() && !keys.contains(&node);
+
+    let found = search::dijkstra(
+        start,
+        Paths::Skip,
+        |&current| {
+            if is_new_key(current) {
+                return Vec::new();
             }
+            graph
+                .get(&current)
+                .into_iter()
+                .flatten()
+                // check if we have permission to pass
+                .filter(|(next, _)| {
+                    !next.is_uppercase() || keys.contains(&next.to_ascii_lowercase())
+                })
+                .map(|(&next, &cost)| (next, cost))
+                .collect()
+        },
+        |_| false,
+    );
 
-            let next = DijkstraState {
-                cost: cost + next_cost,
-                current: next_node,
-            };
-
-            if next.cost < dist[&next_node] {
-                dist.insert(next_node, next.cost);
-                heap.push(next);
-            }
-        }
-    }
     // return a tuple of (new keys, cost to reach)
-    reach.into_iter().map(|node| (node, dist[&node])).collect()
+    found
+        .costs
+        .into_iter()
+        .filter(|&(node, _)| is_new_key(node))
+        .collect()
+}
+
+pub fn render(input: &str) -> Result<Picture, ParseError> {
+    let grid = parse_grid(input)?;
+    Ok(Picture::from_grid(&grid, |tile| match *tile {
+        Tile::Wall => Cell::new('#', Rgb::GREY),
+        Tile::Empty => Cell::new('.', Rgb::BLACK),
+        Tile::Node('@') => Cell::new('@', Rgb::YELLOW),
+        Tile::Node(c) if c.is_lowercase() => Cell::new(c, Rgb::GREEN),
+        Tile::Node(c) => Cell::new(c, Rgb::RED),
+    }))
 }
 
-pub fn solve_first(input: &str) -> usize {
-    let grid = parse_grid(input);
+pub fn solve_first(input: &str) -> Result<usize, ParseError> {
+    let grid = parse_grid(input)?;
+    entrance(&grid)?;
     let graph = graph(&grid);
 
-    search(graph, '@')
+    Ok(search(graph, '@'))
 }
 
 // modify grid to split map into 4 sections
-// add 4 robots on each section
-fn four_robots(grid: &mut HashMap<Coordinate, Tile>) {
-    let robot_coord = grid
-        .iter()
-        .find(|(_, &v)| v == Tile::Node('@'))
-        .map(|(k, _)| k.clone())
-        .unwrap();
+// add 4 robots on each section, the entrance has to be in an open 3x3 square
+fn four_robots(grid: &mut Grid<Tile>) -> Result<(), ParseError> {
+    let robot_coord = entrance(grid)?;
+    let Coordinate { x, y } = robot_coord;
+    let open = (y - 1..=y + 1)
+        .flat_map(|y| (x - 1..=x + 1).map(move |x| Coordinate::new(x, y)))
+        .all(|c| c == robot_coord || grid.get(c) == Some(&Tile::Empty));
+    if !open {
+        return Err(at(robot_coord, "an entrance in an open 3x3 square"));
+    }
 
-    grid.insert(robot_coord, Tile::Wall);
+    grid.set(robot_coord, Tile::Wall);
     for &neighbour in &robot_coord.neighbours() {
-        grid.insert(neighbour, Tile::Wall);
+        grid.set(neighbour, Tile::Wall);
     }
-    grid.insert(
-        Coordinate(robot_coord.0 - 1, robot_coord.1 - 1),
-        Tile::Node('@'),
-    );
-    grid.insert(
-        Coordinate(robot_coord.0 - 1, robot_coord.1 + 1),
-        Tile::Node('='),
-    );
-
-    grid.insert(
-        Coordinate(robot_coord.0 + 1, robot_coord.1 + 1),
-        Tile::Node('%'),
-    );
-    grid.insert(
-        Coordinate(robot_coord.0 + 1, robot_coord.1 - 1),
-        Tile::Node('$'),
-    );
+    grid.set(Coordinate::new(x - 1, y - 1), Tile::Node('@'));
+    grid.set(Coordinate::new(x - 1, y + 1), Tile::Node('='));
+    grid.set(Coordinate::new(x + 1, y + 1), Tile::Node('%'));
+    grid.set(Coordinate::new(x + 1, y - 1), Tile::Node('$'));
+    Ok(())
 }
 
 fn search_four(graph: HashMap<char, HashMap<char, usize>>) -> usize {
-    let mut priority_queue = BinaryHeap::new();
     let key_count = graph.iter().filter(|(k, _)| k.is_lowercase()).count();
-
-    // keep track of best cost at (robot_positions, keys collected)
-    let mut distances: HashMap<([char; 4], BTreeSet<char>), usize> = HashMap::new();
     let robots = ['@', '=', '%', '$'];
 
-    distances.insert((robots.clone(), BTreeSet::new()), 0);
-
-    let start = FourState {
-        steps: 0,
-        robots: robots,
-        keys: BTreeSet::new(),
-    };
-
-    priority_queue.push(start);
-
     // search keys cache, avoid recomputing search keys for the same (position, keys collected)
-    let mut cache: HashMap<(char, BTreeSet<char>), Vec<(char, usize)>> = HashMap::new();
-
-    while let Some(current) = priority_queue.pop() {
-        if current.keys.len() == key_count {
-            return current.steps;
-        }
-
-        if let Some(&best_steps) = distances.get(&(current.robots, current.keys.clone())) {
-            if current.steps > best_steps {
-                continue;
-            }
-        }
-
-        for (robot_number, &robot_location) in current.robots.iter().enumerate() {
-            let cache_key = (robot_location, current.keys.clone());
-
-            let cached_entry = cache
-                .entry(cache_key)
-                .or_insert_with(|| search_keys(&graph, &current.keys, robot_location));
-
-            for &(next_node, cost) in cached_entry.iter() {
-                let mut next_keys = current.keys.clone();
-                next_keys.insert(next_node);
-
-                let mut next_robots = current.robots.clone();
-                next_robots[robot_number] = next_node;
-
-                let next_steps = current.steps + cost;
-
-                let distances_entry = distances
-                    .entry((next_robots.clone(), next_keys.clone()))
-                    .or_insert(usize::max_value());
-
-                if next_steps < *distances_entry {
-                    *distances_entry = next_steps;
-                    let next_state = FourState {
-                        steps: next_steps,
-                        robots: next_robots,
-                        keys: next_keys,
-                    };
-
-                    priority_queue.push(next_state);
+    let mut cache: HashMap<(char, BTreeSet<char>), _> = HashMap::new();
+
+    // states are (robot positions, keys collected)
+    let found = search::dijkstra(
+        (robots, BTreeSet::new()),
+        Paths::Skip,
+        |(robots, keys): &([char; 4], BTreeSet<char>)| {
+            let mut next_states = Vec::new();
+            for (robot_number, &robot_location) in robots.iter().enumerate() {
+                let reachable = cache
+                    .entry((robot_location, keys.clone()))
+                    .or_insert_with(|| search_keys(&graph, keys, robot_location));
+
+                for &(next_node, cost) in reachable.iter() {
+                    let mut next_keys = keys.clone();
+                    next_keys.insert(next_node);
+
+                    let mut next_robots = *robots;
+                    next_robots[robot_number] = next_node;
+
+                    next_states.push(((next_robots, next_keys), cost));
                 }
             }
-        }
-    }
+            next_states
+        },
+        |(_, keys)| keys.len() == key_count,
+    );
+
     // no path found
-    usize::max_value()
+    found.goal.map_or(usize::MAX, |(_, steps)| steps)
 }
 
-#[derive(PartialEq, Eq)]
-struct FourState {
-    steps: usize,
-    robots: [char; 4],
-    keys: BTreeSet<char>,
+pub fn solve_second(input: &str) -> Result<usize, ParseError> {
+    let mut grid = parse_grid(input)?;
+    four_robots(&mut grid)?;
+    let graph = graph(&grid);
+
+    Ok(search_four(graph))
 }
 
-impl Ord for FourState {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other
-            .steps
-            .cmp(&self.steps)
-            .then(self.keys.len().cmp(&other.keys.len()))
+// A perfect maze `size` rooms across (made odd, at least 5 so there's room
+// for a key outside the open 5x5 square part 2 splits in the middle). Keys
+// come in order: the door for each key goes on the way to the next one, off
+// the ways to the keys before it, so they can always be collected a to z. The search grows quickly with
+// the number of keys, a quarter of `size` up to 26.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let rooms = (size.max(5) | 1) as i32;
+    let side = 2 * rooms + 1;
+    let centre = Coordinate::new(rooms, rooms);
+    let inside = |c: Coordinate| c.x > 0 && c.y > 0 && c.x < side - 1 && c.y < side - 1;
+    let in_square = |c: Coordinate| (c.x - centre.x).abs() <= 2 && (c.y - centre.y).abs() <= 2;
+
+    let mut grid = Grid::new(side as usize, side as usize, Tile::Wall);
+    for c in grid::carve_maze(centre, inside, &mut rng) {
+        grid.set(c, Tile::Empty);
     }
-}
+    for y in centre.y - 2..=centre.y + 2 {
+        for x in centre.x - 2..=centre.x + 2 {
+            grid.set(Coordinate::new(x, y), Tile::Empty);
+        }
+    }
+    grid.set(centre, Tile::Node('@'));
 
-impl PartialOrd for FourState {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    let mut free: Vec<Coordinate> = grid
+        .iter()
+        .filter(|&(c, &tile)| tile == Tile::Empty && !in_square(c))
+        .map(|(c, _)| c)
+        .collect();
+    let mut keys = Vec::new();
+    for key in (b'a'..=b'z').take((rooms as usize / 4).clamp(1, 26)) {
+        let c = free.swap_remove(rng.below(free.len()));
+        grid.set(c, Tile::Node(key as char));
+        keys.push(c);
     }
-}
 
-pub fn solve_second(input: &str) -> usize {
-    let mut grid = parse_grid(input);
-    four_robots(&mut grid);
-    let graph = graph(&grid);
+    let tree = search::bfs(
+        centre,
+        Paths::Track,
+        |&c| {
+            grid.neighbours(c)
+                .filter(|&(_, &tile)| tile != Tile::Wall)
+                .map(|(next, _)| next)
+                .collect::<Vec<_>>()
+        },
+        |_| false,
+    );
+    let mut walked = HashSet::new();
+    let mut doors = Vec::new();
+    for (i, key) in keys.iter().enumerate() {
+        let path = tree.path_to(key).expect("the maze joins every room");
+        if i > 0 {
+            let spots: Vec<Coordinate> = path
+                .iter()
+                .copied()
+                .filter(|&c| grid.get(c) == Some(&Tile::Empty) && !in_square(c))
+                .filter(|c| !walked.contains(c))
+                .collect();
+            if !spots.is_empty() {
+                doors.push((*rng.pick(&spots), (b'A' + i as u8 - 1) as char));
+            }
+        }
+        walked.extend(path);
+    }
+    for (c, door) in doors {
+        grid.set(c, Tile::Node(door));
+    }
 
-    search_four(graph)
+    grid.render(|tile| match tile {
+        Tile::Wall => '#',
+        Tile::Empty => '.',
+        Tile::Node(c) => *c,
+    })
 }
 
 #[cfg(test)]
@@ -389,41 +318,65 @@ mod test {
     fn example1() {
         let input = include_str!("example1");
         let expected = 8;
-        assert_eq!(solve_first(input), expected);
+        assert_eq!(solve_first(input), Ok(expected));
     }
 
     #[test]
     fn example2() {
         let input = include_str!("example2");
         let expected = 86;
-        assert_eq!(solve_first(input), expected);
+        assert_eq!(solve_first(input), Ok(expected));
     }
 
     #[test]
     fn example3() {
         let input = include_str!("example3");
         let expected = 132;
-        assert_eq!(solve_first(input), expected);
+        assert_eq!(solve_first(input), Ok(expected));
     }
 
     #[test]
     fn example4() {
         let input = include_str!("example4");
         let expected = 136;
-        assert_eq!(solve_first(input), expected);
+        assert_eq!(solve_first(input), Ok(expected));
     }
 
     #[test]
     fn example5() {
         let input = include_str!("example5");
         let expected = 81;
-        assert_eq!(solve_first(input), expected);
+        assert_eq!(solve_first(input), Ok(expected));
+    }
+
+    #[test]
+    fn bad_entrance() {
+        assert_eq!(
+            solve_first("#####\n#a.b#\n#####"),
+            Err(ParseError::new(1, 1, "", "a map with an entrance @"))
+        );
+        assert_eq!(
+            solve_first("#####\n#@a@#\n#####"),
+            Err(ParseError::new(2, 4, "@", "a single entrance @"))
+        );
+        let input = "#######\n#.....#\n#.a@..#\n#.....#\n#######";
+        assert_eq!(
+            solve_second(input),
+            Err(ParseError::new(
+                3,
+                4,
+                "@",
+                "an entrance in an open 3x3 square"
+            ))
+        );
+        // at the edge of the map
+        assert!(solve_second("@a").is_err());
     }
 
     #[test]
     fn next_keys() {
         let input = include_str!("example3");
-        let graph = graph(&parse_grid(input));
+        let graph = graph(&parse_grid(input).unwrap());
         let keys = BTreeSet::new();
         let result = search_keys(&graph, &keys, '@');
         assert!(result.contains(&('a', 2)));
@@ -431,16 +384,36 @@ mod test {
     }
 
     #[test]
-    fn first() {
-        let input = include_str!("input");
-        let expected = 3832;
-        assert_eq!(solve_first(input), expected);
+    fn generated() {
+        assert_eq!(generate(13, 18), generate(13, 18));
+        for seed in 0..4 {
+            let input = generate(13, seed);
+            let grid = parse_grid(&input).unwrap();
+            assert_eq!(grid.width(), 27);
+            let keys = input.chars().filter(|c| c.is_ascii_lowercase()).count();
+            assert_eq!(keys, 3);
+
+            assert!(solve_first(&input).unwrap() < usize::MAX);
+            assert!(solve_second(&input).unwrap() < usize::MAX);
+        }
+        for size in 0..=5 {
+            let input = generate(size, 0);
+            assert_eq!(parse_grid(&input).unwrap().width(), 11);
+            assert!(solve_first(&input).unwrap() > 0);
+            assert!(solve_second(&input).unwrap() > 0);
+        }
     }
 
     #[test]
-    fn second() {
-        let input = include_str!("input");
-        let expected = 1724;
-        assert_eq!(solve_second(input), expected);
+    fn parse_error() {
+        assert_eq!(
+            parse_grid("#####\n#@.?#\n#####\n"),
+            Err(ParseError::new(
+                2,
+                4,
+                "?",
+                "a wall #, open passage ., entrance @, key or door"
+            ))
+        );
     }
 }
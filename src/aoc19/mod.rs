@@ -1,6 +1,27 @@
 use std::collections::BTreeSet;
 use std::collections::VecDeque;
 
+use crate::params::{at_least, params, Checked};
+use crate::parse::{self, ParseError};
+
+params! {
+    pub struct Params {
+        // part 1 checks every point of a square this wide
+        area: usize = 50,
+        // part 2 scans this many rows, looking for the beam between
+        // slope_min and slope_max times the row down
+        height: usize = 1500,
+        slope_min: f64 = 0.65,
+        slope_max: f64 = 0.85,
+        ship: usize = 100,
+    }
+    check(params) {
+        at_least("slope_min", params.slope_min, 0.0)?;
+        at_least("slope_max", params.slope_max, params.slope_min)?;
+        at_least("ship", params.ship, 1)
+    }
+}
+
 // checks for pull in every coordinate of given area
 fn check_pull(intcode: &Intcode, width: usize, height: usize) -> BTreeSet<(usize, usize)> {
     let mut result = BTreeSet::new();
@@ -20,11 +41,16 @@ fn check_pull(intcode: &Intcode, width: usize, height: usize) -> BTreeSet<(usize
     result
 }
 
-pub fn solve_first(input: &str) -> usize {
-    let memory = read_codes(input);
-    let intcode = Intcode::new(memory);
-    let pulls = check_pull(&intcode, 50, 50);
-    pulls.iter().count()
+pub fn solve_first_with(input: &str, params: &Params) -> Checked<usize> {
+    params.validate()?;
+    Ok(read_codes(input).map(|memory| {
+        let intcode = Intcode::new(memory);
+        check_pull(&intcode, params.area, params.area).len()
+    }))
+}
+
+pub fn solve_first(input: &str) -> Result<usize, ParseError> {
+    solve_first_with(input, &Params::default()).expect("the defaults are valid")
 }
 
 fn check_fits(
@@ -42,13 +68,13 @@ fn check_fits(
         && pull_locations.contains(&(point_x, point_y + size_h - 1))
 }
 
-fn scan(intcode: &Intcode, height: usize) -> BTreeSet<(usize, usize)> {
+fn scan(intcode: &Intcode, params: &Params) -> BTreeSet<(usize, usize)> {
     let mut result = BTreeSet::new();
-    for y in 0..height {
+    for y in 0..params.height {
         // we can verify from plotting part 1 that
         // x will approximately be inbetween 0.65 * y to 0.85 * y
-        let left = f64::floor(0.65f64 * (y as f64)) as usize;
-        let right = f64::ceil(0.85f64 * (y as f64)) as usize;
+        let left = f64::floor(params.slope_min * (y as f64)) as usize;
+        let right = f64::ceil(params.slope_max * (y as f64)) as usize;
 
         for x in left..=right {
             let mut intcode_clone = intcode.clone();
@@ -65,27 +91,30 @@ fn scan(intcode: &Intcode, height: usize) -> BTreeSet<(usize, usize)> {
     result
 }
 
-pub fn solve_second(input: &str) -> usize {
-    let memory = read_codes(input);
-    let intcode = Intcode::new(memory);
-    let pulls = scan(&intcode, 1500);
+// the closest point the ship fits in the scanned rows, 0 if it doesn't
+fn closest_fit(intcode: &Intcode, params: &Params) -> usize {
+    let pulls = scan(intcode, params);
     for &(x, y) in pulls.iter() {
-        if check_fits(&pulls, x, y, 100, 100) {
+        if check_fits(&pulls, x, y, params.ship, params.ship) {
             return x * 10000 + y;
         }
     }
     0
 }
 
-// ------ INTCODE (same as day 15) -----------
+pub fn solve_second_with(input: &str, params: &Params) -> Checked<usize> {
+    params.validate()?;
+    Ok(read_codes(input).map(|memory| closest_fit(&Intcode::new(memory), params)))
+}
 
-fn read_codes(input: &str) -> Vec<i64> {
-    let mut result = Vec::new();
+pub fn solve_second(input: &str) -> Result<usize, ParseError> {
+    solve_second_with(input, &Params::default()).expect("the defaults are valid")
+}
 
-    for number_str in input.trim().split(',') {
-        result.push(number_str.parse().unwrap());
-    }
-    result
+// ------ INTCODE (same as day 15) -----------
+
+fn read_codes(input: &str) -> Result<Vec<i64>, ParseError> {
+    parse::integers(input)
 }
 
 // returns (A, B, C, DE)
@@ -273,26 +302,22 @@ impl Intcode {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::answers::puzzle_input;
 
     #[test]
     fn compare_scan() {
-        let input = include_str!("input");
-        let memory = read_codes(input);
+        let input = match puzzle_input(19) {
+            Some(input) => input,
+            None => return,
+        };
+        let memory = read_codes(&input).unwrap();
         let intcode = Intcode::new(memory);
         let checking_all = check_pull(&intcode, 50, 50);
-        let checking_formula = scan(&intcode, 50);
+        let params = Params {
+            height: 50,
+            ..Params::default()
+        };
+        let checking_formula = scan(&intcode, &params);
         assert_eq!(checking_all, checking_formula);
     }
-
-    #[test]
-    fn first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 154);
-    }
-
-    #[test]
-    fn second() {
-        let input = include_str!("input");
-        assert_eq!(solve_second(input), 9791328);
-    }
 }
@@ -0,0 +1,94 @@
+use std::collections::HashSet;
+
+use crate::grid::{self, Coordinate, Direction};
+use crate::random::Rng;
+
+// A donut maze: a perfect maze on a ring `2 * size + 1` tiles thick round a
+// hole about as wide, inside two tiles of space for the outer labels. Every
+// portal pair has one end on the outside edge and one on the inside, AA and
+// ZZ are on the outside. Rooms sit on odd coordinates and portals hang off
+// them, so no two portal tiles are ever next to each other.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let thickness = 2 * size.max(1) as i32 + 1;
+    let hole = 2 * size.max(2) as i32 + 1;
+    let width = 2 * thickness + hole;
+    let in_hole = |c: Coordinate| {
+        let inside = |n| n >= thickness && n < thickness + hole;
+        inside(c.x) && inside(c.y)
+    };
+
+    // the maze in its own coordinates, 0 to width - 1
+    let room =
+        |c: Coordinate| c.x > 0 && c.y > 0 && c.x < width - 1 && c.y < width - 1 && !in_hole(c);
+    let open = grid::carve_maze(Coordinate::new(1, 1), room, &mut rng);
+
+    // a portal tile and the direction its label reads away from the maze
+    let mut outer = Vec::new();
+    for n in (1..width - 1).step_by(2) {
+        outer.push((Coordinate::new(n, 0), Direction::Up));
+        outer.push((Coordinate::new(n, width - 1), Direction::Down));
+        outer.push((Coordinate::new(0, n), Direction::Left));
+        outer.push((Coordinate::new(width - 1, n), Direction::Right));
+    }
+    // the hole's first and last rows and columns stay clear so labels
+    // on neighbouring sides can't run into each other
+    let mut inner = Vec::new();
+    for n in (thickness + 2..thickness + hole - 2).step_by(2) {
+        inner.push((Coordinate::new(n, thickness - 1), Direction::Down));
+        inner.push((Coordinate::new(n, thickness + hole), Direction::Up));
+        inner.push((Coordinate::new(thickness - 1, n), Direction::Right));
+        inner.push((Coordinate::new(thickness + hole, n), Direction::Left));
+    }
+
+    let mut names = HashSet::new();
+    names.insert(String::from("AA"));
+    names.insert(String::from("ZZ"));
+    let mut portals = Vec::new();
+    for name in ["AA", "ZZ"].iter() {
+        portals.push((outer.swap_remove(rng.below(outer.len())), name.to_string()));
+    }
+    let pairs = (inner.len() / 2).max(1);
+    while portals.len() < 2 + 2 * pairs {
+        let name: String = (0..2)
+            .map(|_| (b'A' + rng.below(26) as u8) as char)
+            .collect();
+        if !names.insert(name.clone()) {
+            continue;
+        }
+        portals.push((outer.swap_remove(rng.below(outer.len())), name.clone()));
+        portals.push((inner.swap_remove(rng.below(inner.len())), name));
+    }
+
+    // the map has two tiles of space round the maze
+    let side = (width + 4) as usize;
+    let mut map = vec![vec![' '; side]; side];
+    for y in 0..width {
+        for x in 0..width {
+            let c = Coordinate::new(x, y);
+            if !in_hole(c) {
+                map[(y + 2) as usize][(x + 2) as usize] = if open.contains(&c) { '.' } else { '#' };
+            }
+        }
+    }
+    // labels read left to right or top to bottom whichever side they're on
+    for ((c, away), name) in portals {
+        let mut tile = Coordinate::new(c.x + 2, c.y + 2);
+        map[tile.y as usize][tile.x as usize] = '.';
+        let mut letters: Vec<char> = name.chars().collect();
+        if away == Direction::Left || away == Direction::Up {
+            letters.reverse();
+        }
+        for letter in letters {
+            tile = tile.step(away);
+            map[tile.y as usize][tile.x as usize] = letter;
+        }
+    }
+
+    let mut result = String::with_capacity((side + 1) * side);
+    for row in map {
+        result.extend(row);
+        result.push('\n');
+    }
+    result
+}
@@ -1,34 +1,50 @@
 // https://adventofcode.com/2019/day/20
 mod first;
+mod generate;
 mod parse;
 mod second;
 
+pub use generate::generate;
+
 use parse::Portal;
 use parse::{parse_graph, parse_input, parse_nodes};
 
 use first::shortest_path;
 use second::shortest_path_depth;
 
-pub fn solve_first(input: &str) -> usize {
-    let grid = parse_input(input);
+use crate::parse::ParseError;
+use crate::render::{Cell, Picture, Rgb};
+
+pub fn render(input: &str) -> Result<Picture, ParseError> {
+    let grid = parse_input(input)?;
+    Ok(Picture::from_grid(&grid, |&c| match c {
+        '#' => Cell::new('#', Rgb::GREY),
+        '.' => Cell::new('.', Rgb::WHITE),
+        ' ' => Cell::new(' ', Rgb::BLACK),
+        _ => Cell::new(c, Rgb::YELLOW),
+    }))
+}
 
-    let nodes = parse_nodes(&grid);
+pub fn solve_first(input: &str) -> Result<usize, ParseError> {
+    let grid = parse_input(input)?;
+
+    let nodes = parse_nodes(&grid)?;
     let graph = parse_graph(&grid, &nodes);
     if let Some(u) = shortest_path(
         graph,
         Portal::Outer(String::from("AA")),
         Portal::Outer(String::from("ZZ")),
     ) {
-        u
+        Ok(u)
     } else {
-        usize::MAX
+        Ok(usize::MAX)
     }
 }
 
-pub fn solve_second(input: &str) -> usize {
-    let grid = parse_input(input);
+pub fn solve_second(input: &str) -> Result<usize, ParseError> {
+    let grid = parse_input(input)?;
 
-    let nodes = parse_nodes(&grid);
+    let nodes = parse_nodes(&grid)?;
     let graph = parse_graph(&grid, &nodes);
 
     if let Some(u) = shortest_path_depth(
@@ -36,9 +52,9 @@ pub fn solve_second(input: &str) -> usize {
         Portal::Outer(String::from("AA")),
         Portal::Outer(String::from("ZZ")),
     ) {
-        u
+        Ok(u)
     } else {
-        usize::MAX
+        Ok(usize::MAX)
     }
 }
 
@@ -48,50 +64,96 @@ mod test {
 
     #[test]
     fn nodes() {
+        use crate::grid::Coordinate;
         use std::collections::HashMap;
 
         let input = include_str!("example1");
-        let map = parse_input(input);
-        let nodes = parse_nodes(&map);
+        let map = parse_input(input).unwrap();
+        let nodes = parse_nodes(&map).unwrap();
         let mut expected = HashMap::new();
-        expected.insert((2, 9), Portal::Outer(String::from("AA")));
-        expected.insert((8, 2), Portal::Outer(String::from("BC")));
-        expected.insert((13, 2), Portal::Outer(String::from("DE")));
-        expected.insert((15, 2), Portal::Outer(String::from("FG")));
-        expected.insert((16, 13), Portal::Outer(String::from("ZZ")));
-        expected.insert((6, 9), Portal::Inner(String::from("BC")));
-        expected.insert((10, 6), Portal::Inner(String::from("DE")));
-        expected.insert((12, 11), Portal::Inner(String::from("FG")));
+        expected.insert(Coordinate::new(9, 2), Portal::Outer(String::from("AA")));
+        expected.insert(Coordinate::new(2, 8), Portal::Outer(String::from("BC")));
+        expected.insert(Coordinate::new(2, 13), Portal::Outer(String::from("DE")));
+        expected.insert(Coordinate::new(2, 15), Portal::Outer(String::from("FG")));
+        expected.insert(Coordinate::new(13, 16), Portal::Outer(String::from("ZZ")));
+        expected.insert(Coordinate::new(9, 6), Portal::Inner(String::from("BC")));
+        expected.insert(Coordinate::new(6, 10), Portal::Inner(String::from("DE")));
+        expected.insert(Coordinate::new(11, 12), Portal::Inner(String::from("FG")));
 
         assert_eq!(nodes, expected);
     }
 
+    #[test]
+    fn missing_portals() {
+        let input = include_str!("example1");
+        assert_eq!(
+            solve_first(&input.replace('Z', "Y")),
+            Err(ParseError::new(
+                1,
+                1,
+                "",
+                "a maze with ZZ on its outside edge"
+            ))
+        );
+        assert_eq!(
+            solve_second(&input.replacen('A', "Q", 2)),
+            Err(ParseError::new(
+                1,
+                1,
+                "",
+                "a maze with AA on its outside edge"
+            ))
+        );
+        let input = "  A  \n  A  \n##.##\n##.##\n  Z  \n  Z  \n";
+        assert_eq!(
+            solve_first(input),
+            Err(ParseError::new(
+                1,
+                1,
+                "",
+                "a maze with a hole in the middle"
+            ))
+        );
+    }
+
     #[test]
     fn example1() {
         let input = include_str!("example1");
-        assert_eq!(solve_first(input), 23);
+        assert_eq!(solve_first(input), Ok(23));
     }
     #[test]
     fn example2() {
         let input = include_str!("example2");
-        assert_eq!(solve_first(input), 58);
+        assert_eq!(solve_first(input), Ok(58));
     }
 
     #[test]
     fn example3() {
         let input = include_str!("example3");
-        assert_eq!(solve_second(input), 396);
+        assert_eq!(solve_second(input), Ok(396));
     }
 
     #[test]
-    fn first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 588);
-    }
+    fn generated() {
+        assert_eq!(generate(3, 20), generate(3, 20));
+        for (size, seed) in [(1, 0), (2, 1), (3, 2), (5, 3)].iter() {
+            let input = generate(*size, *seed);
+            let nodes = parse_nodes(&parse_input(&input).unwrap()).unwrap();
+            let inner = nodes
+                .values()
+                .filter(|p| matches!(p, Portal::Inner(_)))
+                .count();
+            assert_eq!(nodes.len(), 2 + 2 * inner);
 
-    #[test]
-    fn second() {
-        let input = include_str!("input");
-        assert_eq!(solve_second(input), 6834);
+            // the recursive maze can still walk from AA to ZZ without portals
+            let first = solve_first(&input).unwrap();
+            let second = solve_second(&input).unwrap();
+            assert!(
+                first <= second && second < usize::MAX,
+                "{}\n{}",
+                first,
+                input
+            );
+        }
     }
 }
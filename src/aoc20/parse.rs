@@ -1,4 +1,9 @@
-use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::grid::{Bounds, Coordinate, Direction, Grid};
+use crate::parse::ParseError;
+
+const TILE: &str = "a wall #, open passage ., portal letter A-Z or space";
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum Portal {
@@ -6,99 +11,92 @@ pub enum Portal {
     Inner(String),
 }
 
-pub fn parse_input(input: &str) -> BTreeMap<(i32, i32), char> {
-    let mut result = BTreeMap::new();
-    let mut y = 0;
-    for line in input.lines() {
-        let mut x = 0;
-        for c in line.chars() {
-            result.insert((y, x), c);
-            x += 1;
-        }
-        y += 1;
+pub fn parse_input(input: &str) -> Result<Grid<char>, ParseError> {
+    if input.trim().is_empty() {
+        return Err(ParseError::new(1, 1, "", "a maze"));
     }
-    result
+    Grid::parse(input, TILE, |c| match c {
+        '#' | '.' | ' ' | 'A'..='Z' => Some(c),
+        _ => None,
+    })
 }
 
-fn inside_box(position: (i32, i32), top_left: (i32, i32), bottom_right: (i32, i32)) -> bool {
-    position.0 >= top_left.0
-        && position.1 >= top_left.1
-        && position.0 <= bottom_right.0
-        && position.1 <= bottom_right.1
-}
-
-pub fn parse_nodes(map: &BTreeMap<(i32, i32), char>) -> HashMap<(i32, i32), Portal> {
+// Err if there's no hole in the middle, or no AA or ZZ to start and end at
+pub fn parse_nodes(map: &Grid<char>) -> Result<HashMap<Coordinate, Portal>, ParseError> {
     // we can split the maze into three rectangle areas:
     // outer  - empty area containing outer portal names
     // maze   - square containing wall or path values
     // inner  - square containing inner portal names
     // the idea is to scan the outer and inner edges of the maze for open tiles
 
-    // outer bounds is from (0,0) to last coord
-    let outer_bottom_right = *map.iter().last().unwrap().0;
-
-    // maze is (2,2) to outer - 2
-    let maze_top_left = (2, 2);
-    let maze_bottom_right = (outer_bottom_right.0 - 2, outer_bottom_right.1 - 2);
+    // maze is two tiles in from the edge of the map
+    let maze = map.bounds().inset(2);
 
     // find every inner position,
-    // inner_top_left should be first element
-    // and inner_bottom_right should be last element
-    let mut inner_space = map.iter().filter(|(&pos, &c)| {
-        inside_box(pos, maze_top_left, maze_bottom_right) && c != '#' && c != '.'
-    });
-    let inner_top_left = *inner_space.next().unwrap().0;
-    let inner_bottom_right = *inner_space.rev().next().unwrap().0;
+    // inner top left should be first element
+    // and inner bottom right should be last element
+    let mut inner_space = map
+        .iter()
+        .filter(|&(pos, &c)| maze.contains(pos) && c != '#' && c != '.')
+        .map(|(pos, _)| pos);
+    let inner_top_left = inner_space
+        .next()
+        .ok_or_else(|| ParseError::new(1, 1, "", "a maze with a hole in the middle"))?;
+    let inner_bottom_right = inner_space.next_back().unwrap_or(inner_top_left);
+    let inner = Bounds::new(inner_top_left, inner_bottom_right);
 
     let mut result = HashMap::new();
 
-    // outside edge: inside_box(maze_top_left, maze_bottom_right) but not inside_box(maze_top_left + 1, maze_bottom_right - 1)
-    for (&pos, &tile) in map.iter().filter(|(&pos, _)| {
-        inside_box(pos, maze_top_left, maze_bottom_right)
-            && !inside_box(
-                pos,
-                (maze_top_left.0 + 1, maze_top_left.1 + 1),
-                (maze_bottom_right.0 - 1, maze_bottom_right.1 - 1),
-            )
-    }) {
+    // outside edge: inside the maze but not inside the maze inset by one
+    for (pos, &tile) in map
+        .iter()
+        .filter(|&(pos, _)| maze.contains(pos) && !maze.inset(1).contains(pos))
+    {
         if tile == '.' {
-            let name = portal_name(&map, pos);
+            let name = portal_name(map, pos);
             result.insert(pos, Portal::Outer(name));
         }
     }
 
-    // inside edge: inside_box(inner_top_left - 1, inner_bottom_right + 1) but not inside_box(inner_top_left, inner_top_right)
-    for (&pos, &tile) in map.iter().filter(|(&pos, _)| {
-        inside_box(
-            pos,
-            (inner_top_left.0 - 1, inner_top_left.1 - 1),
-            (inner_bottom_right.0 + 1, inner_bottom_right.1 + 1),
-        ) && !inside_box(pos, inner_top_left, inner_bottom_right)
-    }) {
+    // inside edge: inside the inner space grown by one but not inside the inner space
+    for (pos, &tile) in map
+        .iter()
+        .filter(|&(pos, _)| inner.inset(-1).contains(pos) && !inner.contains(pos))
+    {
         if tile == '.' {
-            let name = portal_name(&map, pos);
+            let name = portal_name(map, pos);
             result.insert(pos, Portal::Inner(name));
         }
     }
-    result
+
+    for end in ["AA", "ZZ"].iter() {
+        if !result
+            .values()
+            .any(|p| *p == Portal::Outer(end.to_string()))
+        {
+            let expected = format!("a maze with {} on its outside edge", end);
+            return Err(ParseError::new(1, 1, "", &expected));
+        }
+    }
+    Ok(result)
 }
 
-fn portal_name(map: &BTreeMap<(i32, i32), char>, pos: (i32, i32)) -> String {
-    let dirs: &[(i32, i32)] = &[
-        //left, up (reverse order)
-        (-1, 0),
-        (0, -1),
+fn portal_name(map: &Grid<char>, pos: Coordinate) -> String {
+    let dirs = [
+        // left, up (reverse order)
+        Direction::Left,
+        Direction::Up,
         // right, down (normal order)
-        (1, 0),
-        (0, 1),
+        Direction::Right,
+        Direction::Down,
     ];
     let mut result = Vec::with_capacity(2);
     for (idx, &dir) in dirs.iter().enumerate() {
-        let next = (pos.0 + dir.0, pos.1 + dir.1);
-        if let Some(&c1) = map.get(&next) {
+        let next = pos.step(dir);
+        if let Some(&c1) = map.get(next) {
             if c1.is_ascii_alphabetic() {
                 result.push(c1);
-                if let Some(&c2) = map.get(&(next.0 + dir.0, next.1 + dir.1)) {
+                if let Some(&c2) = map.get(next.step(dir)) {
                     result.push(c2);
                 }
                 if idx < 2 {
@@ -113,15 +111,14 @@ fn portal_name(map: &BTreeMap<(i32, i32), char>, pos: (i32, i32)) -> String {
 }
 
 pub fn parse_graph(
-    map: &BTreeMap<(i32, i32), char>,
-    nodes: &HashMap<(i32, i32), Portal>,
+    map: &Grid<char>,
+    nodes: &HashMap<Coordinate, Portal>,
 ) -> HashMap<Portal, HashMap<Portal, usize>> {
     let mut result = HashMap::with_capacity(nodes.len());
     for (_, portal) in nodes.iter() {
         result.insert(portal.clone(), HashMap::new());
     }
 
-    let dirs = &[(0, -1), (0, 1), (-1, 0), (1, 0)];
     for (portal_pos, portal) in nodes.iter() {
         let mut visited = HashSet::new();
         let mut queue = VecDeque::new();
@@ -129,9 +126,7 @@ pub fn parse_graph(
         visited.insert(*portal_pos);
 
         while let Some((steps, pos)) = queue.pop_front() {
-            for dir in dirs.iter() {
-                let next = (pos.0 + dir.0, pos.1 + dir.1);
-
+            for (next, &c) in map.neighbours(pos) {
                 if visited.contains(&next) {
                     continue;
                 }
@@ -146,11 +141,9 @@ pub fn parse_graph(
                     continue;
                 }
 
-                if let Some(&c) = map.get(&next) {
-                    if c == '.' {
-                        queue.push_back((steps + 1, next));
-                        visited.insert(next);
-                    }
+                if c == '.' {
+                    queue.push_back((steps + 1, next));
+                    visited.insert(next);
                 }
             }
         }
@@ -175,8 +168,17 @@ pub fn parse_graph(
 #[test]
 fn portal() {
     let input = include_str!("example1");
-    let map = parse_input(input);
-    assert_eq!(portal_name(&map, (2, 9)), "AA");
+    let map = parse_input(input).unwrap();
+    assert_eq!(portal_name(&map, Coordinate::new(9, 2)), "AA");
 
-    assert_eq!(portal_name(&map, (8, 2)), "BC");
+    assert_eq!(portal_name(&map, Coordinate::new(2, 8)), "BC");
+}
+
+#[test]
+fn parse_error() {
+    assert_eq!(
+        parse_input("  #.#\n  #a#\n"),
+        Err(ParseError::new(2, 4, "a", TILE))
+    );
+    assert_eq!(parse_input(""), Err(ParseError::new(1, 1, "", "a maze")));
 }
@@ -1,3 +1,6 @@
+use crate::log::log;
+use crate::parse::{self, ParseError};
+
 fn springdroid(intcode: &mut Intcode, script: &str) -> i64 {
     for c in script.chars() {
         intcode.input.push_back(c as i64);
@@ -5,11 +8,13 @@ fn springdroid(intcode: &mut Intcode, script: &str) -> i64 {
     while intcode.finished != true {
         intcode.run_until_output();
     }
-    for &i in intcode.output.iter() {
-        if i <= u8::MAX as i64 {
-            print!("{}", i as u8 as char);
-        }
-    }
+    let transcript: String = intcode
+        .output
+        .iter()
+        .filter(|&&i| i <= u8::MAX as i64)
+        .map(|&i| i as u8 as char)
+        .collect();
+    log!(Debug, "droid reports:\n{}", transcript.trim_end());
     if let Some(num) = intcode.output.back() {
         *num
     } else {
@@ -17,8 +22,8 @@ fn springdroid(intcode: &mut Intcode, script: &str) -> i64 {
     }
 }
 
-pub fn solve_first(input: &str) -> i64 {
-    let memory = read_codes(input);
+pub fn solve_first(input: &str) -> Result<i64, ParseError> {
+    let memory = read_codes(input)?;
     let mut intcode = Intcode::new(memory);
 
     /*
@@ -37,11 +42,11 @@ NOT A T
 OR T J 
 WALK
 ";
-    springdroid(&mut intcode, script)
+    Ok(springdroid(&mut intcode, script))
 }
 
-pub fn solve_second(input: &str) -> i64 {
-    let memory = read_codes(input);
+pub fn solve_second(input: &str) -> Result<i64, ParseError> {
+    let memory = read_codes(input)?;
     let mut intcode = Intcode::new(memory);
 
     // same idea as previous, but we're also checking if H is ground before jumping
@@ -56,37 +61,14 @@ NOT A T
 OR T J 
 RUN
 ";
-    springdroid(&mut intcode, script)
-}
-
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn first() {
-        let input = include_str!("input");
-        let result = solve_first(input);
-        assert_eq!(result, 19358262);
-    }
-    #[test]
-    fn second() {
-        let input = include_str!("input");
-        let result = solve_second(input);
-        assert_eq!(result, 1142686742);
-    }
+    Ok(springdroid(&mut intcode, script))
 }
 
 // ------ INTCODE (same as day 15) -----------
 use std::collections::VecDeque;
 
-fn read_codes(input: &str) -> Vec<i64> {
-    let mut result = Vec::new();
-
-    for number_str in input.trim().split(',') {
-        result.push(number_str.parse().unwrap());
-    }
-    result
+fn read_codes(input: &str) -> Result<Vec<i64>, ParseError> {
+    parse::integers(input)
 }
 
 // returns (A, B, C, DE)
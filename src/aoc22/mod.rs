@@ -1,5 +1,48 @@
 use regex::Regex;
 
+use crate::number;
+use crate::params::{at_least, params, Checked};
+use crate::parse::ParseError;
+use crate::random::Rng;
+
+const ACTION: &str = "deal into new stack, cut N or deal with increment N";
+
+params! {
+    pub struct Params {
+        // part 1 tracks a card through one shuffle
+        deck: i128 = 10007,
+        card: i128 = 2019,
+        // part 2 finds the card at a position after many shuffles of a
+        // deck with a prime number of cards
+        big_deck: i128 = 119315717514047,
+        times: u128 = 101741582076661,
+        position: i128 = 2020,
+    }
+    check(params) {
+        at_least("card", params.card, 0)?;
+        if params.card >= params.deck {
+            return Err(format!(
+                "invalid value '{}' for card, expected one of the deck's {}",
+                params.card, params.deck
+            ));
+        }
+        if !number::is_prime(params.big_deck) {
+            return Err(format!(
+                "invalid value '{}' for big_deck, expected a prime",
+                params.big_deck
+            ));
+        }
+        at_least("position", params.position, 0)?;
+        if params.position >= params.big_deck {
+            return Err(format!(
+                "invalid value '{}' for position, expected one of the big deck's {}",
+                params.position, params.big_deck
+            ));
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Eq, PartialEq)]
 enum Action {
     Increment(i128),
@@ -7,23 +50,33 @@ enum Action {
     Deal,
 }
 
-fn parse_actions(input: &str) -> Vec<Action> {
+fn parse_actions(input: &str) -> Result<Vec<Action>, ParseError> {
     let mut result = Vec::new();
-    let r1 = Regex::new(r"deal with increment (\d+)").unwrap();
-    let r2 = Regex::new(r"cut (-?\d+)").unwrap();
-    let r3 = Regex::new("deal into new stack").unwrap();
-
-    for line in input.lines() {
-        if let Some(c) = r1.captures(line) {
-            result.push(Action::Increment(c[1].parse().unwrap()));
-        } else if let Some(c) = r2.captures(line) {
-            result.push(Action::Cut(c[1].parse().unwrap()));
-        } else if let Some(_) = r3.captures(line) {
-            result.push(Action::Deal);
+    let r1 = Regex::new(r"^deal with increment (\d+)$").unwrap();
+    let r2 = Regex::new(r"^cut (-?\d+)$").unwrap();
+    let r3 = Regex::new("^deal into new stack$").unwrap();
+
+    for (idx, line) in input.lines().enumerate() {
+        let action = line.trim();
+        if action.is_empty() {
+            continue;
+        }
+        let parsed = if let Some(c) = r1.captures(action) {
+            c[1].parse().ok().map(Action::Increment)
+        } else if let Some(c) = r2.captures(action) {
+            c[1].parse().ok().map(Action::Cut)
+        } else if r3.is_match(action) {
+            Some(Action::Deal)
+        } else {
+            None
+        };
+        match parsed {
+            Some(action) => result.push(action),
+            None => return Err(ParseError::within(idx, line, action, ACTION)),
         }
     }
 
-    result
+    Ok(result)
 }
 
 // what is card's index after actions
@@ -58,47 +111,20 @@ fn rev_card_cut(res: i128, size: i128, cut: i128) -> i128 {
     (res + cut + size) % size
 }
 
-fn egcd(a: i128, b: i128) -> (i128, i128, i128) {
-    if a == 0 {
-        (b, 0, 1)
-    } else {
-        let (g, y, x) = egcd(b % a, a);
-        (g, x - (b / a) * y, y)
-    }
-}
-
-fn modinv(a: i128, m: i128) -> i128 {
-    let (g, x, _) = egcd(a, m);
-    if g != 1 {
-        panic!("Modular inverse does not exist");
-    }
-    x % m
-}
-
-fn mod_power(mut a: i128, mut b: i128, p: i128) -> i128 {
-    let mut res = 1;
-
-    a = a % p;
-    if a == 0 {
-        return 0;
-    }
-    while b > 0 {
-        if b & 1 == 1 {
-            res = (res * a) % p
-        }
-        b = b >> 1;
-        a = (a * a) % p
-    }
-    res
+// deck sizes are prime, so any increment short of the deck size has an inverse
+fn rev_card_increment(res: i128, size: i128, incr: i128) -> i128 {
+    let inverse = number::mod_inv(incr, size).expect("increment coprime with deck size");
+    number::mul_mod(inverse, res, size)
 }
 
-fn rev_card_increment(res: i128, size: i128, incr: i128) -> i128 {
-    (modinv(incr, size) * res) % size
+pub fn solve_first_with(input: &str, params: &Params) -> Checked<i128> {
+    params.validate()?;
+    Ok(parse_actions(input).map(|actions| card_find(params.card, params.deck, &actions)))
 }
 
-pub fn solve_first(input: &str) -> u16 {
-    let actions = parse_actions(input);
-    card_find(2019, 10007, &actions) as u16
+pub fn solve_first(input: &str) -> Result<u16, ParseError> {
+    let card = solve_first_with(input, &Params::default()).expect("the defaults are valid")?;
+    Ok(card as u16)
 }
 
 fn reverse_apply(mut res: i128, size: i128, actions: &[Action]) -> i128 {
@@ -112,45 +138,75 @@ fn reverse_apply(mut res: i128, size: i128, actions: &[Action]) -> i128 {
     res
 }
 
-fn mul_mod(mut a: i128, mut b: i128, m: i128) -> i128 {
-    if a >= m {
-        a %= m;
-    }
-    if b >= m {
-        b %= m;
-    }
-    let x = a;
-    let c = x * b / m;
-    let r = (a * b - c * m) % m;
-    if r < 0 {
-        r + m
+// the card at `position` after shuffling `times` times, the deck size prime
+fn card_at(position: i128, size: i128, times: u128, actions: &[Action]) -> i128 {
+    // https://www.reddit.com/r/adventofcode/comments/ee0rqi/2019_day_22_solutions/fbnifwk/
+    // the reverse shuffle is linear, card = a * position + b
+    let b = reverse_apply(0, size, actions);
+    let a = (reverse_apply(1, size, actions) - b).rem_euclid(size);
+
+    // after n shuffles: a^n * x + (a^n - 1) / (a - 1) * b, or x + n * b
+    // when a is 1 and the shuffle only cuts
+    let power = number::pow_mod(a, times, size);
+    let series = if a == 1 {
+        (times % size as u128) as i128
     } else {
-        r
-    }
+        let inverse = number::mod_inv(a - 1, size).expect("deck size is prime");
+        number::mul_mod(power - 1, inverse, size)
+    };
+    number::add_mod(
+        number::mul_mod(power, position, size),
+        number::mul_mod(series, b, size),
+        size,
+    )
 }
 
-pub fn solve_second(input: &str) -> i128 {
-    let actions = parse_actions(input);
-    let deck_size = 119315717514047;
-
-    // https://www.reddit.com/r/adventofcode/comments/ee0rqi/2019_day_22_solutions/fbnifwk/
-    let x = 2020;
-    let y = reverse_apply(x, deck_size, &actions);
-    let z = reverse_apply(y, deck_size, &actions);
-
-    let a = ((y - z) * modinv(x - y, deck_size)) % deck_size;
-    let b = (y - a * x) % deck_size;
+// undoing the shuffle needs every increment to have an inverse, so none can
+// be a multiple of the deck size
+fn read_reversible(input: &str, size: i128) -> Result<Vec<Action>, ParseError> {
+    let actions = parse_actions(input)?;
+    let lines = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    for ((idx, line), action) in lines.zip(&actions) {
+        if let Action::Increment(incr) = action {
+            if incr % size == 0 {
+                let expected = format!("an increment that isn't a multiple of {}", size);
+                return Err(ParseError::within(idx, line, line.trim(), &expected));
+            }
+        }
+    }
+    Ok(actions)
+}
 
-    let times = 101741582076661;
+pub fn solve_second_with(input: &str, params: &Params) -> Checked<i128> {
+    params.validate()?;
+    Ok(read_reversible(input, params.big_deck)
+        .map(|actions| card_at(params.position, params.big_deck, params.times, &actions)))
+}
 
-    let one = mod_power(a, times, deck_size) * x;
-    let two = mod_power(a, times, deck_size) - 1;
-    let three = modinv(a - 1, deck_size);
+pub fn solve_second(input: &str) -> Result<i128, ParseError> {
+    solve_second_with(input, &Params::default()).expect("the defaults are valid")
+}
 
-    // mul_mod to avoid overflow
-    // https://www.reddit.com/r/adventofcode/comments/ee0rqi/2019_day_22_solutions/fey51nx/
-    (one + mul_mod(mul_mod(two, three, deck_size), b, deck_size)) % deck_size
+// `size` random techniques; cuts and increments stay below the part 1 deck
+// size, so every increment has an inverse for both (prime) deck sizes
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut result = String::new();
+    for _ in 0..size {
+        let line = match rng.below(3) {
+            0 => String::from("deal into new stack"),
+            1 => format!("cut {}", rng.range(-10006..=10006)),
+            _ => format!("deal with increment {}", rng.range(1..=10006)),
+        };
+        result.push_str(&line);
+        result.push('\n');
+    }
+    result
 }
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -163,19 +219,52 @@ mod test {
 
         let result = parse_actions(input);
         let expected = vec![Action::Deal, Action::Cut(-2203), Action::Increment(74)];
-        assert_eq!(result, expected);
+        assert_eq!(result, Ok(expected));
+    }
+
+    #[test]
+    fn parse_error() {
+        let input = "deal into new stack\n  cut 3 cards\ncut 2";
+        assert_eq!(
+            parse_actions(input),
+            Err(ParseError::new(2, 3, "cut 3 cards", ACTION))
+        );
     }
 
     #[test]
-    fn first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 7096);
+    fn bad_params() {
+        let input = "cut 3\ndeal with increment 7";
+        let params = Params {
+            deck: 10,
+            ..Params::default()
+        };
+        assert!(solve_first_with(input, &params).is_err());
+        let params = Params {
+            big_deck: 10,
+            ..Params::default()
+        };
+        assert!(solve_second_with(input, &params).is_err());
+        // 7 has no inverse in a deck of 7
+        let params = Params {
+            big_deck: 7,
+            position: 3,
+            ..Params::default()
+        };
+        assert_eq!(
+            solve_second_with(input, &params),
+            Ok(Err(ParseError::new(
+                2,
+                1,
+                "deal with increment 7",
+                "an increment that isn't a multiple of 7"
+            )))
+        );
     }
 
     #[test]
     fn example1() {
         let input = include_str!("example1");
-        let actions = parse_actions(input);
+        let actions = parse_actions(input).unwrap();
         assert_eq!(card_find(1, 10, &actions), 7);
         assert_eq!(card_find(7, 10, &actions), 9);
         assert_eq!(card_find(4, 10, &actions), 8);
@@ -185,13 +274,83 @@ mod test {
     #[test]
     fn example2() {
         let input = include_str!("example2");
-        let actions = parse_actions(input);
+        let actions = parse_actions(input).unwrap();
         assert_eq!(card_find(3, 10, &actions), 0);
         assert_eq!(card_find(6, 10, &actions), 9);
         assert_eq!(card_find(1, 10, &actions), 4);
         //expected = vec![3, 0, 7, 4, 1, 8, 5, 2, 9, 6];
     }
 
+    #[test]
+    fn test_generate() {
+        let size = 10007;
+        for seed in 0..3 {
+            let actions = parse_actions(&generate(100, seed)).unwrap();
+            // the shuffle is a permutation and reverse_apply undoes it
+            let mut seen = vec![false; size as usize];
+            for card in 0..size {
+                let position = card_find(card, size, &actions);
+                assert!(!seen[position as usize]);
+                seen[position as usize] = true;
+                assert_eq!(reverse_apply(position, size, &actions), card);
+            }
+        }
+    }
+
+    // the deck itself, shuffled one technique at a time
+    fn naive_shuffle(size: usize, times: usize, actions: &[Action]) -> Vec<usize> {
+        let mut deck: Vec<usize> = (0..size).collect();
+        for _ in 0..times {
+            for action in actions {
+                match *action {
+                    Action::Deal => deck.reverse(),
+                    Action::Cut(cut) => deck.rotate_left(cut.rem_euclid(size as i128) as usize),
+                    Action::Increment(incr) => {
+                        let mut next = vec![0; size];
+                        for (idx, &card) in deck.iter().enumerate() {
+                            next[idx * incr as usize % size] = card;
+                        }
+                        deck = next;
+                    }
+                }
+            }
+        }
+        deck
+    }
+
+    #[test]
+    fn test_card_at() {
+        let mut rng = Rng::new(22);
+        for _ in 0..100 {
+            let size = *rng.pick(&[2, 3, 5, 7, 11, 13, 101, 1009]);
+            let actions: Vec<Action> = (0..rng.range(0..=8))
+                .map(|_| match rng.below(3) {
+                    0 => Action::Deal,
+                    1 => Action::Cut(rng.range(1 - size..=size - 1) as i128),
+                    _ => Action::Increment(rng.range(1..=size - 1) as i128),
+                })
+                .collect();
+            let times = rng.range(0..=20) as usize;
+            let size = size as usize;
+
+            let deck = naive_shuffle(size, times, &actions);
+            for (position, &card) in deck.iter().enumerate() {
+                let found = card_at(position as i128, size as i128, times as u128, &actions);
+                assert_eq!(
+                    found, card as i128,
+                    "{:?} x {} on {} cards",
+                    actions, times, size
+                );
+                if times == 1 {
+                    assert_eq!(
+                        card_find(card as i128, size as i128, &actions),
+                        position as i128
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn single_cut() {
         assert_eq!(card_cut(0, 10, 3), 7);
@@ -199,9 +358,4 @@ mod test {
         assert_eq!(card_cut(6, 10, -4), 0);
         assert_eq!(card_cut(0, 10, -4), 4);
     }
-    #[test]
-    fn second() {
-        let input = include_str!("input");
-        assert_eq!(solve_second(input), 27697279941366);
-    }
 }
@@ -1,17 +1,29 @@
-use crossbeam::{channel::unbounded, Receiver, Sender};
-use std::thread;
+use crossbeam::{
+    channel::{unbounded, RecvTimeoutError},
+    Receiver, Sender,
+};
+use std::convert::TryFrom;
+use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
 
+use crate::params::{at_least, params, Checked};
+use crate::parse::{self, ParseError};
+
+params! {
+    pub struct Params {
+        computers: usize = 50,
+    }
+    check(params) {
+        at_least("computers", params.computers, 1)
+    }
+}
+
 // starts every computer in its own thread,
 // receives and sends packets to intcode threads
 // finishes when it receives a packet addressed to 255
 // returns the y value of that packet
-fn run_network_255(network: Network) -> i64 {
-    for mut computer in network.computers.into_iter() {
-        thread::spawn(move || {
-            computer.run();
-        });
-    }
+fn run_network_255(mut network: Network) -> Result<i64, ParseError> {
+    network.start();
 
     loop {
         for r in network.receive.iter() {
@@ -20,11 +32,9 @@ fn run_network_255(network: Network) -> i64 {
                 let y = r.recv().unwrap();
 
                 if to == 255 {
-                    return y;
+                    return Ok(y);
                 }
-
-                network.send[to as usize].send(x).unwrap();
-                network.send[to as usize].send(y).unwrap();
+                network.forward(to, x, y)?;
             }
         }
     }
@@ -33,12 +43,8 @@ fn run_network_255(network: Network) -> i64 {
 // forwards last nat packet to computer 0,
 // keeps track of last nat packet y value sent, stop when it repeats
 // returns the repeated y value
-fn run_network_nat(network: Network) -> i64 {
-    for mut computer in network.computers.into_iter() {
-        thread::spawn(move || {
-            computer.run();
-        });
-    }
+fn run_network_nat(mut network: Network) -> Result<i64, ParseError> {
+    network.start();
     let mut nat_x = 0;
     let mut nat_y = 0;
 
@@ -55,8 +61,7 @@ fn run_network_nat(network: Network) -> i64 {
                     nat_x = x;
                     nat_y = y;
                 } else {
-                    network.send[to as usize].send(x).unwrap();
-                    network.send[to as usize].send(y).unwrap();
+                    network.forward(to, x, y)?;
                 }
                 last_active = Instant::now();
             }
@@ -64,7 +69,7 @@ fn run_network_nat(network: Network) -> i64 {
         // if there's no activity, send nat packet
         if last_active.elapsed() > Duration::from_millis(50) {
             if last_sent == nat_y {
-                return last_sent;
+                return Ok(last_sent);
             }
             last_sent = nat_y;
             network.send[0].send(nat_x).unwrap();
@@ -78,6 +83,48 @@ struct Network {
     send: Vec<Sender<i64>>,
     receive: Vec<Receiver<i64>>,
     computers: Vec<Intcode>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Network {
+    // starts every computer in its own thread
+    fn start(&mut self) {
+        for mut computer in self.computers.drain(..) {
+            self.threads.push(thread::spawn(move || computer.run()));
+        }
+    }
+
+    // passes a packet on, Err if nobody on the network has that address
+    fn forward(&self, to: i64, x: i64, y: i64) -> Result<(), ParseError> {
+        let computer = usize::try_from(to).ok().and_then(|to| self.send.get(to));
+        match computer {
+            Some(computer) => {
+                computer.send(x).unwrap();
+                computer.send(y).unwrap();
+                Ok(())
+            }
+            None => {
+                let expected = format!(
+                    "a program sending to computers 0 to {} or 255",
+                    self.send.len() - 1
+                );
+                Err(ParseError::new(1, 1, &to.to_string(), &expected))
+            }
+        }
+    }
+}
+
+// closing the channels halts every computer at its next input or output,
+// so nothing is left running once the answer is in
+impl Drop for Network {
+    fn drop(&mut self) {
+        self.send.clear();
+        self.receive.clear();
+        for thread in self.threads.drain(..) {
+            // a computer that panicked has stopped all the same
+            let _ = thread.join();
+        }
+    }
 }
 
 fn create_network(n: usize, memory: Vec<i64>) -> Network {
@@ -109,47 +156,36 @@ fn create_network(n: usize, memory: Vec<i64>) -> Network {
         send,
         receive,
         computers,
+        threads: Vec::new(),
     }
 }
 
-pub fn solve_first(input: &str) -> i64 {
-    let memory = read_codes(input);
-    let network = create_network(50, memory);
-    run_network_255(network)
+pub fn solve_first_with(input: &str, params: &Params) -> Checked<i64> {
+    params.validate()?;
+    Ok(read_codes(input)
+        .and_then(|memory| run_network_255(create_network(params.computers, memory))))
 }
 
-pub fn solve_second(input: &str) -> i64 {
-    let memory = read_codes(input);
-    let network = create_network(50, memory);
-    run_network_nat(network)
+pub fn solve_first(input: &str) -> Result<i64, ParseError> {
+    solve_first_with(input, &Params::default()).expect("the defaults are valid")
 }
-#[cfg(test)]
-mod test {
-    use super::*;
-    #[test]
-    fn first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 23815);
-    }
 
-    #[test]
-    fn second() {
-        let input = include_str!("input");
-        assert_eq!(solve_second(input), 16666);
-    }
+pub fn solve_second_with(input: &str, params: &Params) -> Checked<i64> {
+    params.validate()?;
+    Ok(read_codes(input)
+        .and_then(|memory| run_network_nat(create_network(params.computers, memory))))
+}
+
+pub fn solve_second(input: &str) -> Result<i64, ParseError> {
+    solve_second_with(input, &Params::default()).expect("the defaults are valid")
 }
 
 // ------ INTCODE ---
 // input and output are crossbeam channels
 // timeouts for 5ms on no input with recv_timeout(5ms)
 // and then continues with -1
-fn read_codes(input: &str) -> Vec<i64> {
-    let mut result = Vec::new();
-
-    for number_str in input.trim().split(',') {
-        result.push(number_str.parse().unwrap());
-    }
-    result
+fn read_codes(input: &str) -> Result<Vec<i64>, ParseError> {
+    parse::integers(input)
 }
 
 // returns (A, B, C, DE)
@@ -269,14 +305,23 @@ impl Intcode {
                 // wait for input, then continue with value -1
                 let input_value = match self.input.recv_timeout(Duration::from_millis(5)) {
                     Ok(value) => value,
-                    _ => -1,
+                    Err(RecvTimeoutError::Timeout) => -1,
+                    // the network has shut down
+                    Err(RecvTimeoutError::Disconnected) => {
+                        self.finished = true;
+                        return 99;
+                    }
                 };
                 self.save_value(pointer + 1, arg1_mode, input_value);
                 pointer + 2
             }
             4 => {
                 let v = self.load_value(pointer + 1, arg1_mode);
-                self.output.send(v).unwrap();
+                if self.output.send(v).is_err() {
+                    // nobody's listening any more
+                    self.finished = true;
+                    return 99;
+                }
                 pointer + 2
             }
             5 => {
@@ -337,3 +382,18 @@ impl Intcode {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bad_params() {
+        let params = Params { computers: 0 };
+        assert!(solve_first_with("99", &params).is_err());
+        // the puzzle's computers send to each other, so one alone can't run it
+        let params = Params { computers: 1 };
+        let result = solve_first_with(include_str!("input"), &params).unwrap();
+        assert!(result.is_err());
+    }
+}
@@ -1,16 +1,40 @@
-pub fn read_input(input: &str) -> u32 {
-    let mut result = 0;
-    let mut idx = 0;
+use crate::grid::Grid;
+use crate::parse::ParseError;
 
-    for line in input.lines() {
-        for c in line.chars() {
-            if c == '#' {
-                result += 1 << idx;
-            }
-            idx += 1;
+pub const TILE: &str = "a bug # or empty tile .";
+
+const SHAPE: &str = "5 rows of 5 tiles";
+
+// the 5x5 scan, a short, long or ragged one is reported at the first row
+// that doesn't fit
+pub fn read_grid(input: &str) -> Result<Grid<bool>, ParseError> {
+    let lines: Vec<&str> = input.trim_end_matches('\n').lines().collect();
+    for (idx, line) in lines.iter().enumerate() {
+        if idx == 5 || line.chars().count() != 5 {
+            return Err(ParseError::new(idx + 1, 1, line, SHAPE));
         }
     }
-    result
+    if lines.len() < 5 {
+        return Err(ParseError::new(lines.len() + 1, 1, "", SHAPE));
+    }
+    Grid::parse(input, TILE, |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })
+}
+
+// one bit per tile, in reading order
+pub fn read_input(input: &str) -> Result<u32, ParseError> {
+    let grid = read_grid(input)?;
+
+    let mut result = 0;
+    for (idx, (_, &bug)) in grid.iter().enumerate() {
+        if bug {
+            result += 1 << idx;
+        }
+    }
+    Ok(result)
 }
 
 pub fn step(state: u32) -> u32 {
@@ -56,24 +80,59 @@ fn neighbours(state: u32, idx: u32) -> u32 {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::grid::Coordinate;
+    use crate::random::Rng;
+    use std::collections::HashSet;
+
+    // infested tiles as a set, each tile counting its neighbours in the set
+    fn naive_step(bugs: &HashSet<Coordinate>) -> HashSet<Coordinate> {
+        let mut next = HashSet::new();
+        for y in 0..5 {
+            for x in 0..5 {
+                let tile = Coordinate::new(x, y);
+                let n = tile
+                    .neighbours()
+                    .iter()
+                    .filter(|c| bugs.contains(c))
+                    .count();
+                if n == 1 || (n == 2 && !bugs.contains(&tile)) {
+                    next.insert(tile);
+                }
+            }
+        }
+        next
+    }
+
+    fn bits(bugs: &HashSet<Coordinate>) -> u32 {
+        bugs.iter().map(|c| 1 << (c.y * 5 + c.x)).sum()
+    }
+
+    // fills the rest of the 5x5 scan with empty tiles
+    fn padded(input: &str) -> String {
+        let rows: Vec<&str> = input.lines().collect();
+        (0..5)
+            .map(|y| format!("{:.<5}\n", rows.get(y).unwrap_or(&"")))
+            .collect()
+    }
+
     #[test]
     fn input_simple() {
         let input = ".";
-        assert_eq!(read_input(input), 0);
+        assert_eq!(read_input(&padded(input)).unwrap(), 0);
 
         let input2 = "#";
-        assert_eq!(read_input(input2), 1);
+        assert_eq!(read_input(&padded(input2)).unwrap(), 1);
 
         let input3 = "##";
-        assert_eq!(read_input(input3), 3);
+        assert_eq!(read_input(&padded(input3)).unwrap(), 3);
 
         let input4 = "#.#";
-        assert_eq!(read_input(input4), 5);
+        assert_eq!(read_input(&padded(input4)).unwrap(), 5);
     }
     #[test]
     fn input() {
         let input = "#...#\n....#";
-        assert_eq!(read_input(input), 1 + 16 + 512);
+        assert_eq!(read_input(&padded(input)).unwrap(), 1 + 16 + 512);
     }
 
     #[test]
@@ -88,7 +147,7 @@ mod test {
     #[test]
     fn neighbours_normal() {
         let input = include_str!("example1");
-        let state = read_input(input);
+        let state = read_input(input).unwrap();
 
         assert_eq!(neighbours(state, 0), 1);
         assert_eq!(neighbours(state, 9), 3);
@@ -97,7 +156,52 @@ mod test {
     #[test]
     fn state_is_biodiversity() {
         let input = ".....\n.....\n.....\n#....\n.#...";
-        let state = read_input(input);
+        let state = read_input(input).unwrap();
         assert_eq!(state, 2129920);
     }
+
+    #[test]
+    fn parse_error() {
+        assert_eq!(
+            read_input(&padded("#.#\n.x.\n")),
+            Err(ParseError::new(2, 2, "x", TILE))
+        );
+        // cut off, ragged and too long
+        assert_eq!(
+            read_input("#....\n.....\n"),
+            Err(ParseError::new(3, 1, "", SHAPE))
+        );
+        assert_eq!(
+            read_input("#....\n.....\n...\n.....\n....."),
+            Err(ParseError::new(3, 1, "...", SHAPE))
+        );
+        let input = padded("#") + "..#..\n";
+        assert_eq!(
+            read_input(&input),
+            Err(ParseError::new(6, 1, "..#..", SHAPE))
+        );
+    }
+
+    #[test]
+    fn test_step() {
+        let mut rng = Rng::new(24);
+        for _ in 0..200 {
+            let state = rng.next_u64() as u32 & ((1 << 25) - 1);
+            let mut bugs: HashSet<Coordinate> = (0..25)
+                .filter(|idx| state >> idx & 1 == 1)
+                .map(|idx| Coordinate::new(idx % 5, idx / 5))
+                .collect();
+            assert_eq!(bits(&bugs), state);
+
+            // and on to the first repeat
+            let mut current = state;
+            let mut seen = HashSet::new();
+            while seen.insert(current) {
+                let next = naive_step(&bugs);
+                assert_eq!(step(current), bits(&next), "after {:025b}", current);
+                current = step(current);
+                bugs = next;
+            }
+        }
+    }
 }
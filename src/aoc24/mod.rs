@@ -3,25 +3,75 @@ mod second;
 
 use std::collections::BTreeSet;
 
-pub fn solve_first(input: &str) -> u32 {
+use crate::grid::{Coordinate, Grid};
+use crate::params::{params, Checked};
+use crate::parse::ParseError;
+use crate::record::Recorder;
+use crate::render::{Cell, Picture, Rgb};
+
+params! {
+    pub struct Params {
+        // part 2 only, part 1 runs until a layout repeats
+        minutes: usize = 200,
+    }
+}
+
+fn picture(state: u32) -> Picture {
+    let mut grid = Grid::new(5, 5, false);
+    for idx in 0..25 {
+        let coord = Coordinate::new(idx % 5, idx / 5);
+        grid.set(coord, state >> idx & 1 == 1);
+    }
+    Picture::from_grid(&grid, |&bug| {
+        if bug {
+            Cell::new('#', Rgb::GREEN)
+        } else {
+            Cell::new('.', Rgb::BLACK)
+        }
+    })
+}
+
+// the first layout that appears twice
+pub fn render(input: &str) -> Result<Picture, ParseError> {
+    Ok(picture(solve_first(input)?))
+}
+
+// every minute until a layout appears twice
+pub fn record(input: &str, recorder: &mut Recorder) -> Result<(), ParseError> {
+    let mut states = BTreeSet::new();
+    let mut current = first::read_input(input)?;
+    while states.insert(current) && !recorder.is_full() {
+        recorder.offer(|| picture(current));
+        current = first::step(current);
+    }
+    recorder.offer(|| picture(current));
+    Ok(())
+}
+
+pub fn solve_first(input: &str) -> Result<u32, ParseError> {
     let mut states = BTreeSet::new();
 
-    let mut current = first::read_input(input);
+    let mut current = first::read_input(input)?;
 
     // insert returns false when value is already in the set
     while states.insert(current) != false {
         current = first::step(current);
     }
-    current
+    Ok(current)
 }
 
-pub fn solve_second(input: &str) -> u32 {
-    let mut grid = second::read_input(input);
+pub fn solve_second_with(input: &str, params: &Params) -> Checked<u32> {
+    params.validate()?;
+    Ok(second::read_input(input).map(|mut levels| {
+        for _ in 0..params.minutes {
+            levels = second::step(levels);
+        }
+        levels.len() as u32
+    }))
+}
 
-    for _ in 0..200 {
-        grid = second::step(grid);
-    }
-    grid.len() as u32
+pub fn solve_second(input: &str) -> Result<u32, ParseError> {
+    solve_second_with(input, &Params::default()).expect("the defaults are valid")
 }
 #[cfg(test)]
 mod test {
@@ -30,17 +80,8 @@ mod test {
     #[test]
     fn example() {
         let input = include_str!("example1");
-        assert_eq!(solve_first(input), 2129920);
-    }
-
-    #[test]
-    fn first() {
-        let input = include_str!("input");
-        assert_eq!(solve_first(input), 1151290);
-    }
-    #[test]
-    fn second() {
-        let input = include_str!("input");
-        assert_eq!(solve_second(input), 1953);
+        assert_eq!(solve_first(input), Ok(2129920));
+        let params = Params { minutes: 10 };
+        assert_eq!(solve_second_with(input, &params), Ok(Ok(99)));
     }
 }
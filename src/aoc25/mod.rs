@@ -2,6 +2,8 @@ use std::io;
 
 use regex::Regex;
 
+use crate::parse::{self, ParseError};
+
 // solved manually by drawing the map and picking up safe items
 // unsafe items to pick up are:
 // infinite loop (heh..), escape pod, photons, giant electromagnet, molten lava
@@ -11,9 +13,9 @@ use regex::Regex;
 // - sand   (WW)
 // - wreath (WWSEN)
 // finally, exit is at (WWNEE)
-// cargo run --release -- src/aoc25/input < src/aoc25/solution
-pub fn solve_first(input: &str) -> u32 {
-    let memory = read_codes(input);
+// cargo run --release -- 25 1 < src/aoc25/solution
+pub fn solve_first(input: &str) -> Result<u32, ParseError> {
+    let memory = read_codes(input)?;
     let mut intcode = Intcode::new(memory);
     let mut buf = String::new();
     let answer_regex = Regex::new(
@@ -27,7 +29,7 @@ pub fn solve_first(input: &str) -> u32 {
         let output: String = intcode.output.iter().map(|&v| v as u8 as char).collect();
         println!("{}", output);
         if let Some(group) = answer_regex.captures(&output) {
-            return group[1].parse().unwrap();
+            return Ok(group[1].parse().unwrap());
         }
         intcode.output.clear();
         io::stdin().read_line(&mut buf).unwrap();
@@ -37,20 +39,15 @@ pub fn solve_first(input: &str) -> u32 {
         }
         buf.clear();
     }
-    0
+    Ok(0)
 }
 
 // ------ INTCODE (added run_until_input and run_instruction returns early
 // on input instruction if there's no input to consume) -----------
 use std::collections::VecDeque;
 
-fn read_codes(input: &str) -> Vec<i64> {
-    let mut result = Vec::new();
-
-    for number_str in input.trim().split(',') {
-        result.push(number_str.parse().unwrap());
-    }
-    result
+fn read_codes(input: &str) -> Result<Vec<i64>, ParseError> {
+    parse::integers(input)
 }
 
 // returns (A, B, C, DE)
@@ -231,6 +228,7 @@ impl Intcode {
         op
     }
 
+    #[allow(dead_code)]
     fn run_until_output(&mut self) {
         while self.ptr < self.memory.len() {
             let op = self.run_instruction();
@@ -249,3 +247,38 @@ impl Intcode {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::intcode::conformance::assert_conforms;
+    use crate::intcode::{Status, Vm};
+
+    impl Vm for Intcode {
+        fn load(memory: Vec<i64>) -> Self {
+            Intcode::new(memory)
+        }
+        fn push_input(&mut self, value: i64) {
+            self.input.push_back(value);
+        }
+        fn run(&mut self) -> Status {
+            self.run_until_input();
+            if self.finished {
+                Status::Halted
+            } else {
+                Status::NeedsInput
+            }
+        }
+        fn take_output(&mut self) -> Vec<i64> {
+            self.output.drain(..).collect()
+        }
+        fn memory(&self) -> &[i64] {
+            &self.memory
+        }
+    }
+
+    #[test]
+    fn conformance() {
+        assert_conforms::<Intcode>();
+    }
+}
@@ -0,0 +1,403 @@
+// 2D coordinates, directions and maps for the grid puzzles
+// y grows downwards like the puzzle text, and coordinates sort row by row
+// (y, then x) so ordered maps of them iterate in reading order.
+use std::collections::BTreeSet;
+
+use crate::parse::ParseError;
+use crate::random::Rng;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Coordinate {
+    // field order is the derived Ord
+    pub y: i32,
+    pub x: i32,
+}
+
+impl Coordinate {
+    pub const fn new(x: i32, y: i32) -> Coordinate {
+        Coordinate { y, x }
+    }
+
+    pub fn step(self, direction: Direction) -> Coordinate {
+        let (dx, dy) = direction.delta();
+        Coordinate::new(self.x + dx, self.y + dy)
+    }
+
+    // in Direction::ALL order
+    pub fn neighbours(self) -> [Coordinate; 4] {
+        Direction::ALL.map(|direction| self.step(direction))
+    }
+
+    // clockwise from up, diagonals included
+    pub fn neighbours8(self) -> [Coordinate; 8] {
+        let Coordinate { x, y } = self;
+        [
+            Coordinate::new(x, y - 1),
+            Coordinate::new(x + 1, y - 1),
+            Coordinate::new(x + 1, y),
+            Coordinate::new(x + 1, y + 1),
+            Coordinate::new(x, y + 1),
+            Coordinate::new(x - 1, y + 1),
+            Coordinate::new(x - 1, y),
+            Coordinate::new(x - 1, y - 1),
+        ]
+    }
+
+    // None unless `to` is one step away
+    pub fn direction_to(self, to: Coordinate) -> Option<Direction> {
+        Direction::ALL
+            .iter()
+            .copied()
+            .find(|&direction| self.step(direction) == to)
+    }
+
+    pub fn manhattan(self, other: Coordinate) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    // clockwise from up
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    // (dx, dy) of one step
+    pub fn delta(self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Right => (1, 0),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+        }
+    }
+
+    pub fn turn_left(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Down,
+        }
+    }
+
+    pub fn turn_right(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn reverse(self) -> Direction {
+        self.turn_left().turn_left()
+    }
+}
+
+// rectangle between two corners, both included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub min: Coordinate,
+    pub max: Coordinate,
+}
+
+impl Bounds {
+    pub fn new(min: Coordinate, max: Coordinate) -> Bounds {
+        Bounds { min, max }
+    }
+
+    // smallest rectangle holding every coordinate, None if there are none
+    pub fn of<I>(coordinates: I) -> Option<Bounds>
+    where
+        I: IntoIterator<Item = Coordinate>,
+    {
+        let mut coordinates = coordinates.into_iter();
+        let first = coordinates.next()?;
+        Some(coordinates.fold(Bounds::new(first, first), |bounds, c| {
+            Bounds::new(
+                Coordinate::new(bounds.min.x.min(c.x), bounds.min.y.min(c.y)),
+                Coordinate::new(bounds.max.x.max(c.x), bounds.max.y.max(c.y)),
+            )
+        }))
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1).max(0) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1).max(0) as usize
+    }
+
+    pub fn contains(&self, c: Coordinate) -> bool {
+        (self.min.x..=self.max.x).contains(&c.x) && (self.min.y..=self.max.y).contains(&c.y)
+    }
+
+    // moves every side n tiles inwards, or outwards if n is negative
+    pub fn inset(&self, n: i32) -> Bounds {
+        Bounds::new(
+            Coordinate::new(self.min.x + n, self.min.y + n),
+            Coordinate::new(self.max.x - n, self.max.y - n),
+        )
+    }
+}
+
+// rectangular map stored row by row, (0, 0) is the top left tile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    tiles: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            tiles: vec![fill; width * height],
+        }
+    }
+
+    // one tile per character, `tile` maps the characters `expected` describes
+    // and rejects any other; rows must all be as wide as the first
+    pub fn parse<F>(input: &str, expected: &str, tile: F) -> Result<Grid<T>, ParseError>
+    where
+        F: Fn(char) -> Option<T>,
+    {
+        let lines: Vec<&str> = input.trim_end_matches('\n').lines().collect();
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        let mut tiles = Vec::with_capacity(width * lines.len());
+        for (idx, line) in lines.iter().enumerate() {
+            for (column, c) in line.chars().enumerate() {
+                match tile(c) {
+                    Some(t) if column < width => tiles.push(t),
+                    Some(_) => {
+                        let rest: String = line.chars().skip(width).collect();
+                        let shape = format!("a row of {} tiles", width);
+                        return Err(ParseError::new(idx + 1, width + 1, &rest, &shape));
+                    }
+                    None => {
+                        return Err(ParseError::new(
+                            idx + 1,
+                            column + 1,
+                            &c.to_string(),
+                            expected,
+                        ))
+                    }
+                }
+            }
+            if tiles.len() < width * (idx + 1) {
+                let shape = format!("a row of {} tiles", width);
+                return Err(ParseError::new(idx + 1, 1, line, &shape));
+            }
+        }
+        if tiles.is_empty() {
+            return Err(ParseError::new(1, 1, "", expected));
+        }
+        Ok(Grid {
+            width,
+            height: lines.len(),
+            tiles,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        Bounds::new(
+            Coordinate::new(0, 0),
+            Coordinate::new(self.width as i32 - 1, self.height as i32 - 1),
+        )
+    }
+
+    pub fn contains(&self, c: Coordinate) -> bool {
+        self.bounds().contains(c)
+    }
+
+    fn index(&self, c: Coordinate) -> Option<usize> {
+        if self.contains(c) {
+            Some(c.y as usize * self.width + c.x as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, c: Coordinate) -> Option<&T> {
+        self.index(c).map(|idx| &self.tiles[idx])
+    }
+
+    pub fn get_mut(&mut self, c: Coordinate) -> Option<&mut T> {
+        self.index(c).map(move |idx| &mut self.tiles[idx])
+    }
+
+    // panics outside the grid
+    pub fn set(&mut self, c: Coordinate, tile: T) {
+        let idx = self
+            .index(c)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", c));
+        self.tiles[idx] = tile;
+    }
+
+    // every tile in reading order
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (Coordinate, &T)> {
+        let width = self.width;
+        self.tiles.iter().enumerate().map(move |(idx, tile)| {
+            let c = Coordinate::new((idx % width) as i32, (idx / width) as i32);
+            (c, tile)
+        })
+    }
+
+    // first tile in reading order matching
+    pub fn find<F>(&self, matches: F) -> Option<Coordinate>
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.iter().find(|(_, t)| matches(t)).map(|(c, _)| c)
+    }
+
+    // four-way neighbours inside the grid
+    pub fn neighbours(&self, c: Coordinate) -> impl Iterator<Item = (Coordinate, &T)> {
+        IntoIterator::into_iter(c.neighbours()).filter_map(move |n| self.get(n).map(|t| (n, t)))
+    }
+
+    pub fn render<F>(&self, glyph: F) -> String
+    where
+        F: Fn(&T) -> char,
+    {
+        let mut result = String::with_capacity((self.width + 1) * self.height);
+        for row in self.tiles.chunks(self.width.max(1)) {
+            result.extend(row.iter().map(&glyph));
+            result.push('\n');
+        }
+        result
+    }
+}
+
+// A perfect maze, carved depth first from `start`: rooms are two apart,
+// `room` says which of them belong to the maze, and the wall between two
+// rooms comes down when the carving first crosses it. Returns the rooms and
+// the walls taken down, every room reachable from every other one way only.
+pub fn carve_maze<F>(start: Coordinate, room: F, rng: &mut Rng) -> BTreeSet<Coordinate>
+where
+    F: Fn(Coordinate) -> bool,
+{
+    let mut open = BTreeSet::new();
+    open.insert(start);
+    let mut stack = vec![start];
+    while let Some(&current) = stack.last() {
+        let next: Vec<Direction> = Direction::ALL
+            .iter()
+            .copied()
+            .filter(|&d| {
+                let next = current.step(d).step(d);
+                room(next) && !open.contains(&next)
+            })
+            .collect();
+        if next.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let direction = *rng.pick(&next);
+        open.insert(current.step(direction));
+        let next = current.step(direction).step(direction);
+        open.insert(next);
+        stack.push(next);
+    }
+    open
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_coordinate() {
+        let c = Coordinate::new(2, 5);
+        assert_eq!(c.step(Direction::Up), Coordinate::new(2, 4));
+        assert_eq!(c.neighbours()[1], Coordinate::new(3, 5));
+        assert_eq!(c.neighbours8().len(), 8);
+        assert_eq!(c.direction_to(Coordinate::new(1, 5)), Some(Direction::Left));
+        assert_eq!(c.direction_to(Coordinate::new(1, 4)), None);
+        assert_eq!(c.manhattan(Coordinate::new(-1, 1)), 7);
+        // row by row
+        assert!(Coordinate::new(9, 0) < Coordinate::new(0, 1));
+
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.reverse(), Direction::Right);
+    }
+
+    #[test]
+    fn test_bounds() {
+        let bounds = Bounds::of(vec![Coordinate::new(-1, 3), Coordinate::new(4, -2)]).unwrap();
+        assert_eq!(bounds.min, Coordinate::new(-1, -2));
+        assert_eq!((bounds.width(), bounds.height()), (6, 6));
+        assert!(bounds.contains(Coordinate::new(0, 0)));
+        assert!(!bounds.inset(1).contains(Coordinate::new(-1, 0)));
+        assert_eq!(Bounds::of(vec![]), None);
+    }
+
+    #[test]
+    fn test_grid() {
+        let wall = |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        };
+        let grid = Grid::parse("#.#\n..#\n", "# or .", wall).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(Coordinate::new(2, 1)), Some(&true));
+        assert_eq!(grid.get(Coordinate::new(3, 1)), None);
+        assert_eq!(grid.find(|&t| !t), Some(Coordinate::new(1, 0)));
+        assert_eq!(grid.neighbours(Coordinate::new(0, 0)).count(), 2);
+        assert_eq!(grid.render(|&t| if t { '#' } else { '.' }), "#.#\n..#\n");
+
+        assert_eq!(
+            Grid::parse("#.#\n.x#\n", "# or .", wall),
+            Err(ParseError::new(2, 2, "x", "# or ."))
+        );
+        assert_eq!(
+            Grid::parse("#.#\n.#\n", "# or .", wall),
+            Err(ParseError::new(2, 1, ".#", "a row of 3 tiles"))
+        );
+        assert_eq!(
+            Grid::parse("#.#\n.#..\n", "# or .", wall),
+            Err(ParseError::new(2, 4, ".", "a row of 3 tiles"))
+        );
+    }
+
+    #[test]
+    fn test_carve_maze() {
+        let room = |c: Coordinate| c.x >= 1 && c.y >= 1 && c.x <= 9 && c.y <= 7;
+        let open = carve_maze(Coordinate::new(1, 1), room, &mut Rng::new(18));
+        // 5 by 4 rooms and a tree joining them
+        let rooms = open.iter().filter(|c| c.x % 2 == 1 && c.y % 2 == 1).count();
+        assert_eq!(rooms, 20);
+        assert_eq!(open.len(), 20 + 19);
+        assert!(open
+            .iter()
+            .all(|c| (1..=9).contains(&c.x) && (1..=7).contains(&c.y)));
+    }
+}
@@ -0,0 +1,418 @@
+// Golden programs every Intcode engine has to agree on
+// One case per opcode and parameter mode, plus the examples from
+// days 2, 5 and 9 and a few self-modifying programs.
+// `check::<Engine>()` runs them all and names every case that disagrees,
+// so a broken opcode shows up here instead of as a wrong day 19 answer.
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+use super::{read_codes, Status, Vm};
+
+pub struct Case {
+    pub name: &'static str,
+    pub program: &'static str,
+    pub input: &'static [i64],
+    pub output: &'static [i64],
+    // expected start of memory once the program halts
+    pub memory: Option<&'static [i64]>,
+}
+
+const fn case(name: &'static str, program: &'static str) -> Case {
+    Case {
+        name,
+        program,
+        input: &[],
+        output: &[],
+        memory: None,
+    }
+}
+
+const fn io(
+    name: &'static str,
+    program: &'static str,
+    input: &'static [i64],
+    output: &'static [i64],
+) -> Case {
+    Case {
+        name,
+        program,
+        input,
+        output,
+        memory: None,
+    }
+}
+
+const fn mem(name: &'static str, program: &'static str, memory: &'static [i64]) -> Case {
+    Case {
+        name,
+        program,
+        input: &[],
+        output: &[],
+        memory: Some(memory),
+    }
+}
+
+const AOC05_LARGER: &str = "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99";
+
+const QUINE: &str = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+
+pub const CASES: &[Case] = &[
+    // add / multiply
+    mem("add, position mode", "1,0,0,0,99", &[2, 0, 0, 0, 99]),
+    mem("multiply, position mode", "2,3,0,3,99", &[2, 3, 0, 6, 99]),
+    mem(
+        "multiply into trailing cell",
+        "2,4,4,5,99,0",
+        &[2, 4, 4, 5, 99, 9801],
+    ),
+    mem(
+        "add then multiply",
+        "1,1,1,4,99,5,6,0,99",
+        &[30, 1, 1, 4, 2, 5, 6, 0, 99],
+    ),
+    mem(
+        "aoc02 example",
+        "1,9,10,3,2,3,11,0,99,30,40,50",
+        &[3500, 9, 10, 70, 2, 3, 11, 0, 99, 30, 40, 50],
+    ),
+    mem(
+        "add, immediate mode",
+        "1101,100,-1,4,0",
+        &[1101, 100, -1, 4, 99],
+    ),
+    mem(
+        "multiply, negative operand",
+        "1102,-3,7,5,99,0",
+        &[1102, -3, 7, 5, 99, -21],
+    ),
+    mem(
+        "add, beyond f64 precision",
+        "1101,9007199254740993,1,5,99,0",
+        &[1101, 9007199254740993, 1, 5, 99, 9007199254740994],
+    ),
+    io(
+        "add, relative mode",
+        "109,20,21101,2,3,0,204,0,99",
+        &[],
+        &[5],
+    ),
+    io(
+        "multiply, relative mode",
+        "109,8,22202,0,1,2,204,2,99,6,7",
+        &[],
+        &[594],
+    ),
+    // input / output
+    io("input, position mode", "3,0,4,0,99", &[42], &[42]),
+    io(
+        "input, relative mode",
+        "109,10,203,0,204,0,99",
+        &[-7],
+        &[-7],
+    ),
+    io("output, immediate mode", "104,-3,99", &[], &[-3]),
+    io("several inputs", "3,0,4,0,3,0,4,0,99", &[1, 2], &[1, 2]),
+    // jumps
+    io("jump-if-true, taken", "1105,1,4,99,104,1,99", &[], &[1]),
+    io("jump-if-true, not taken", "1105,0,4,104,2,99", &[], &[2]),
+    io("jump-if-false, taken", "1106,0,4,99,104,3,99", &[], &[3]),
+    io("jump-if-false, not taken", "1106,1,4,104,4,99", &[], &[4]),
+    io(
+        "jump-if-true, relative mode",
+        "109,9,2205,0,1,99,104,5,99,1,6",
+        &[],
+        &[5],
+    ),
+    io(
+        "jump-if-false, position mode",
+        "6,9,10,99,104,6,99,0,0,0,4",
+        &[],
+        &[6],
+    ),
+    // comparisons, from aoc05
+    io(
+        "less than, position mode (lower)",
+        "3,9,7,9,10,9,4,9,99,-1,8",
+        &[5],
+        &[1],
+    ),
+    io(
+        "less than, position mode (equal)",
+        "3,9,7,9,10,9,4,9,99,-1,8",
+        &[8],
+        &[0],
+    ),
+    io(
+        "equals, position mode (equal)",
+        "3,9,8,9,10,9,4,9,99,-1,8",
+        &[8],
+        &[1],
+    ),
+    io(
+        "equals, position mode (higher)",
+        "3,9,8,9,10,9,4,9,99,-1,8",
+        &[9],
+        &[0],
+    ),
+    io(
+        "less than, immediate mode (lower)",
+        "3,3,1107,-1,8,3,4,3,99",
+        &[7],
+        &[1],
+    ),
+    io(
+        "less than, immediate mode (equal)",
+        "3,3,1107,-1,8,3,4,3,99",
+        &[8],
+        &[0],
+    ),
+    io(
+        "equals, immediate mode (equal)",
+        "3,3,1108,-1,8,3,4,3,99",
+        &[8],
+        &[1],
+    ),
+    io(
+        "equals, immediate mode (lower)",
+        "3,3,1108,-1,8,3,4,3,99",
+        &[1],
+        &[0],
+    ),
+    io(
+        "less than, relative mode",
+        "109,1,21107,4,5,10,204,10,99",
+        &[],
+        &[1],
+    ),
+    io(
+        "equals, relative mode",
+        "109,1,21108,5,5,10,204,10,99",
+        &[],
+        &[1],
+    ),
+    io(
+        "aoc05 jump, position mode (zero)",
+        "3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9",
+        &[0],
+        &[0],
+    ),
+    io(
+        "aoc05 jump, position mode (non zero)",
+        "3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9",
+        &[5],
+        &[1],
+    ),
+    io(
+        "aoc05 jump, immediate mode (zero)",
+        "3,3,1105,-1,9,1101,0,0,12,4,12,99,1",
+        &[0],
+        &[0],
+    ),
+    io(
+        "aoc05 jump, immediate mode (non zero)",
+        "3,3,1105,-1,9,1101,0,0,12,4,12,99,1",
+        &[3],
+        &[1],
+    ),
+    io("aoc05 compare to 8 (below)", AOC05_LARGER, &[7], &[999]),
+    io("aoc05 compare to 8 (equal)", AOC05_LARGER, &[8], &[1000]),
+    io("aoc05 compare to 8 (above)", AOC05_LARGER, &[9], &[1001]),
+    // relative base
+    io(
+        "adjust relative base, negative offset",
+        "109,10,109,-5,204,-5,99",
+        &[],
+        &[109],
+    ),
+    io(
+        "adjust relative base, position mode",
+        "9,5,204,-1,99,3",
+        &[],
+        &[204],
+    ),
+    io(
+        "adjust relative base, relative mode",
+        "109,3,209,3,204,-100,99",
+        &[],
+        &[209],
+    ),
+    // aoc09
+    io(
+        "aoc09 quine",
+        QUINE,
+        &[],
+        &[
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ],
+    ),
+    io(
+        "aoc09 sixteen digit number",
+        "1102,34915192,34915192,7,4,7,99,0",
+        &[],
+        &[1219070632396864],
+    ),
+    io(
+        "aoc09 large number",
+        "104,1125899906842624,99",
+        &[],
+        &[1125899906842624],
+    ),
+    // self-modifying code
+    mem(
+        "self-modifying, patch halt",
+        "1002,4,3,4,33",
+        &[1002, 4, 3, 4, 99],
+    ),
+    io(
+        "self-modifying, write instructions",
+        "1101,104,0,8,1101,99,0,10,-1,42,-1",
+        &[],
+        &[42],
+    ),
+    io(
+        "self-modifying, patch jump target",
+        "1101,0,7,6,1105,1,9,104,9,99",
+        &[],
+        &[9],
+    ),
+    io(
+        "self-modifying, input as opcode",
+        "3,2,0,5,99",
+        &[104],
+        &[5],
+    ),
+    case("halt", "99"),
+];
+
+#[derive(Debug)]
+pub struct Failure {
+    pub case: &'static str,
+    pub reason: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.case, self.reason)
+    }
+}
+
+pub fn run_case<V: Vm>(case: &Case) -> Result<(), String> {
+    let mut vm = V::load(read_codes(case.program).unwrap());
+    for &value in case.input {
+        vm.push_input(value);
+    }
+    if vm.run() == Status::NeedsInput {
+        return Err(String::from("still waiting for input"));
+    }
+
+    let output = vm.take_output();
+    if output != case.output {
+        return Err(format!(
+            "expected output {:?}, got {:?}",
+            case.output, output
+        ));
+    }
+
+    if let Some(expected) = case.memory {
+        let memory = vm.memory();
+        if memory.len() < expected.len() || &memory[..expected.len()] != expected {
+            return Err(format!(
+                "expected memory to start with {:?}, got {:?}",
+                expected, memory
+            ));
+        }
+    }
+    Ok(())
+}
+
+// runs every case against the engine, returns the ones that failed
+pub fn check<V: Vm>() -> Vec<Failure> {
+    let mut failures = Vec::new();
+    for case in CASES {
+        // an engine panicking on one opcode shouldn't hide the other cases
+        let result = panic::catch_unwind(AssertUnwindSafe(|| run_case::<V>(case)));
+        let reason = match result {
+            Ok(Ok(())) => continue,
+            Ok(Err(reason)) => reason,
+            Err(payload) => {
+                let message = payload
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+                    .unwrap_or_default();
+                format!("panicked: {}", message)
+            }
+        };
+        failures.push(Failure {
+            case: case.name,
+            reason,
+        });
+    }
+    failures
+}
+
+// fails the test with every case the engine gets wrong
+#[cfg(test)]
+pub fn assert_conforms<V: Vm>() {
+    let failures = check::<V>();
+    let report: Vec<String> = failures.iter().map(|f| f.to_string()).collect();
+    assert!(failures.is_empty(), "\n{}", report.join("\n"));
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{decode_op, Intcode};
+    use super::*;
+
+    #[test]
+    fn reference() {
+        assert_conforms::<Intcode>();
+    }
+
+    // reference interpreter with "less than" swapped for "equals"
+    struct BrokenLessThan(Intcode);
+
+    impl Vm for BrokenLessThan {
+        fn load(memory: Vec<i64>) -> Self {
+            BrokenLessThan(Intcode::new(memory))
+        }
+        fn push_input(&mut self, value: i64) {
+            self.0.push_input(value);
+        }
+        fn run(&mut self) -> Status {
+            while !self.0.finished {
+                if self.0.needs_input() {
+                    return Status::NeedsInput;
+                }
+                let code = self.0.memory[self.0.ptr];
+                if decode_op(code).3 == 7 {
+                    self.0.memory[self.0.ptr] += 1;
+                }
+                self.0.step();
+            }
+            Status::Halted
+        }
+        fn take_output(&mut self) -> Vec<i64> {
+            self.0.take_output()
+        }
+        fn memory(&self) -> &[i64] {
+            self.0.memory()
+        }
+    }
+
+    #[test]
+    fn reports_broken_opcode() {
+        let failures = check::<BrokenLessThan>();
+        assert!(!failures.is_empty());
+        for failure in failures.iter() {
+            assert!(
+                failure.case.starts_with("less than") || failure.case.starts_with("aoc05"),
+                "unexpected failure {}",
+                failure
+            );
+        }
+        assert!(failures
+            .iter()
+            .any(|f| f.case == "less than, relative mode"));
+    }
+}
@@ -0,0 +1,727 @@
+// Turns an Intcode program back into readable pseudocode
+// Instructions are found by following the control flow from address 0, so
+// data after a halt isn't decoded as code. On top of the plain listing it
+// recognises the idioms the puzzle programs are compiled with:
+// - calls: the return address is stored in [rb+0], arguments in [rb+1..],
+//   then an unconditional jump; the callee starts with `arb N` and returns
+//   with `arb -N` followed by a jump to [rb+0]; the jump may also go through
+//   a function pointer
+// - loops: a jz/jnz jumping backwards becomes `do { .. } while (..)`
+// - array indexing: writes into an operand of another instruction become a
+//   pointer variable `pN` that the patched instruction reads through
+// Code only reachable through pointers stored in data tables isn't found.
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use super::{decode_op, instruction_length, valid_instruction, ParameterMode};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Operand {
+    Immediate(i64),
+    Position(i64),
+    Relative(i64),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Instruction {
+    pub address: usize,
+    pub op: i64,
+    pub operands: Vec<Operand>,
+}
+
+impl Instruction {
+    pub fn decode(memory: &[i64], address: usize) -> Option<Instruction> {
+        let code = *memory.get(address)?;
+        if !valid_instruction(code) {
+            return None;
+        }
+        let (a, b, c, op) = decode_op(code);
+        let length = instruction_length(op)?;
+        let modes = [c, b, a];
+        let operands = (1..length)
+            .map(|idx| {
+                let value = memory.get(address + idx).copied().unwrap_or(0);
+                match modes[idx - 1] {
+                    ParameterMode::Position => Operand::Position(value),
+                    ParameterMode::Immediate => Operand::Immediate(value),
+                    ParameterMode::Relative => Operand::Relative(value),
+                }
+            })
+            .collect();
+        Some(Instruction {
+            address,
+            op,
+            operands,
+        })
+    }
+
+    pub fn length(&self) -> usize {
+        self.operands.len() + 1
+    }
+
+    pub fn next(&self) -> usize {
+        self.address + self.length()
+    }
+
+    // memory cell written by the instruction, if it's a fixed address
+    fn destination(&self) -> Option<Operand> {
+        match self.op {
+            1 | 2 | 7 | 8 => Some(self.operands[2]),
+            3 => Some(self.operands[0]),
+            _ => None,
+        }
+    }
+
+    // for jz/jnz: Some(true) if always taken, Some(false) if never taken
+    fn constant_condition(&self) -> Option<bool> {
+        match self.operands[0] {
+            Operand::Immediate(value) => Some((value != 0) == (self.op == 5)),
+            _ => None,
+        }
+    }
+
+    fn jump_target(&self) -> Option<usize> {
+        match self.operands[1] {
+            Operand::Immediate(target) if target >= 0 => Some(target as usize),
+            _ => None,
+        }
+    }
+
+    fn is_jump(&self) -> bool {
+        self.op == 5 || self.op == 6
+    }
+
+    fn is_return(&self) -> bool {
+        self.is_jump()
+            && self.constant_condition() == Some(true)
+            && self.operands[1] == Operand::Relative(0)
+    }
+}
+
+// the value an add/mul stores when both operands are immediate
+fn constant_store(instruction: &Instruction) -> Option<i64> {
+    if instruction.operands.len() < 2 {
+        return None;
+    }
+    match (
+        instruction.op,
+        instruction.operands[0],
+        instruction.operands[1],
+    ) {
+        (1, Operand::Immediate(a), Operand::Immediate(b)) => Some(a + b),
+        (2, Operand::Immediate(a), Operand::Immediate(b)) => Some(a * b),
+        _ => None,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Call {
+    // the instruction storing the return address
+    pub address: usize,
+    // None when calling through a function pointer
+    pub target: Option<usize>,
+    // instructions storing the arguments in [rb+1], [rb+2], ..
+    pub arguments: Vec<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Function {
+    pub entry: usize,
+    // size of the stack frame from the `arb N` prologue, 0 without one
+    pub frame: i64,
+    pub parameters: usize,
+    pub instructions: BTreeSet<usize>,
+    pub calls: Vec<Call>,
+}
+
+impl Function {
+    pub fn callees(&self) -> BTreeSet<usize> {
+        self.calls.iter().filter_map(|call| call.target).collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct Program {
+    pub instructions: BTreeMap<usize, Instruction>,
+    pub functions: BTreeMap<usize, Function>,
+    // operand cells written by other instructions, the pointer idiom
+    pub patched: BTreeSet<usize>,
+}
+
+pub fn decompile(memory: &[i64]) -> Program {
+    let mut instructions = BTreeMap::new();
+    let mut functions = BTreeMap::new();
+    let mut pending = vec![0];
+    let mut parameters: BTreeMap<usize, usize> = BTreeMap::new();
+
+    while let Some(entry) = pending.pop() {
+        if functions.contains_key(&entry) {
+            continue;
+        }
+        let function = trace_function(memory, entry, &mut instructions);
+        for call in function.calls.iter() {
+            if let Some(target) = call.target {
+                let count = parameters.entry(target).or_insert(0);
+                *count = (*count).max(call.arguments.len());
+                pending.push(target);
+            }
+            // function pointers passed as arguments, recognised by their `arb` prologue
+            for argument in call.arguments.iter() {
+                let pointer = constant_store(&instructions[argument]);
+                if let Some(pointer) = pointer.filter(|&p| p > 0) {
+                    if memory.get(pointer as usize) == Some(&109) {
+                        pending.push(pointer as usize);
+                    }
+                }
+            }
+        }
+        functions.insert(entry, function);
+    }
+    for (entry, function) in functions.iter_mut() {
+        function.parameters = parameters.get(entry).copied().unwrap_or(0);
+    }
+
+    let operand_cells: BTreeSet<usize> = instructions
+        .values()
+        .flat_map(|i: &Instruction| i.address + 1..i.next())
+        .collect();
+    let patched = instructions
+        .values()
+        .filter_map(|i| match i.destination() {
+            Some(Operand::Position(cell)) if cell >= 0 => Some(cell as usize),
+            _ => None,
+        })
+        .filter(|cell| operand_cells.contains(cell))
+        .collect();
+
+    Program {
+        instructions,
+        functions,
+        patched,
+    }
+}
+
+// follows control flow inside one function, calls continue at their return address
+fn trace_function(
+    memory: &[i64],
+    entry: usize,
+    instructions: &mut BTreeMap<usize, Instruction>,
+) -> Function {
+    let mut visited = BTreeSet::new();
+    let mut calls = Vec::new();
+    let mut pending = vec![entry];
+
+    while let Some(address) = pending.pop() {
+        if !visited.insert(address) {
+            continue;
+        }
+        let instruction = match Instruction::decode(memory, address) {
+            Some(instruction) => instruction,
+            None => {
+                visited.remove(&address);
+                continue;
+            }
+        };
+        instructions.insert(address, instruction.clone());
+
+        if let Some(call) = match_call(memory, &instruction) {
+            visited.insert(instruction.next());
+            let jump = Instruction::decode(memory, instruction.next()).unwrap();
+            pending.push(jump.next());
+            instructions.insert(jump.address, jump);
+            calls.push(call);
+            continue;
+        }
+
+        match instruction.op {
+            99 => {}
+            5 | 6 if instruction.is_return() => {}
+            5 | 6 => {
+                let condition = instruction.constant_condition();
+                if condition != Some(false) {
+                    if let Some(target) = instruction.jump_target() {
+                        pending.push(target);
+                    }
+                }
+                if condition != Some(true) {
+                    pending.push(instruction.next());
+                }
+            }
+            _ => pending.push(instruction.next()),
+        }
+    }
+
+    let frame = match Instruction::decode(memory, entry) {
+        Some(Instruction {
+            op: 9, operands, ..
+        }) => match operands[0] {
+            Operand::Immediate(size) if entry != 0 => size,
+            _ => 0,
+        },
+        _ => 0,
+    };
+    calls.sort_by_key(|call| call.address);
+    // arguments are only kept when their setup is part of this function
+    for call in calls.iter_mut() {
+        call.arguments.retain(|address| visited.contains(address));
+    }
+
+    Function {
+        entry,
+        frame,
+        parameters: 0,
+        instructions: visited,
+        calls,
+    }
+}
+
+// `[rb+0] = return address; goto target` with the return address right after the jump
+fn match_call(memory: &[i64], instruction: &Instruction) -> Option<Call> {
+    if instruction.destination() != Some(Operand::Relative(0)) {
+        return None;
+    }
+    let return_address = constant_store(instruction)?;
+    let jump = Instruction::decode(memory, instruction.next())?;
+    if !jump.is_jump() || jump.constant_condition() != Some(true) {
+        return None;
+    }
+    let target = jump.jump_target();
+    if return_address != jump.next() as i64 {
+        return None;
+    }
+
+    // argument stores directly in front of the return address
+    let mut arguments = Vec::new();
+    let mut slots = BTreeSet::new();
+    let mut address = instruction.address;
+    'outer: while address > 0 {
+        for length in [4, 2] {
+            if address < length {
+                continue;
+            }
+            let candidate = match Instruction::decode(memory, address - length) {
+                Some(candidate) if candidate.length() == length => candidate,
+                _ => continue,
+            };
+            match candidate.destination() {
+                Some(Operand::Relative(slot)) if slot > 0 && slots.insert(slot) => {
+                    arguments.push((slot, candidate.address));
+                    address = candidate.address;
+                    continue 'outer;
+                }
+                _ => {}
+            }
+        }
+        break;
+    }
+    arguments.sort();
+
+    Some(Call {
+        address: instruction.address,
+        target,
+        arguments: arguments.into_iter().map(|(_, address)| address).collect(),
+    })
+}
+
+impl Program {
+    pub fn function_of(&self, address: usize) -> Option<&Function> {
+        self.functions
+            .values()
+            .find(|f| f.instructions.contains(&address))
+    }
+
+    fn name(&self, address: usize) -> String {
+        if address == 0 {
+            String::from("main")
+        } else {
+            format!("fn_{}", address)
+        }
+    }
+
+    // how an operand reads inside the given function
+    fn operand(&self, function: &Function, operand: Operand, cell: usize) -> String {
+        if self.patched.contains(&cell) {
+            return match operand {
+                Operand::Immediate(_) => format!("p{}", cell),
+                Operand::Position(_) => format!("mem[p{}]", cell),
+                Operand::Relative(_) => format!("rb[p{}]", cell),
+            };
+        }
+        match operand {
+            Operand::Immediate(value) => value.to_string(),
+            Operand::Position(address) => {
+                if address >= 0 && self.patched.contains(&(address as usize)) {
+                    format!("p{}", address)
+                } else {
+                    format!("mem[{}]", address)
+                }
+            }
+            Operand::Relative(offset) => self.frame_slot(function, offset),
+        }
+    }
+
+    // caller frame: [rb+0] return address, [rb+1..] arguments; the callee's `arb N`
+    // moves them to [rb-N], [rb-N+1..]
+    fn frame_slot(&self, function: &Function, offset: i64) -> String {
+        if offset > 0 {
+            return format!("out{}", offset);
+        }
+        if function.frame == 0 {
+            return format!("rb[{}]", offset);
+        }
+        let index = offset + function.frame;
+        match index {
+            0 => String::from("return_address"),
+            i if i < 0 => format!("rb[{}]", offset),
+            i if i as usize <= function.parameters => format!("arg{}", i),
+            i => format!("local{}", i),
+        }
+    }
+
+    fn expression(&self, function: &Function, instruction: &Instruction) -> String {
+        let a = self.operand(function, instruction.operands[0], instruction.address + 1);
+        if instruction.op == 3 {
+            return String::from("input()");
+        }
+        let b = self.operand(function, instruction.operands[1], instruction.address + 2);
+        match (
+            instruction.op,
+            instruction.operands[0],
+            instruction.operands[1],
+        ) {
+            (1, _, Operand::Immediate(0)) | (2, _, Operand::Immediate(1)) => a,
+            (1, Operand::Immediate(0), _) | (2, Operand::Immediate(1), _) => b,
+            (1, _, Operand::Immediate(v)) if v < 0 => format!("{} - {}", a, -v),
+            (2, _, Operand::Immediate(-1)) => format!("-{}", a),
+            (1, _, _) => format!("{} + {}", a, b),
+            (2, _, _) => format!("{} * {}", a, b),
+            (7, _, _) => format!("{} < {}", a, b),
+            _ => format!("{} == {}", a, b),
+        }
+    }
+
+    fn destination(&self, function: &Function, instruction: &Instruction) -> String {
+        let cell = instruction.next() - 1;
+        match instruction.destination() {
+            Some(Operand::Position(address))
+                if address >= 0 && self.patched.contains(&(address as usize)) =>
+            {
+                format!("p{}", address)
+            }
+            Some(operand) => self.operand(function, operand, cell),
+            None => unreachable!(),
+        }
+    }
+
+    fn condition(&self, function: &Function, instruction: &Instruction) -> String {
+        let value = self.operand(function, instruction.operands[0], instruction.address + 1);
+        match instruction.constant_condition() {
+            Some(true) => String::from("true"),
+            _ if instruction.op == 5 => format!("{} != 0", value),
+            _ => format!("{} == 0", value),
+        }
+    }
+
+    fn statement(&self, function: &Function, instruction: &Instruction) -> String {
+        match instruction.op {
+            1 | 2 | 7 | 8 | 3 => format!(
+                "{} = {}",
+                self.destination(function, instruction),
+                self.expression(function, instruction)
+            ),
+            4 => format!(
+                "output({})",
+                self.operand(function, instruction.operands[0], instruction.address + 1)
+            ),
+            9 => format!(
+                "rb += {}",
+                self.operand(function, instruction.operands[0], instruction.address + 1)
+            ),
+            99 => String::from("halt"),
+            _ if instruction.is_return() => String::from("return"),
+            _ => {
+                let target = match instruction.jump_target() {
+                    Some(target) => format!("L_{}", target),
+                    None => format!(
+                        "*{}",
+                        self.operand(function, instruction.operands[1], instruction.address + 2)
+                    ),
+                };
+                match instruction.constant_condition() {
+                    Some(true) => format!("goto {}", target),
+                    Some(false) => String::from("nop"),
+                    None => format!(
+                        "if ({}) goto {}",
+                        self.condition(function, instruction),
+                        target
+                    ),
+                }
+            }
+        }
+    }
+
+    // backward jumps that nest properly, as (head, jump) pairs
+    fn loops(&self, function: &Function, calls: &BTreeSet<usize>) -> BTreeMap<usize, usize> {
+        let mut candidates: Vec<(usize, usize)> = function
+            .instructions
+            .iter()
+            .filter(|address| !calls.contains(address))
+            .filter_map(|address| {
+                let instruction = &self.instructions[address];
+                if !instruction.is_jump() || instruction.constant_condition() == Some(false) {
+                    return None;
+                }
+                let target = instruction.jump_target()?;
+                let head = target < *address && function.instructions.contains(&target);
+                head.then_some((target, *address))
+            })
+            .collect();
+        // outer loops first
+        candidates.sort_by_key(|&(head, jump)| (head, std::cmp::Reverse(jump)));
+
+        let mut accepted: Vec<(usize, usize)> = Vec::new();
+        for (head, jump) in candidates {
+            let crosses = accepted.iter().any(|&(h, j)| {
+                let inside = h <= head && jump <= j;
+                let disjoint = jump < h || j < head;
+                !inside && !disjoint || h == head
+            });
+            if !crosses {
+                accepted.push((head, jump));
+            }
+        }
+        accepted.into_iter().map(|(h, j)| (j, h)).collect()
+    }
+
+    fn write_function(&self, f: &mut fmt::Formatter, function: &Function) -> fmt::Result {
+        let parameters: Vec<String> = (1..=function.parameters)
+            .map(|i| format!("arg{}", i))
+            .collect();
+        writeln!(
+            f,
+            "fn {}({}) {{ // frame {}",
+            self.name(function.entry),
+            parameters.join(", "),
+            function.frame
+        )?;
+
+        // the jump of a call and its argument stores are folded into the call
+        let mut folded = BTreeSet::new();
+        let mut calls = BTreeMap::new();
+        let mut jumps = BTreeSet::new();
+        for call in function.calls.iter() {
+            folded.extend(call.arguments.iter().copied());
+            let jump = self.instructions[&call.address].next();
+            folded.insert(jump);
+            jumps.insert(jump);
+            calls.insert(call.address, call);
+        }
+        let loops = self.loops(function, &jumps);
+        let heads: BTreeMap<usize, usize> = loops.iter().fold(BTreeMap::new(), |mut m, (_, &h)| {
+            *m.entry(h).or_insert(0) += 1;
+            m
+        });
+        let targets: BTreeSet<usize> = function
+            .instructions
+            .iter()
+            .map(|a| &self.instructions[a])
+            .filter(|i| {
+                i.is_jump() && !jumps.contains(&i.address) && !loops.contains_key(&i.address)
+            })
+            .filter_map(|i| i.jump_target())
+            .collect();
+
+        let mut depth = 1;
+        let mut last = None;
+        for &address in function.instructions.iter() {
+            let instruction = &self.instructions[&address];
+            if last.is_some_and(|next| next != address) {
+                writeln!(
+                    f,
+                    "{:indent$}// {}..{} skipped",
+                    "",
+                    last.unwrap(),
+                    address,
+                    indent = depth * 4
+                )?;
+            }
+            last = Some(instruction.next());
+            if targets.contains(&address) {
+                writeln!(
+                    f,
+                    "{:indent$}L_{}:",
+                    "",
+                    address,
+                    indent = (depth - 1) * 4 + 2
+                )?;
+            }
+            for _ in 0..heads.get(&address).copied().unwrap_or(0) {
+                writeln!(f, "{:indent$}do {{", "", indent = depth * 4)?;
+                depth += 1;
+            }
+            if folded.contains(&address) {
+                continue;
+            }
+
+            let line = if let Some(call) = calls.get(&address) {
+                let arguments: Vec<String> = call
+                    .arguments
+                    .iter()
+                    .map(|a| self.expression(function, &self.instructions[a]))
+                    .collect();
+                let callee = match call.target {
+                    Some(target) => self.name(target),
+                    None => {
+                        let jump = &self.instructions[&self.instructions[&address].next()];
+                        format!(
+                            "(*{})",
+                            self.operand(function, jump.operands[1], jump.next() - 1)
+                        )
+                    }
+                };
+                format!("{}({})", callee, arguments.join(", "))
+            } else if loops.contains_key(&address) {
+                depth -= 1;
+                format!("}} while ({})", self.condition(function, instruction))
+            } else {
+                self.statement(function, instruction)
+            };
+            writeln!(
+                f,
+                "{:>6}: {:indent$}{}",
+                address,
+                "",
+                line,
+                indent = depth * 4 - 4
+            )?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, function) in self.functions.values().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            self.write_function(f, function)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::read_codes;
+    use super::*;
+    use crate::answers::puzzle_input;
+
+    #[test]
+    fn call_and_loop() {
+        // main: out1 = 3; fn_16(out1); output(out1); halt
+        // fn_16: do { arg1 = 2 * arg1; local2 = arg1 < 50 } while (local2 != 0)
+        let memory = read_codes(
+            "109,30,21101,3,0,1,21101,13,0,0,1105,1,16,204,1,99,\
+             109,3,22102,2,-2,-2,21207,-2,50,-1,1205,-1,18,109,-3,2105,1,0",
+        )
+        .unwrap();
+        let program = decompile(&memory);
+        assert_eq!(
+            program.functions.keys().copied().collect::<Vec<_>>(),
+            vec![0, 16]
+        );
+        let function = &program.functions[&16];
+        assert_eq!(function.frame, 3);
+        assert_eq!(function.parameters, 1);
+
+        let text = program.to_string();
+        assert!(text.contains("fn_16(3)"), "{}", text);
+        assert!(text.contains("fn fn_16(arg1)"), "{}", text);
+        assert!(text.contains("do {"), "{}", text);
+        assert!(text.contains("arg1 = 2 * arg1"), "{}", text);
+        assert!(text.contains("} while (local2 != 0)"), "{}", text);
+        assert!(text.contains("return"), "{}", text);
+    }
+
+    #[test]
+    fn array_indexing() {
+        // p5 = mem[20] + 100; output(mem[p5]); halt
+        let memory = read_codes("1001,20,100,5,4,0,99").unwrap();
+        let program = decompile(&memory);
+        assert!(program.patched.contains(&5), "{:?}", program.patched);
+        let text = program.to_string();
+        assert!(text.contains("p5 = mem[20] + 100"), "{}", text);
+        assert!(text.contains("output(mem[p5])"), "{}", text);
+    }
+
+    fn check_program(input: &str) -> Program {
+        let memory = read_codes(input).unwrap();
+        let program = decompile(&memory);
+        assert!(program.functions.len() > 1);
+        for function in program.functions.values() {
+            for call in function.calls.iter() {
+                if let Some(target) = call.target {
+                    assert!(program.functions.contains_key(&target));
+                }
+            }
+            if function.entry != 0 {
+                assert!(function.frame > 0, "fn_{} has no frame", function.entry);
+            }
+        }
+        let text = program.to_string();
+        assert!(text.contains("return"));
+        program
+    }
+
+    #[test]
+    fn aoc09() {
+        let input = match puzzle_input(9) {
+            Some(input) => input,
+            None => return,
+        };
+        let program = check_program(&input);
+        assert!(program.to_string().contains("halt"));
+        // the recursive function at the end of the program
+        let (entry, _) = program
+            .functions
+            .iter()
+            .find(|(entry, f)| f.callees().contains(entry))
+            .unwrap();
+        assert_eq!(program.functions[entry].parameters, 1);
+    }
+
+    #[test]
+    fn aoc13() {
+        let input = match puzzle_input(13) {
+            Some(input) => input,
+            None => return,
+        };
+        let program = check_program(&input);
+        // the screen is written through a patched pointer
+        assert!(!program.patched.is_empty());
+        assert!(program.to_string().contains("mem[p"));
+    }
+
+    #[test]
+    fn aoc17() {
+        let input = match puzzle_input(17) {
+            Some(input) => input,
+            None => return,
+        };
+        let program = check_program(&input);
+        assert!(program.to_string().contains("halt"));
+    }
+
+    #[test]
+    fn aoc25() {
+        // the room handlers are only reachable through pointer tables in data,
+        // so the halt after the airlock isn't found
+        let input = match puzzle_input(25) {
+            Some(input) => input,
+            None => return,
+        };
+        let program = check_program(&input);
+        assert!(program.to_string().contains("do {"));
+    }
+}
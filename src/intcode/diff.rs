@@ -0,0 +1,276 @@
+// Compares two Intcode memory images
+// Useful to find where a program keeps its state (day 13's score, day 25's
+// inventory flags) so it can be patched the way aoc13 sets memory[0] = 2.
+// Changed cells are grouped into contiguous ranges, and cells that were part
+// of an executed instruction are flagged as self-modifying code.
+use std::collections::BTreeSet;
+use std::fmt;
+
+use super::{instruction_length, Intcode, Status};
+
+// every memory cell that was part of an instruction the vm ran
+#[derive(Debug, Default, Clone)]
+pub struct Executed {
+    cells: BTreeSet<usize>,
+}
+
+impl Executed {
+    pub fn new() -> Self {
+        Executed::default()
+    }
+
+    // records the instruction the vm is about to run
+    fn record(&mut self, intcode: &Intcode) {
+        let code = intcode.memory.get(intcode.ptr).copied().unwrap_or(0);
+        let length = instruction_length(code % 100).unwrap_or(1);
+        self.cells.extend(intcode.ptr..intcode.ptr + length);
+    }
+
+    pub fn contains(&self, address: usize) -> bool {
+        self.cells.contains(&address)
+    }
+
+    // executed cells grouped into contiguous (start, end) ranges, end exclusive
+    pub fn ranges(&self) -> Vec<(usize, usize)> {
+        group(self.cells.iter().copied())
+    }
+}
+
+// same as Intcode::run_until_input, recording every instruction that runs
+pub fn run_traced(intcode: &mut Intcode, executed: &mut Executed) -> Status {
+    loop {
+        if intcode.finished {
+            return Status::Halted;
+        }
+        if intcode.needs_input() {
+            return Status::NeedsInput;
+        }
+        executed.record(intcode);
+        intcode.step();
+    }
+}
+
+fn group<I: Iterator<Item = usize>>(addresses: I) -> Vec<(usize, usize)> {
+    let mut result: Vec<(usize, usize)> = Vec::new();
+    for address in addresses {
+        match result.last_mut() {
+            Some((_, end)) if *end == address => *end += 1,
+            _ => result.push((address, address + 1)),
+        }
+    }
+    result
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ChangedRange {
+    pub start: usize,
+    pub before: Vec<i64>,
+    pub after: Vec<i64>,
+}
+
+impl ChangedRange {
+    // exclusive
+    pub fn end(&self) -> usize {
+        self.start + self.after.len()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MemoryDiff {
+    pub ranges: Vec<ChangedRange>,
+}
+
+impl MemoryDiff {
+    // memory only grows, cells missing from the shorter image count as 0
+    pub fn between(before: &[i64], after: &[i64]) -> MemoryDiff {
+        let len = before.len().max(after.len());
+        let cell = |memory: &[i64], idx: usize| memory.get(idx).copied().unwrap_or(0);
+
+        let changed = (0..len).filter(|&idx| cell(before, idx) != cell(after, idx));
+        let ranges = group(changed)
+            .into_iter()
+            .map(|(start, end)| ChangedRange {
+                start,
+                before: (start..end).map(|idx| cell(before, idx)).collect(),
+                after: (start..end).map(|idx| cell(after, idx)).collect(),
+            })
+            .collect();
+
+        MemoryDiff { ranges }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn changed_cells(&self) -> usize {
+        self.ranges.iter().map(|r| r.after.len()).sum()
+    }
+
+    // changed cells that were also executed as code, grouped into (start, end) ranges
+    pub fn self_modifying(&self, executed: &Executed) -> Vec<(usize, usize)> {
+        let cells = self
+            .ranges
+            .iter()
+            .flat_map(|r| r.start..r.end())
+            .filter(|&address| executed.contains(address));
+        group(cells)
+    }
+
+    pub fn report<'a>(&'a self, executed: &'a Executed) -> Report<'a> {
+        Report {
+            diff: self,
+            executed,
+        }
+    }
+}
+
+impl fmt::Display for MemoryDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for range in self.ranges.iter() {
+            writeln!(
+                f,
+                "{:>6}..{:<6} {:?} -> {:?}",
+                range.start,
+                range.end(),
+                range.before,
+                range.after
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// diff listing with changes to executed code marked
+pub struct Report<'a> {
+    diff: &'a MemoryDiff,
+    executed: &'a Executed,
+}
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} cells changed in {} ranges",
+            self.diff.changed_cells(),
+            self.diff.ranges.len()
+        )?;
+        for range in self.diff.ranges.iter() {
+            let code = (range.start..range.end()).any(|a| self.executed.contains(a));
+            writeln!(
+                f,
+                "{} {:>6}..{:<6} {:?} -> {:?}",
+                if code { "*" } else { " " },
+                range.start,
+                range.end(),
+                range.before,
+                range.after
+            )?;
+        }
+        let modified = self.diff.self_modifying(self.executed);
+        if !modified.is_empty() {
+            writeln!(f, "self-modifying writes (*):")?;
+            for (start, end) in modified {
+                writeln!(
+                    f,
+                    "  {}..{} starts with {} after the write",
+                    start,
+                    end,
+                    self.diff_value(start)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Report<'_> {
+    fn diff_value(&self, address: usize) -> i64 {
+        self.diff
+            .ranges
+            .iter()
+            .find(|r| r.start <= address && address < r.end())
+            .map(|r| r.after[address - r.start])
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::read_codes;
+    use super::*;
+    use crate::answers::puzzle_input;
+
+    #[test]
+    fn groups_ranges() {
+        let before = vec![1, 2, 3, 4, 5, 6];
+        let after = vec![1, 0, 0, 4, 5, 7, 0, 9];
+        let diff = MemoryDiff::between(&before, &after);
+        assert_eq!(
+            diff.ranges,
+            vec![
+                ChangedRange {
+                    start: 1,
+                    before: vec![2, 3],
+                    after: vec![0, 0]
+                },
+                ChangedRange {
+                    start: 5,
+                    before: vec![6],
+                    after: vec![7]
+                },
+                ChangedRange {
+                    start: 7,
+                    before: vec![0],
+                    after: vec![9]
+                },
+            ]
+        );
+        assert_eq!(diff.changed_cells(), 4);
+        assert!(MemoryDiff::between(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn flags_self_modifying() {
+        // writes `104` and `99` over the -1 cells, then runs them
+        let memory = read_codes("1101,104,0,8,1101,99,0,10,-1,42,-1,0").unwrap();
+        let mut intcode = Intcode::new(memory.clone());
+        let mut executed = Executed::new();
+        assert_eq!(run_traced(&mut intcode, &mut executed), Status::Halted);
+
+        assert_eq!(executed.ranges(), vec![(0, 11)]);
+        let diff = MemoryDiff::between(&memory, &intcode.memory);
+        assert_eq!(diff.self_modifying(&executed), vec![(8, 9), (10, 11)]);
+
+        // plain data writes aren't flagged
+        let memory = read_codes("1101,1,2,5,99,0").unwrap();
+        let mut intcode = Intcode::new(memory.clone());
+        let mut executed = Executed::new();
+        run_traced(&mut intcode, &mut executed);
+        let diff = MemoryDiff::between(&memory, &intcode.memory);
+        assert_eq!(diff.changed_cells(), 1);
+        assert!(diff.self_modifying(&executed).is_empty());
+    }
+
+    #[test]
+    fn arcade_frame() {
+        // play one frame of day 13 and look for the score counter
+        let input = match puzzle_input(13) {
+            Some(input) => input,
+            None => return,
+        };
+        let mut memory = read_codes(&input).unwrap();
+        memory[0] = 2;
+        let mut intcode = Intcode::new(memory);
+        let mut executed = Executed::new();
+        assert_eq!(run_traced(&mut intcode, &mut executed), Status::NeedsInput);
+        let before = intcode.memory.clone();
+
+        intcode.input.push_back(0);
+        run_traced(&mut intcode, &mut executed);
+        let diff = MemoryDiff::between(&before, &intcode.memory);
+        assert!(!diff.is_empty());
+        let report = diff.report(&executed).to_string();
+        assert!(report.starts_with(&format!("{} cells changed", diff.changed_cells())));
+    }
+}
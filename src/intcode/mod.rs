@@ -0,0 +1,284 @@
+// Shared Intcode virtual machine
+// Every day keeps its own copy of the interpreter (see aoc09, aoc15, aoc25),
+// this one is the reference implementation tools are built against.
+// Other engines plug into the same tools by implementing `Vm`.
+pub mod conformance;
+pub mod decompile;
+pub mod diff;
+pub mod solver;
+pub mod symbolic;
+
+use std::collections::VecDeque;
+
+use crate::parse::{self, ParseError};
+
+pub fn read_codes(input: &str) -> Result<Vec<i64>, ParseError> {
+    parse::integers(input)
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Status {
+    Halted,
+    // program is waiting on an input instruction with nothing queued
+    NeedsInput,
+}
+
+// interface every Intcode engine implements
+pub trait Vm {
+    fn load(memory: Vec<i64>) -> Self;
+    fn push_input(&mut self, value: i64);
+    // runs until the program halts or asks for input it doesn't have
+    fn run(&mut self) -> Status;
+    // removes and returns every output produced so far
+    fn take_output(&mut self) -> Vec<i64>;
+    fn memory(&self) -> &[i64];
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ParameterMode {
+    Position,
+    Immediate,
+    Relative,
+}
+
+impl ParameterMode {
+    fn decode(n: i64) -> ParameterMode {
+        match n {
+            0 => ParameterMode::Position,
+            1 => ParameterMode::Immediate,
+            2 => ParameterMode::Relative,
+            _ => panic!("Unexpected parameter mode"),
+        }
+    }
+}
+
+// returns (A, B, C, DE)
+pub fn decode_op(code: i64) -> (ParameterMode, ParameterMode, ParameterMode, i64) {
+    let de = code % 100;
+    let c = ParameterMode::decode(code / 100 % 10);
+    let b = ParameterMode::decode(code / 1000 % 10);
+    let a = ParameterMode::decode(code / 10000 % 10);
+
+    (a, b, c, de)
+}
+
+// number of memory cells an instruction takes, including the opcode itself
+pub fn instruction_length(op: i64) -> Option<usize> {
+    match op {
+        1 | 2 | 7 | 8 => Some(4),
+        5 | 6 => Some(3),
+        3 | 4 | 9 => Some(2),
+        99 => Some(1),
+        _ => None,
+    }
+}
+
+// true if the code decodes to a known opcode with valid parameter modes
+pub fn valid_instruction(code: i64) -> bool {
+    let modes_valid = (2..5).all(|digit| code / 10i64.pow(digit) % 10 <= 2);
+    (0..100_000).contains(&code) && instruction_length(code % 100).is_some() && modes_valid
+}
+
+#[derive(Clone)]
+pub struct Intcode {
+    pub memory: Vec<i64>,
+    pub ptr: usize,
+    pub input: VecDeque<i64>,
+    pub output: VecDeque<i64>,
+    pub relative: i64,
+    pub finished: bool,
+}
+
+impl Intcode {
+    pub fn new(memory: Vec<i64>) -> Self {
+        Intcode {
+            memory,
+            ptr: 0,
+            relative: 0,
+            input: VecDeque::new(),
+            output: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    fn address(&mut self, index: usize, mode: ParameterMode) -> usize {
+        match mode {
+            ParameterMode::Position => self.read_memory(index) as usize,
+            ParameterMode::Immediate => index,
+            ParameterMode::Relative => (self.read_memory(index) + self.relative) as usize,
+        }
+    }
+
+    fn load_value(&mut self, index: usize, mode: ParameterMode) -> i64 {
+        let address = self.address(index, mode);
+        self.read_memory(address)
+    }
+
+    fn save_value(&mut self, index: usize, mode: ParameterMode, value: i64) {
+        let address = self.address(index, mode);
+        self.write_memory(address, value);
+    }
+
+    fn read_memory(&mut self, index: usize) -> i64 {
+        if self.memory.len() <= index {
+            self.memory.resize(index + 1, 0);
+        }
+        self.memory[index]
+    }
+
+    fn write_memory(&mut self, index: usize, value: i64) {
+        if self.memory.len() <= index {
+            self.memory.resize(index + 1, 0);
+        }
+        self.memory[index] = value;
+    }
+
+    // runs a single instruction, returns the opcode ran
+    // returns 3 without moving the pointer when input is required but not available
+    pub fn step(&mut self) -> i64 {
+        if self.finished {
+            return 99;
+        }
+
+        let pointer = self.ptr;
+        let code = self.read_memory(pointer);
+        let (arg3_mode, arg2_mode, arg1_mode, op) = decode_op(code);
+        let next_pointer = match op {
+            1 | 2 | 7 | 8 => {
+                let par1 = self.load_value(pointer + 1, arg1_mode);
+                let par2 = self.load_value(pointer + 2, arg2_mode);
+                let store_value = match op {
+                    1 => par1 + par2,
+                    2 => par1 * par2,
+                    7 => (par1 < par2) as i64,
+                    _ => (par1 == par2) as i64,
+                };
+                self.save_value(pointer + 3, arg3_mode, store_value);
+                pointer + 4
+            }
+            3 => {
+                if let Some(input_value) = self.input.pop_front() {
+                    self.save_value(pointer + 1, arg1_mode, input_value);
+                    pointer + 2
+                } else {
+                    return op;
+                }
+            }
+            4 => {
+                let v = self.load_value(pointer + 1, arg1_mode);
+                self.output.push_back(v);
+                pointer + 2
+            }
+            5 | 6 => {
+                let par1 = self.load_value(pointer + 1, arg1_mode);
+                let par2 = self.load_value(pointer + 2, arg2_mode) as usize;
+                if (par1 != 0) == (op == 5) {
+                    par2
+                } else {
+                    pointer + 3
+                }
+            }
+            9 => {
+                let par1 = self.load_value(pointer + 1, arg1_mode);
+                self.relative += par1;
+                pointer + 2
+            }
+            99 => {
+                self.finished = true;
+                pointer + 1
+            }
+            _ => panic!("Unknown opcode {} at position {}", code, pointer),
+        };
+        self.ptr = next_pointer;
+        op
+    }
+
+    // true when the next instruction is an input and there's nothing to read
+    pub fn needs_input(&self) -> bool {
+        self.memory
+            .get(self.ptr)
+            .is_some_and(|code| code % 100 == 3)
+            && self.input.is_empty()
+    }
+
+    pub fn run_until_output(&mut self) {
+        while !self.finished && !self.needs_input() {
+            if self.step() == 4 {
+                break;
+            }
+        }
+    }
+
+    pub fn run_until_input(&mut self) -> Status {
+        loop {
+            if self.finished {
+                return Status::Halted;
+            }
+            if self.needs_input() {
+                return Status::NeedsInput;
+            }
+            self.step();
+        }
+    }
+}
+
+impl Vm for Intcode {
+    fn load(memory: Vec<i64>) -> Self {
+        Intcode::new(memory)
+    }
+
+    fn push_input(&mut self, value: i64) {
+        self.input.push_back(value);
+    }
+
+    fn run(&mut self) -> Status {
+        self.run_until_input()
+    }
+
+    fn take_output(&mut self) -> Vec<i64> {
+        self.output.drain(..).collect()
+    }
+
+    fn memory(&self) -> &[i64] {
+        &self.memory
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decode() {
+        assert_eq!(
+            decode_op(21108),
+            (
+                ParameterMode::Relative,
+                ParameterMode::Immediate,
+                ParameterMode::Immediate,
+                8
+            )
+        );
+    }
+
+    #[test]
+    fn valid() {
+        assert!(valid_instruction(21108));
+        assert!(valid_instruction(99));
+        assert!(!valid_instruction(0));
+        assert!(!valid_instruction(1301));
+        assert!(!valid_instruction(-1));
+    }
+
+    #[test]
+    fn pauses_on_input() {
+        let mut intcode = Intcode::new(read_codes("3,0,4,0,3,0,4,0,99").unwrap());
+        assert_eq!(intcode.run(), Status::NeedsInput);
+        intcode.push_input(7);
+        assert_eq!(intcode.run(), Status::NeedsInput);
+        assert_eq!(intcode.take_output(), vec![7]);
+        intcode.push_input(8);
+        assert_eq!(intcode.run(), Status::Halted);
+        assert_eq!(intcode.take_output(), vec![8]);
+    }
+}
@@ -0,0 +1,296 @@
+// Goal seeking over Intcode inputs, generalizing day 2 part 2
+// Picks memory cells to vary (like the noun and verb at 1 and 2), runs
+// the program for every combination of their values on a worker pool and
+// keeps the combinations whose final state satisfies the goal.
+use std::convert::TryFrom;
+use std::ops::RangeInclusive;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use super::{valid_instruction, Intcode};
+
+// final state of a run, handed to the goal predicate
+pub struct Outcome<'a> {
+    pub memory: &'a [i64],
+    pub output: &'a [i64],
+}
+
+pub struct Variable {
+    pub address: usize,
+    pub range: RangeInclusive<i64>,
+}
+
+impl Variable {
+    // None if there are more values than a usize counts
+    fn len(&self) -> Option<usize> {
+        if self.range.is_empty() {
+            Some(0)
+        } else {
+            let len = *self.range.end() as i128 - *self.range.start() as i128 + 1;
+            usize::try_from(len).ok()
+        }
+    }
+}
+
+pub struct GoalSeek {
+    memory: Vec<i64>,
+    variables: Vec<Variable>,
+    input: Vec<i64>,
+    workers: usize,
+    limit: Option<usize>,
+    max_steps: usize,
+}
+
+impl GoalSeek {
+    pub fn new(memory: Vec<i64>) -> Self {
+        let workers = thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        GoalSeek {
+            memory,
+            variables: Vec::new(),
+            input: Vec::new(),
+            workers,
+            limit: None,
+            max_steps: 1_000_000,
+        }
+    }
+
+    // sets memory[address] to every value in range
+    pub fn vary(mut self, address: usize, range: RangeInclusive<i64>) -> Self {
+        self.variables.push(Variable { address, range });
+        self
+    }
+
+    // values fed to the program's input instructions on every run
+    pub fn input(mut self, input: Vec<i64>) -> Self {
+        self.input = input;
+        self
+    }
+
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    // stop searching once this many assignments were found
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    // runs longer than this count as not satisfying the goal
+    pub fn max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    // None if there are more than a usize counts
+    pub fn candidates(&self) -> Option<usize> {
+        self.variables
+            .iter()
+            .try_fold(1usize, |total, v| total.checked_mul(v.len()?))
+    }
+
+    // decodes a candidate number into one value per variable (mixed radix)
+    fn assignment(&self, mut index: usize) -> Vec<i64> {
+        let mut result = Vec::with_capacity(self.variables.len());
+        for variable in self.variables.iter().rev() {
+            // candidates() fit a usize, so every range does
+            let len = variable.len().unwrap();
+            result.push(variable.range.start() + (index % len) as i64);
+            index /= len;
+        }
+        result.reverse();
+        result
+    }
+
+    fn run<F>(&self, assignment: &[i64], goal: &F) -> bool
+    where
+        F: Fn(&Outcome) -> bool,
+    {
+        let mut intcode = Intcode::new(self.memory.clone());
+        for (variable, &value) in self.variables.iter().zip(assignment) {
+            intcode.memory[variable.address] = value;
+        }
+        intcode.input.extend(self.input.iter().copied());
+
+        let mut steps = 0;
+        // a bad assignment may make the program jump into garbage, that's a miss
+        let halted = panic::catch_unwind(AssertUnwindSafe(|| {
+            while !intcode.finished {
+                if intcode.needs_input() || steps == self.max_steps {
+                    return false;
+                }
+                if !valid_instruction(intcode.memory.get(intcode.ptr).copied().unwrap_or(0)) {
+                    return false;
+                }
+                intcode.step();
+                steps += 1;
+            }
+            true
+        }));
+        if !matches!(halted, Ok(true)) {
+            return false;
+        }
+
+        let output: Vec<i64> = intcode.output.iter().copied().collect();
+        goal(&Outcome {
+            memory: &intcode.memory,
+            output: &output,
+        })
+    }
+
+    // every assignment that reaches the goal, in the order of the variables'
+    // ranges; Err if a variable is outside the program or there are too many
+    // assignments to count
+    pub fn solve<F>(&self, goal: F) -> Result<Vec<Vec<i64>>, String>
+    where
+        F: Fn(&Outcome) -> bool + Sync,
+    {
+        for variable in &self.variables {
+            if variable.address >= self.memory.len() {
+                return Err(format!(
+                    "address {} is outside the program's {} cells",
+                    variable.address,
+                    self.memory.len()
+                ));
+            }
+        }
+        let total = self
+            .candidates()
+            .ok_or_else(|| String::from("too many assignments to search"))?;
+        let next = AtomicUsize::new(0);
+        // with a limit, nothing past the limit-th hit so far can make the cut;
+        // candidates are handed out in order, so everything before it still
+        // finishes
+        let last = AtomicUsize::new(usize::MAX);
+        let results = Mutex::new(Vec::new());
+
+        thread::scope(|scope| {
+            for _ in 0..self.workers {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= total || index > last.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let assignment = self.assignment(index);
+                    if !self.run(&assignment, &goal) {
+                        continue;
+                    }
+                    let mut results = results.lock().unwrap();
+                    results.push((index, assignment));
+                    if let Some(limit) = self.limit.filter(|&limit| results.len() >= limit) {
+                        let mut hits: Vec<usize> = results.iter().map(|&(i, _)| i).collect();
+                        hits.sort_unstable();
+                        last.store(hits[limit - 1], Ordering::Relaxed);
+                    }
+                });
+            }
+        });
+
+        let mut results = results.into_inner().unwrap();
+        results.sort();
+        if let Some(limit) = self.limit {
+            results.truncate(limit);
+        }
+        Ok(results
+            .into_iter()
+            .map(|(_, assignment)| assignment)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::read_codes;
+    use super::*;
+    use crate::answers::puzzle_input;
+
+    #[test]
+    fn all_assignments() {
+        // memory[0] = memory[9] + memory[10]
+        let memory = read_codes("1,9,10,0,99,0,0,0,0,0,0").unwrap();
+        let solutions = GoalSeek::new(memory)
+            .vary(9, 0..=5)
+            .vary(10, 0..=5)
+            .workers(3)
+            .solve(|outcome| outcome.memory[0] == 5)
+            .unwrap();
+
+        let expected: Vec<Vec<i64>> = (0..=5).map(|a| vec![a, 5 - a]).collect();
+        assert_eq!(solutions, expected);
+    }
+
+    #[test]
+    fn output_goal_and_limit() {
+        // outputs input * memory[11]
+        let memory = read_codes("3,12,2,12,11,12,4,12,99,0,0,0,0").unwrap();
+        let search = GoalSeek::new(memory)
+            .vary(11, -10..=10)
+            .input(vec![3])
+            .limit(1);
+        assert_eq!(search.candidates(), Some(21));
+        assert_eq!(
+            search.solve(|outcome| outcome.output == [-6]),
+            Ok(vec![vec![-2]])
+        );
+    }
+
+    #[test]
+    fn limit_keeps_the_first() {
+        // every assignment works, the first few in range order have to win
+        // however the workers race
+        let memory = read_codes("99,0,0").unwrap();
+        for workers in 1..=4 {
+            let solutions = GoalSeek::new(memory.clone())
+                .vary(1, 0..=9)
+                .vary(2, 0..=9)
+                .workers(workers)
+                .limit(3)
+                .solve(|_| true);
+            assert_eq!(solutions, Ok(vec![vec![0, 0], vec![0, 1], vec![0, 2]]));
+        }
+    }
+
+    #[test]
+    fn bad_variables() {
+        let memory = read_codes("99,0,0").unwrap();
+        assert_eq!(
+            GoalSeek::new(memory.clone()).vary(3, 0..=1).solve(|_| true),
+            Err(String::from("address 3 is outside the program's 3 cells"))
+        );
+        let huge = GoalSeek::new(memory)
+            .vary(1, i64::MIN..=i64::MAX)
+            .vary(2, 0..=1);
+        assert_eq!(huge.candidates(), None);
+        assert!(huge.solve(|_| true).is_err());
+    }
+
+    #[test]
+    fn skips_runaway_programs() {
+        // jumps to the address in memory[2], only 3 and 4 land on a halt
+        let memory = read_codes("1105,1,0,99,99,0").unwrap();
+        let solutions = GoalSeek::new(memory)
+            .vary(2, 0..=4)
+            .max_steps(100)
+            .solve(|_| true);
+        assert_eq!(solutions, Ok(vec![vec![3], vec![4]]));
+    }
+
+    #[test]
+    fn aoc02() {
+        let input = match puzzle_input(2) {
+            Some(input) => input,
+            None => return,
+        };
+        let memory = read_codes(&input).unwrap();
+        let solutions = GoalSeek::new(memory)
+            .vary(1, 0..=99)
+            .vary(2, 0..=99)
+            .solve(|outcome| outcome.memory[0] == 19690720);
+        assert_eq!(solutions, Ok(vec![vec![38, 92]]));
+    }
+}
@@ -0,0 +1,621 @@
+// Symbolic execution of straight-line Intcode
+// Selected memory cells and input values are treated as variables and
+// add/mul/lt/eq build expressions over them instead of numbers.
+// Day 2's memory[0] comes out as a polynomial in noun and verb, which can
+// then be solved directly instead of trying all 10000 combinations.
+// Anything that depends on a symbolic value to continue (jumps, addresses,
+// opcodes) is reported as unsupported rather than guessed.
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+
+use super::{decode_op, ParameterMode};
+
+// monomial (sorted variable names, repeated for powers) -> coefficient
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Polynomial {
+    terms: BTreeMap<Vec<String>, i64>,
+}
+
+impl Polynomial {
+    pub fn constant(value: i64) -> Self {
+        let mut terms = BTreeMap::new();
+        if value != 0 {
+            terms.insert(Vec::new(), value);
+        }
+        Polynomial { terms }
+    }
+
+    pub fn variable(name: &str) -> Self {
+        let mut terms = BTreeMap::new();
+        terms.insert(vec![name.to_string()], 1);
+        Polynomial { terms }
+    }
+
+    pub fn as_constant(&self) -> Option<i64> {
+        match self.terms.len() {
+            0 => Some(0),
+            1 => self.terms.get(&Vec::new()).copied(),
+            _ => None,
+        }
+    }
+
+    // coefficient of the product of the given variables, `&[]` for the constant term
+    pub fn coefficient(&self, variables: &[&str]) -> i64 {
+        let mut key: Vec<String> = variables.iter().map(|v| v.to_string()).collect();
+        key.sort();
+        self.terms.get(&key).copied().unwrap_or(0)
+    }
+
+    fn add(&self, other: &Polynomial) -> Polynomial {
+        let mut terms = self.terms.clone();
+        for (monomial, coefficient) in other.terms.iter() {
+            *terms.entry(monomial.clone()).or_insert(0) += coefficient;
+        }
+        terms.retain(|_, c| *c != 0);
+        Polynomial { terms }
+    }
+
+    fn mul(&self, other: &Polynomial) -> Polynomial {
+        let mut terms = BTreeMap::new();
+        for (m1, c1) in self.terms.iter() {
+            for (m2, c2) in other.terms.iter() {
+                let mut monomial = m1.clone();
+                monomial.extend(m2.iter().cloned());
+                monomial.sort();
+                *terms.entry(monomial).or_insert(0) += c1 * c2;
+            }
+        }
+        terms.retain(|_, c| *c != 0);
+        Polynomial { terms }
+    }
+
+    fn eval(&self, values: &HashMap<String, i64>) -> Option<i64> {
+        let mut total = 0;
+        for (monomial, coefficient) in self.terms.iter() {
+            let mut term = *coefficient;
+            for variable in monomial {
+                term *= values.get(variable)?;
+            }
+            total += term;
+        }
+        Some(total)
+    }
+}
+
+impl fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.terms.is_empty() {
+            return write!(f, "0");
+        }
+        // highest degree first, constant last
+        let mut terms: Vec<_> = self.terms.iter().collect();
+        terms.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then(a.0.cmp(b.0)));
+        for (idx, (monomial, &coefficient)) in terms.into_iter().enumerate() {
+            let magnitude = coefficient.abs();
+            if idx == 0 {
+                if coefficient < 0 {
+                    write!(f, "-")?;
+                }
+            } else if coefficient < 0 {
+                write!(f, " - ")?;
+            } else {
+                write!(f, " + ")?;
+            }
+            if monomial.is_empty() {
+                write!(f, "{}", magnitude)?;
+            } else {
+                if magnitude != 1 {
+                    write!(f, "{}*", magnitude)?;
+                }
+                write!(f, "{}", monomial.join("*"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Poly(Polynomial),
+    // comparisons can't be folded into a polynomial, they're kept as is
+    Less(Box<Expr>, Box<Expr>),
+    Equal(Box<Expr>, Box<Expr>),
+    Sum(Box<Expr>, Box<Expr>),
+    Product(Box<Expr>, Box<Expr>),
+    // value read through a symbolic pointer, memory at that address when it was read
+    Load(Box<Expr>),
+}
+
+impl Expr {
+    pub fn constant(value: i64) -> Expr {
+        Expr::Poly(Polynomial::constant(value))
+    }
+
+    pub fn variable(name: &str) -> Expr {
+        Expr::Poly(Polynomial::variable(name))
+    }
+
+    pub fn as_constant(&self) -> Option<i64> {
+        match self {
+            Expr::Poly(p) => p.as_constant(),
+            _ => None,
+        }
+    }
+
+    pub fn as_polynomial(&self) -> Option<&Polynomial> {
+        match self {
+            Expr::Poly(p) => Some(p),
+            _ => None,
+        }
+    }
+
+    fn add(a: Expr, b: Expr) -> Expr {
+        match (a, b) {
+            (Expr::Poly(a), Expr::Poly(b)) => Expr::Poly(a.add(&b)),
+            (a, b) => Expr::Sum(Box::new(a), Box::new(b)),
+        }
+    }
+
+    fn mul(a: Expr, b: Expr) -> Expr {
+        match (a, b) {
+            (Expr::Poly(a), Expr::Poly(b)) => Expr::Poly(a.mul(&b)),
+            (a, b) => Expr::Product(Box::new(a), Box::new(b)),
+        }
+    }
+
+    fn less(a: Expr, b: Expr) -> Expr {
+        match (a.as_constant(), b.as_constant()) {
+            (Some(x), Some(y)) => Expr::constant((x < y) as i64),
+            _ => Expr::Less(Box::new(a), Box::new(b)),
+        }
+    }
+
+    // None if both sides read the same symbolic address, a Load doesn't know
+    // what memory held when it was read so they may still differ
+    fn equal(a: Expr, b: Expr) -> Option<Expr> {
+        if a == b {
+            return if a.has_load() {
+                None
+            } else {
+                Some(Expr::constant(1))
+            };
+        }
+        Some(match (a.as_constant(), b.as_constant()) {
+            (Some(x), Some(y)) => Expr::constant((x == y) as i64),
+            _ => Expr::Equal(Box::new(a), Box::new(b)),
+        })
+    }
+
+    fn has_load(&self) -> bool {
+        match self {
+            Expr::Poly(_) => false,
+            Expr::Less(a, b) | Expr::Equal(a, b) | Expr::Sum(a, b) | Expr::Product(a, b) => {
+                a.has_load() || b.has_load()
+            }
+            Expr::Load(_) => true,
+        }
+    }
+
+    // None if a variable is missing from values
+    pub fn eval(&self, values: &HashMap<String, i64>) -> Option<i64> {
+        Some(match self {
+            Expr::Poly(p) => p.eval(values)?,
+            Expr::Less(a, b) => (a.eval(values)? < b.eval(values)?) as i64,
+            Expr::Equal(a, b) => (a.eval(values)? == b.eval(values)?) as i64,
+            Expr::Sum(a, b) => a.eval(values)? + b.eval(values)?,
+            Expr::Product(a, b) => a.eval(values)? * b.eval(values)?,
+            Expr::Load(_) => return None,
+        })
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Poly(p) => write!(f, "{}", p),
+            Expr::Less(a, b) => write!(f, "({} < {})", a, b),
+            Expr::Equal(a, b) => write!(f, "({} == {})", a, b),
+            Expr::Sum(a, b) => write!(f, "({} + {})", a, b),
+            Expr::Product(a, b) => write!(f, "({}) * ({})", a, b),
+            Expr::Load(address) => write!(f, "mem[{}]", address),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unsupported {
+    SymbolicOpcode { pointer: usize, value: Expr },
+    SymbolicAddress { pointer: usize, value: Expr },
+    SymbolicBranch { pointer: usize, condition: Expr },
+    NegativeAddress { pointer: usize, address: i64 },
+    LoadComparison { pointer: usize, value: Expr },
+    UnknownOpcode { pointer: usize, code: i64 },
+    MissingInput { pointer: usize },
+    StepLimit,
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Unsupported::SymbolicOpcode { pointer, value } => {
+                write!(f, "opcode at {} is symbolic: {}", pointer, value)
+            }
+            Unsupported::SymbolicAddress { pointer, value } => write!(
+                f,
+                "instruction at {} uses a symbolic address: {}",
+                pointer, value
+            ),
+            Unsupported::SymbolicBranch { pointer, condition } => write!(
+                f,
+                "jump at {} branches on a symbolic value: {}",
+                pointer, condition
+            ),
+            Unsupported::NegativeAddress { pointer, address } => {
+                write!(
+                    f,
+                    "instruction at {} uses negative address {}",
+                    pointer, address
+                )
+            }
+            Unsupported::LoadComparison { pointer, value } => write!(
+                f,
+                "comparison at {} reads {} twice, memory may have changed in between",
+                pointer, value
+            ),
+            Unsupported::UnknownOpcode { pointer, code } => {
+                write!(f, "unknown opcode {} at {}", code, pointer)
+            }
+            Unsupported::MissingInput { pointer } => {
+                write!(f, "input instruction at {} has no value to read", pointer)
+            }
+            Unsupported::StepLimit => write!(f, "step limit reached"),
+        }
+    }
+}
+
+pub struct Symbolic {
+    memory: Vec<Expr>,
+    input: VecDeque<Expr>,
+    ptr: usize,
+    relative: i64,
+    output: Vec<Expr>,
+    max_steps: usize,
+}
+
+// memory and output once the program halts
+#[derive(Debug)]
+pub struct SymbolicRun {
+    pub memory: Vec<Expr>,
+    pub output: Vec<Expr>,
+}
+
+impl SymbolicRun {
+    pub fn cell(&self, address: usize) -> Expr {
+        self.memory
+            .get(address)
+            .cloned()
+            .unwrap_or_else(|| Expr::constant(0))
+    }
+}
+
+impl Symbolic {
+    pub fn new(memory: &[i64]) -> Self {
+        Symbolic {
+            memory: memory.iter().map(|&v| Expr::constant(v)).collect(),
+            input: VecDeque::new(),
+            ptr: 0,
+            relative: 0,
+            output: Vec::new(),
+            max_steps: 100_000,
+        }
+    }
+
+    // replaces memory[address] with a variable
+    pub fn variable(mut self, address: usize, name: &str) -> Self {
+        self.write(address, Expr::variable(name));
+        self
+    }
+
+    // queues a variable to be read by the next input instruction
+    pub fn input_variable(mut self, name: &str) -> Self {
+        self.input.push_back(Expr::variable(name));
+        self
+    }
+
+    pub fn input_value(mut self, value: i64) -> Self {
+        self.input.push_back(Expr::constant(value));
+        self
+    }
+
+    pub fn max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    fn read(&self, address: usize) -> Expr {
+        self.memory
+            .get(address)
+            .cloned()
+            .unwrap_or_else(|| Expr::constant(0))
+    }
+
+    fn write(&mut self, address: usize, value: Expr) {
+        if self.memory.len() <= address {
+            self.memory.resize(address + 1, Expr::constant(0));
+        }
+        self.memory[address] = value;
+    }
+
+    fn address(&self, index: usize, mode: ParameterMode) -> Result<usize, Unsupported> {
+        let base = match mode {
+            ParameterMode::Immediate => return Ok(index),
+            ParameterMode::Position => 0,
+            ParameterMode::Relative => self.relative,
+        };
+        let value = self.read(index);
+        match value.as_constant() {
+            Some(offset) if base + offset < 0 => Err(Unsupported::NegativeAddress {
+                pointer: self.ptr,
+                address: base + offset,
+            }),
+            Some(offset) => Ok((base + offset) as usize),
+            None => Err(Unsupported::SymbolicAddress {
+                pointer: self.ptr,
+                value,
+            }),
+        }
+    }
+
+    // reads through a symbolic pointer are kept as a Load, only writes have to be concrete
+    fn load(&self, index: usize, mode: ParameterMode) -> Result<Expr, Unsupported> {
+        match self.address(index, mode) {
+            Ok(address) => Ok(self.read(address)),
+            Err(Unsupported::SymbolicAddress { value, .. }) => {
+                let address = match mode {
+                    ParameterMode::Relative => Expr::add(Expr::constant(self.relative), value),
+                    _ => value,
+                };
+                Ok(Expr::Load(Box::new(address)))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn concrete(&self, value: Expr, condition: bool) -> Result<i64, Unsupported> {
+        match value.as_constant() {
+            Some(v) => Ok(v),
+            None if condition => Err(Unsupported::SymbolicBranch {
+                pointer: self.ptr,
+                condition: value,
+            }),
+            None => Err(Unsupported::SymbolicAddress {
+                pointer: self.ptr,
+                value,
+            }),
+        }
+    }
+
+    pub fn run(mut self) -> Result<SymbolicRun, Unsupported> {
+        for _ in 0..self.max_steps {
+            let pointer = self.ptr;
+            let code_expr = self.read(pointer);
+            let code = match code_expr.as_constant() {
+                Some(code) => code,
+                None => {
+                    return Err(Unsupported::SymbolicOpcode {
+                        pointer,
+                        value: code_expr,
+                    })
+                }
+            };
+            if !super::valid_instruction(code) {
+                return Err(Unsupported::UnknownOpcode { pointer, code });
+            }
+            let (arg3_mode, arg2_mode, arg1_mode, op) = decode_op(code);
+            self.ptr = match op {
+                1 | 2 | 7 | 8 => {
+                    let par1 = self.load(pointer + 1, arg1_mode)?;
+                    let par2 = self.load(pointer + 2, arg2_mode)?;
+                    let target = self.address(pointer + 3, arg3_mode)?;
+                    let value = match op {
+                        1 => Expr::add(par1, par2),
+                        2 => Expr::mul(par1, par2),
+                        7 => Expr::less(par1, par2),
+                        _ => {
+                            Expr::equal(par1.clone(), par2).ok_or(Unsupported::LoadComparison {
+                                pointer,
+                                value: par1,
+                            })?
+                        }
+                    };
+                    self.write(target, value);
+                    pointer + 4
+                }
+                3 => {
+                    let target = self.address(pointer + 1, arg1_mode)?;
+                    let value = self
+                        .input
+                        .pop_front()
+                        .ok_or(Unsupported::MissingInput { pointer })?;
+                    self.write(target, value);
+                    pointer + 2
+                }
+                4 => {
+                    let value = self.load(pointer + 1, arg1_mode)?;
+                    self.output.push(value);
+                    pointer + 2
+                }
+                5 | 6 => {
+                    let condition = self.load(pointer + 1, arg1_mode)?;
+                    let condition = self.concrete(condition, true)?;
+                    if (condition != 0) == (op == 5) {
+                        let target = self.load(pointer + 2, arg2_mode)?;
+                        self.concrete(target, false)? as usize
+                    } else {
+                        pointer + 3
+                    }
+                }
+                9 => {
+                    let offset = self.load(pointer + 1, arg1_mode)?;
+                    self.relative += self.concrete(offset, false)?;
+                    pointer + 2
+                }
+                _ => {
+                    return Ok(SymbolicRun {
+                        memory: self.memory,
+                        output: self.output,
+                    })
+                }
+            };
+        }
+        Err(Unsupported::StepLimit)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{read_codes, Intcode};
+    use super::*;
+    use crate::answers::puzzle_input;
+
+    #[test]
+    fn polynomial() {
+        let x = Polynomial::variable("x");
+        let y = Polynomial::variable("y");
+        let p = x
+            .add(&Polynomial::constant(3))
+            .mul(&y.add(&Polynomial::constant(-2)));
+        assert_eq!(p.to_string(), "x*y - 2*x + 3*y - 6");
+        assert_eq!(p.coefficient(&["y", "x"]), 1);
+        assert_eq!(p.coefficient(&[]), -6);
+        assert_eq!(x.mul(&x).to_string(), "x*x");
+        assert_eq!(
+            x.add(&x.mul(&Polynomial::constant(-1))),
+            Polynomial::default()
+        );
+    }
+
+    #[test]
+    fn straight_line() {
+        // out = (a + 5) * b, also compares a with b
+        let memory = read_codes("1001,20,5,21,2,21,22,23,7,20,22,24,4,23,99").unwrap();
+        let run = Symbolic::new(&memory)
+            .variable(20, "a")
+            .variable(22, "b")
+            .run()
+            .unwrap();
+        assert_eq!(run.output, vec![run.cell(23)]);
+        assert_eq!(run.cell(23).to_string(), "a*b + 5*b");
+        assert_eq!(run.cell(24).to_string(), "(a < b)");
+
+        let values: HashMap<String, i64> = vec![("a".to_string(), 2), ("b".to_string(), 3)]
+            .into_iter()
+            .collect();
+        assert_eq!(run.cell(23).eval(&values), Some(21));
+        assert_eq!(run.cell(24).eval(&values), Some(1));
+    }
+
+    #[test]
+    fn symbolic_reads() {
+        let memory = read_codes("4,5,99").unwrap();
+        let run = Symbolic::new(&memory).variable(1, "p").run().unwrap();
+        assert_eq!(run.output[0].to_string(), "mem[p]");
+        assert_eq!(run.output[0].eval(&HashMap::new()), None);
+    }
+
+    #[test]
+    fn input_variables() {
+        let memory = read_codes("3,0,3,1,2,0,1,0,4,0,99").unwrap();
+        let run = Symbolic::new(&memory)
+            .input_variable("x")
+            .input_value(7)
+            .run()
+            .unwrap();
+        assert_eq!(run.output[0].to_string(), "7*x");
+    }
+
+    #[test]
+    fn unsupported() {
+        // branches on the input
+        let memory = read_codes("3,10,1005,10,6,99,104,1,99,0,0").unwrap();
+        let result = Symbolic::new(&memory).input_variable("x").run();
+        assert_eq!(
+            result.unwrap_err(),
+            Unsupported::SymbolicBranch {
+                pointer: 2,
+                condition: Expr::variable("x")
+            }
+        );
+
+        // writes through a symbolic pointer
+        let memory = read_codes("1101,1,2,0,99").unwrap();
+        let result = Symbolic::new(&memory).variable(3, "p").run();
+        assert!(matches!(
+            result,
+            Err(Unsupported::SymbolicAddress { pointer: 0, .. })
+        ));
+
+        let result = Symbolic::new(&read_codes("3,0,99").unwrap()).run();
+        assert_eq!(
+            result.unwrap_err(),
+            Unsupported::MissingInput { pointer: 0 }
+        );
+
+        // writes below address 0 through the relative base
+        let memory = read_codes("109,-5,21101,1,1,0,99").unwrap();
+        let result = Symbolic::new(&memory).run();
+        assert_eq!(
+            result.unwrap_err(),
+            Unsupported::NegativeAddress {
+                pointer: 2,
+                address: -5
+            }
+        );
+
+        // compares mem[p] with itself
+        let memory = read_codes("8,0,0,7,4,7,99,0").unwrap();
+        let result = Symbolic::new(&memory)
+            .variable(1, "p")
+            .variable(2, "p")
+            .run();
+        assert!(matches!(
+            result,
+            Err(Unsupported::LoadComparison { pointer: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn aoc02() {
+        let input = match puzzle_input(2) {
+            Some(input) => input,
+            None => return,
+        };
+        let memory = read_codes(&input).unwrap();
+        let run = Symbolic::new(&memory)
+            .variable(1, "noun")
+            .variable(2, "verb")
+            .run()
+            .unwrap();
+        let formula = run.cell(0);
+        let polynomial = formula.as_polynomial().unwrap();
+
+        // part 1 from the formula
+        let values: HashMap<String, i64> = vec![("noun".to_string(), 12), ("verb".to_string(), 2)]
+            .into_iter()
+            .collect();
+        let mut intcode = Intcode::new(memory.clone());
+        intcode.memory[1] = 12;
+        intcode.memory[2] = 2;
+        intcode.run_until_input();
+        assert_eq!(formula.eval(&values), Some(intcode.memory[0]));
+
+        // part 2: output = a*noun + verb + c, noun and verb both below 100
+        let a = polynomial.coefficient(&["noun"]);
+        let c = polynomial.coefficient(&[]);
+        assert_eq!(polynomial.coefficient(&["verb"]), 1);
+        assert_eq!(formula.to_string(), format!("{}*noun + verb + {}", a, c));
+        let remaining = 19690720 - c;
+        let (noun, verb) = (remaining / a, remaining % a);
+        assert_eq!(noun * 100 + verb, 3892);
+    }
+}
@@ -0,0 +1,507 @@
+// Every day's solution, plus the shared Intcode tooling
+// `DAYS` registers each day so tools can run any of them through `Solution`.
+// Days are grouped behind cargo features, `DAYS` only holds the ones built.
+pub mod answers;
+#[cfg(feature = "math-days")]
+pub mod aoc01;
+#[cfg(feature = "intcode-days")]
+pub mod aoc02;
+#[cfg(feature = "grid-days")]
+pub mod aoc03;
+#[cfg(feature = "math-days")]
+pub mod aoc04;
+#[cfg(feature = "intcode-days")]
+pub mod aoc05;
+#[cfg(feature = "math-days")]
+pub mod aoc06;
+#[cfg(feature = "intcode-days")]
+pub mod aoc07;
+#[cfg(feature = "math-days")]
+pub mod aoc08;
+#[cfg(feature = "intcode-days")]
+pub mod aoc09;
+#[cfg(feature = "grid-days")]
+pub mod aoc10;
+#[cfg(feature = "intcode-days")]
+pub mod aoc11;
+#[cfg(feature = "math-days")]
+pub mod aoc12;
+#[cfg(feature = "intcode-days")]
+pub mod aoc13;
+#[cfg(feature = "math-days")]
+pub mod aoc14;
+#[cfg(feature = "intcode-days")]
+pub mod aoc15;
+#[cfg(feature = "math-days")]
+pub mod aoc16;
+#[cfg(feature = "intcode-days")]
+pub mod aoc17;
+#[cfg(feature = "grid-days")]
+pub mod aoc18;
+#[cfg(feature = "intcode-days")]
+pub mod aoc19;
+#[cfg(feature = "grid-days")]
+pub mod aoc20;
+#[cfg(feature = "intcode-days")]
+pub mod aoc21;
+#[cfg(feature = "math-days")]
+pub mod aoc22;
+#[cfg(feature = "intcode-days")]
+pub mod aoc23;
+#[cfg(feature = "grid-days")]
+pub mod aoc24;
+#[cfg(feature = "intcode-days")]
+pub mod aoc25;
+pub mod grid;
+pub mod intcode;
+pub mod live;
+pub mod log;
+pub mod number;
+pub mod ocr;
+pub mod params;
+pub mod parse;
+pub mod random;
+pub mod record;
+pub mod render;
+pub mod search;
+mod solution;
+
+pub use parse::ParseError;
+pub use render::Picture;
+pub use solution::{Answer, Bitmap, Day, Input, Solution, Tuning};
+
+pub static DAYS: &[Day] = &[
+    #[cfg(feature = "math-days")]
+    Day {
+        day: 1,
+        input: Input::File,
+        first: |input| aoc01::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc01::solve_second(input).map(Answer::from)),
+        render: None,
+        record: None,
+        live: None,
+        generate: None,
+        tuning: None,
+    },
+    #[cfg(feature = "intcode-days")]
+    Day {
+        day: 2,
+        input: Input::File,
+        first: |input| aoc02::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc02::solve_second(input).map(Answer::from)),
+        render: None,
+        record: None,
+        live: None,
+        generate: None,
+        tuning: None,
+    },
+    #[cfg(feature = "grid-days")]
+    Day {
+        day: 3,
+        input: Input::File,
+        first: |input| aoc03::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc03::solve_second(input).map(Answer::from)),
+        render: None,
+        record: None,
+        live: None,
+        generate: Some(aoc03::generate),
+        tuning: None,
+    },
+    #[cfg(feature = "math-days")]
+    Day {
+        day: 4,
+        input: Input::None,
+        first: |_| Ok(aoc04::solve_first().into()),
+        second: Some(|_| Ok(aoc04::solve_second().into())),
+        render: None,
+        record: None,
+        live: None,
+        generate: None,
+        tuning: None,
+    },
+    #[cfg(feature = "intcode-days")]
+    Day {
+        day: 5,
+        input: Input::File,
+        first: |input| aoc05::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc05::solve_second(input).map(Answer::from)),
+        render: None,
+        record: None,
+        live: None,
+        generate: None,
+        tuning: None,
+    },
+    #[cfg(feature = "math-days")]
+    Day {
+        day: 6,
+        input: Input::File,
+        first: |input| aoc06::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc06::solve_second(input).map(Answer::from)),
+        render: None,
+        record: None,
+        live: None,
+        generate: Some(aoc06::generate),
+        tuning: None,
+    },
+    #[cfg(feature = "intcode-days")]
+    Day {
+        day: 7,
+        input: Input::File,
+        first: |input| aoc07::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc07::solve_second(input).map(Answer::from)),
+        render: None,
+        record: None,
+        live: None,
+        generate: None,
+        tuning: None,
+    },
+    #[cfg(feature = "math-days")]
+    Day {
+        day: 8,
+        input: Input::File,
+        first: |input| aoc08::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc08::message(input).and_then(Answer::lettering)),
+        render: Some(aoc08::render),
+        record: None,
+        live: None,
+        generate: None,
+        tuning: Some(Tuning {
+            defaults: params::defaults::<aoc08::Params>,
+            solve: |part, input, overrides| {
+                let params = params::with::<aoc08::Params>(overrides)?;
+                Ok(match part {
+                    1 => Some(aoc08::solve_first_with(input, &params)?.map(Answer::from)),
+                    2 => Some(aoc08::message_with(input, &params)?.and_then(Answer::lettering)),
+                    _ => None,
+                })
+            },
+        }),
+    },
+    #[cfg(feature = "intcode-days")]
+    Day {
+        day: 9,
+        input: Input::File,
+        first: |input| aoc09::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc09::solve_second(input).map(Answer::from)),
+        render: None,
+        record: None,
+        live: None,
+        generate: None,
+        tuning: None,
+    },
+    #[cfg(feature = "grid-days")]
+    Day {
+        day: 10,
+        input: Input::File,
+        first: |input| aoc10::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc10::solve_second(input).map(Answer::from)),
+        render: None,
+        record: None,
+        live: None,
+        generate: Some(aoc10::generate),
+        tuning: None,
+    },
+    #[cfg(feature = "intcode-days")]
+    Day {
+        day: 11,
+        input: Input::File,
+        first: |input| aoc11::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc11::registration(input).and_then(Answer::lettering)),
+        render: Some(aoc11::render),
+        record: Some(aoc11::record),
+        live: None,
+        generate: None,
+        tuning: None,
+    },
+    #[cfg(feature = "math-days")]
+    Day {
+        day: 12,
+        input: Input::File,
+        first: |input| aoc12::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc12::solve_second(input).map(Answer::from)),
+        render: None,
+        record: None,
+        live: None,
+        generate: None,
+        tuning: Some(Tuning {
+            defaults: params::defaults::<aoc12::Params>,
+            solve: |part, input, overrides| {
+                let params = params::with::<aoc12::Params>(overrides)?;
+                Ok(match part {
+                    1 => Some(aoc12::solve_first_with(input, &params)?.map(Answer::from)),
+                    2 => Some(aoc12::solve_second(input).map(Answer::from)),
+                    _ => None,
+                })
+            },
+        }),
+    },
+    #[cfg(feature = "intcode-days")]
+    Day {
+        day: 13,
+        input: Input::File,
+        first: |input| aoc13::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc13::solve_second(input).map(Answer::from)),
+        render: Some(aoc13::render),
+        record: Some(aoc13::record),
+        live: Some(aoc13::live),
+        generate: None,
+        tuning: None,
+    },
+    #[cfg(feature = "math-days")]
+    Day {
+        day: 14,
+        input: Input::File,
+        first: |input| aoc14::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc14::solve_second(input).map(Answer::from)),
+        render: None,
+        record: None,
+        live: None,
+        generate: Some(aoc14::generate),
+        tuning: Some(Tuning {
+            defaults: params::defaults::<aoc14::Params>,
+            solve: |part, input, overrides| {
+                let params = params::with::<aoc14::Params>(overrides)?;
+                Ok(match part {
+                    1 => Some(aoc14::solve_first(input).map(Answer::from)),
+                    2 => Some(aoc14::solve_second_with(input, &params)?.map(Answer::from)),
+                    _ => None,
+                })
+            },
+        }),
+    },
+    #[cfg(feature = "intcode-days")]
+    Day {
+        day: 15,
+        input: Input::File,
+        first: |input| aoc15::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc15::solve_second(input).map(Answer::from)),
+        render: Some(aoc15::render),
+        record: Some(aoc15::record),
+        live: Some(aoc15::live),
+        generate: None,
+        tuning: None,
+    },
+    #[cfg(feature = "math-days")]
+    Day {
+        day: 16,
+        input: Input::File,
+        first: |input| aoc16::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc16::solve_second(input).map(Answer::from)),
+        render: None,
+        record: None,
+        live: None,
+        generate: None,
+        tuning: Some(Tuning {
+            defaults: params::defaults::<aoc16::Params>,
+            solve: |part, input, overrides| {
+                let params = params::with::<aoc16::Params>(overrides)?;
+                Ok(match part {
+                    1 => Some(aoc16::solve_first_with(input, &params)?.map(Answer::from)),
+                    2 => Some(aoc16::solve_second_with(input, &params)?.map(Answer::from)),
+                    _ => None,
+                })
+            },
+        }),
+    },
+    #[cfg(feature = "intcode-days")]
+    Day {
+        day: 17,
+        input: Input::File,
+        first: |input| aoc17::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc17::solve_second(input).map(Answer::from)),
+        render: Some(aoc17::render),
+        record: None,
+        live: Some(aoc17::live),
+        generate: None,
+        tuning: None,
+    },
+    #[cfg(feature = "grid-days")]
+    Day {
+        day: 18,
+        input: Input::File,
+        first: |input| aoc18::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc18::solve_second(input).map(Answer::from)),
+        render: Some(aoc18::render),
+        record: None,
+        live: None,
+        generate: Some(aoc18::generate),
+        tuning: None,
+    },
+    #[cfg(feature = "intcode-days")]
+    Day {
+        day: 19,
+        input: Input::File,
+        first: |input| aoc19::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc19::solve_second(input).map(Answer::from)),
+        render: None,
+        record: None,
+        live: None,
+        generate: None,
+        tuning: Some(Tuning {
+            defaults: params::defaults::<aoc19::Params>,
+            solve: |part, input, overrides| {
+                let params = params::with::<aoc19::Params>(overrides)?;
+                Ok(match part {
+                    1 => Some(aoc19::solve_first_with(input, &params)?.map(Answer::from)),
+                    2 => Some(aoc19::solve_second_with(input, &params)?.map(Answer::from)),
+                    _ => None,
+                })
+            },
+        }),
+    },
+    #[cfg(feature = "grid-days")]
+    Day {
+        day: 20,
+        input: Input::File,
+        first: |input| aoc20::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc20::solve_second(input).map(Answer::from)),
+        render: Some(aoc20::render),
+        record: None,
+        live: None,
+        generate: Some(aoc20::generate),
+        tuning: None,
+    },
+    #[cfg(feature = "intcode-days")]
+    Day {
+        day: 21,
+        input: Input::File,
+        first: |input| aoc21::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc21::solve_second(input).map(Answer::from)),
+        render: None,
+        record: None,
+        live: None,
+        generate: None,
+        tuning: None,
+    },
+    #[cfg(feature = "math-days")]
+    Day {
+        day: 22,
+        input: Input::File,
+        first: |input| aoc22::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc22::solve_second(input).map(Answer::from)),
+        render: None,
+        record: None,
+        live: None,
+        generate: Some(aoc22::generate),
+        tuning: Some(Tuning {
+            defaults: params::defaults::<aoc22::Params>,
+            solve: |part, input, overrides| {
+                let params = params::with::<aoc22::Params>(overrides)?;
+                Ok(match part {
+                    1 => Some(aoc22::solve_first_with(input, &params)?.map(Answer::from)),
+                    2 => Some(aoc22::solve_second_with(input, &params)?.map(Answer::from)),
+                    _ => None,
+                })
+            },
+        }),
+    },
+    #[cfg(feature = "intcode-days")]
+    Day {
+        day: 23,
+        input: Input::File,
+        first: |input| aoc23::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc23::solve_second(input).map(Answer::from)),
+        render: None,
+        record: None,
+        live: None,
+        generate: None,
+        tuning: Some(Tuning {
+            defaults: params::defaults::<aoc23::Params>,
+            solve: |part, input, overrides| {
+                let params = params::with::<aoc23::Params>(overrides)?;
+                Ok(match part {
+                    1 => Some(aoc23::solve_first_with(input, &params)?.map(Answer::from)),
+                    2 => Some(aoc23::solve_second_with(input, &params)?.map(Answer::from)),
+                    _ => None,
+                })
+            },
+        }),
+    },
+    #[cfg(feature = "grid-days")]
+    Day {
+        day: 24,
+        input: Input::File,
+        first: |input| aoc24::solve_first(input).map(Answer::from),
+        second: Some(|input| aoc24::solve_second(input).map(Answer::from)),
+        render: Some(aoc24::render),
+        record: Some(aoc24::record),
+        live: None,
+        generate: None,
+        tuning: Some(Tuning {
+            defaults: params::defaults::<aoc24::Params>,
+            solve: |part, input, overrides| {
+                let params = params::with::<aoc24::Params>(overrides)?;
+                Ok(match part {
+                    1 => Some(aoc24::solve_first(input).map(Answer::from)),
+                    2 => Some(aoc24::solve_second_with(input, &params)?.map(Answer::from)),
+                    _ => None,
+                })
+            },
+        }),
+    },
+    #[cfg(feature = "intcode-days")]
+    Day {
+        day: 25,
+        input: Input::Interactive,
+        first: |input| aoc25::solve_first(input).map(Answer::from),
+        second: None,
+        render: None,
+        record: None,
+        live: None,
+        generate: None,
+        tuning: None,
+    },
+];
+
+// None if there's no such day or it was compiled out
+pub fn solution(day: u32) -> Option<&'static dyn Solution> {
+    DAYS.iter()
+        .find(|d| d.day == day)
+        .map(|d| d as &dyn Solution)
+}
+
+// the cargo feature a day is built with
+pub fn feature(day: u32) -> Option<&'static str> {
+    match day {
+        2 | 5 | 7 | 9 | 11 | 13 | 15 | 17 | 19 | 21 | 23 | 25 => Some("intcode-days"),
+        3 | 10 | 18 | 20 | 24 => Some("grid-days"),
+        1 | 4 | 6 | 8 | 12 | 14 | 16 | 22 => Some("math-days"),
+        _ => None,
+    }
+}
+
+// days whose feature this build was made without
+pub fn compiled_out() -> Vec<u32> {
+    (1..=25).filter(|&day| solution(day).is_none()).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn registry() {
+        let mut days: Vec<u32> = DAYS.iter().map(|d| d.day).collect();
+        days.extend(compiled_out());
+        days.sort();
+        assert_eq!(days, (1..=25).collect::<Vec<_>>());
+        assert!(solution(26).is_none());
+        assert_eq!(feature(26), None);
+    }
+
+    #[test]
+    #[cfg(feature = "intcode-days")]
+    fn registry_intcode() {
+        assert_eq!(solution(25).unwrap().parts(), 1);
+        assert_eq!(feature(25), Some("intcode-days"));
+    }
+
+    #[test]
+    #[cfg(feature = "math-days")]
+    fn registry_math() {
+        assert_eq!(
+            solution(4).unwrap().solve(1, ""),
+            Some(Ok(Answer::Integer(511)))
+        );
+    }
+}
@@ -0,0 +1,13 @@
+// Days that can be watched while their Intcode program runs
+// A session owns the day's VM and the state it builds up; the terminal UI
+// advances it a step at a time and draws its picture in between. What a
+// step is depends on the day, e.g. one ball move or one droid move.
+use crate::render::Picture;
+
+pub trait Session {
+    // runs the program up to the next step, false if it had already halted
+    fn step(&mut self) -> bool;
+    fn picture(&self) -> Picture;
+    // a line about the state beside the picture, e.g. the score
+    fn status(&self) -> String;
+}
@@ -0,0 +1,182 @@
+// Leveled logging for what the days and tools say on the side
+// Records go to stderr, or a file with `--log-file`, and only when their
+// level is at or above the one set for their module, so the days are quiet
+// unless asked. `--log aoc21=debug,info` shows day 21's droid transcript and
+// everything else's info.
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Level, String> {
+        match s {
+            "error" => Ok(Level::Error),
+            "warn" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!(
+                "invalid log level '{}', expected error, warn, info, debug or trace",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        };
+        f.write_str(name)
+    }
+}
+
+// the most verbose level each module logs at, the longest matching module
+// wins and everything else gets the default
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    default: Level,
+    modules: Vec<(String, Level)>,
+}
+
+impl Default for Filter {
+    fn default() -> Filter {
+        Filter::new()
+    }
+}
+
+impl Filter {
+    // warnings and errors from everywhere
+    pub const fn new() -> Filter {
+        Filter {
+            default: Level::Warn,
+            modules: Vec::new(),
+        }
+    }
+
+    // comma separated `level` or `module=level`, e.g. `aoc25=info,error`
+    pub fn parse(spec: &str) -> Result<Filter, String> {
+        let mut filter = Filter::default();
+        for directive in spec.split(',').filter(|d| !d.is_empty()) {
+            match directive.find('=') {
+                Some(idx) => {
+                    let module = &directive[..idx];
+                    if module.is_empty() {
+                        return Err(format!("invalid log filter '{}'", directive));
+                    }
+                    filter
+                        .modules
+                        .push((module.to_string(), directive[idx + 1..].parse()?));
+                }
+                None => filter.default = directive.parse()?,
+            }
+        }
+        Ok(filter)
+    }
+
+    pub fn level(&self, module: &str) -> Level {
+        let module = short_name(module);
+        self.modules
+            .iter()
+            .filter(|(name, _)| {
+                module == name
+                    || module.starts_with(name.as_str()) && module[name.len()..].starts_with("::")
+            })
+            .max_by_key(|(name, _)| name.len())
+            .map_or(self.default, |&(_, level)| level)
+    }
+}
+
+// `aoc2019::aoc21` is just `aoc21` in filters and records
+fn short_name(module: &str) -> &str {
+    module.strip_prefix("aoc2019::").unwrap_or(module)
+}
+
+struct Logger {
+    filter: Filter,
+    // None is stderr
+    file: Option<File>,
+}
+
+static LOGGER: Mutex<Logger> = Mutex::new(Logger {
+    filter: Filter::new(),
+    file: None,
+});
+
+fn logger() -> std::sync::MutexGuard<'static, Logger> {
+    // a panic while logging leaves nothing half done worth refusing over
+    LOGGER.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn set_filter(filter: Filter) {
+    logger().filter = filter;
+}
+
+// records from now on are appended to `file` instead of stderr
+pub fn set_file(file: File) {
+    logger().file = Some(file);
+}
+
+pub fn enabled(level: Level, module: &str) -> bool {
+    level <= logger().filter.level(module)
+}
+
+pub fn write(level: Level, module: &str, args: fmt::Arguments) {
+    let record = format!("{} {}: {}\n", level, short_name(module), args);
+    let mut logger = logger();
+    // there's nowhere left to report a failed log write
+    let _ = match &mut logger.file {
+        Some(file) => file.write_all(record.as_bytes()),
+        None => io::stderr().write_all(record.as_bytes()),
+    };
+}
+
+// `log!(Debug, "{} steps", steps)`, formatted only if it'll be written
+macro_rules! log {
+    ($level:ident, $($arg:tt)+) => {
+        if $crate::log::enabled($crate::log::Level::$level, module_path!()) {
+            $crate::log::write($crate::log::Level::$level, module_path!(), format_args!($($arg)+));
+        }
+    };
+}
+
+pub(crate) use log;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_filter() {
+        let filter = Filter::parse("aoc21=debug,info,aoc2=error,intcode::fast=trace").unwrap();
+        assert_eq!(filter.level("aoc2019::aoc21"), Level::Debug);
+        assert_eq!(filter.level("aoc2019::aoc2"), Level::Error);
+        assert_eq!(filter.level("aoc2019::aoc25"), Level::Info);
+        assert_eq!(filter.level("aoc2019::intcode::fast::ops"), Level::Trace);
+        assert_eq!(filter.level("aoc2019::intcode"), Level::Info);
+        assert_eq!(Filter::parse("").unwrap(), Filter::default());
+        assert_eq!(Filter::default().level("aoc2019::aoc25"), Level::Warn);
+
+        assert!(Filter::parse("loud").is_err());
+        assert!(Filter::parse("aoc21=loud").is_err());
+        assert!(Filter::parse("=info").is_err());
+    }
+}
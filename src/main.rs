@@ -1,46 +1,685 @@
-#![allow(dead_code)]
 use std::env;
-use std::fs::File;
-use std::io::prelude::*;
-
-mod aoc01;
-mod aoc02;
-mod aoc03;
-mod aoc04;
-mod aoc05;
-mod aoc06;
-mod aoc07;
-mod aoc08;
-mod aoc09;
-mod aoc10;
-mod aoc11;
-mod aoc12;
-mod aoc13;
-mod aoc14;
-mod aoc15;
-mod aoc16;
-mod aoc17;
-mod aoc18;
-mod aoc19;
-mod aoc20;
-mod aoc21;
-mod aoc22;
-mod aoc23;
-mod aoc24;
-mod aoc25;
-
-fn main() -> std::io::Result<()> {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        println!("Input file missing");
-        return Ok(());
-    }
-
-    println!("{}", &args[1]);
-    let mut file = File::open(&args[1])?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
-    println!("{}", aoc25::solve_first(&contents));
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::process;
+use std::time::{Duration, Instant};
 
+use aoc2019::answers::{self, Check};
+use aoc2019::log::{self, Filter};
+use aoc2019::params::{self, Overrides};
+use aoc2019::record::Recorder;
+use aoc2019::render::Target;
+use aoc2019::{Answer, Input, Solution, DAYS};
+
+mod pool;
+mod report;
+mod timing;
+mod tui;
+
+use report::{Format, Outcome};
+use timing::{Baseline, Timing};
+
+const USAGE: &str =
+    "usage: aoc2019 [--format text|json] [--artifacts DIR] [--param NAME=VALUE]... <day> [part] [input-path|-]
+       aoc2019 [--format text|json] [--artifacts DIR] [--jobs N] --all
+       aoc2019 --render ascii|ansi|png [--scale N] [--artifacts DIR] <day> [input-path|-]
+       aoc2019 --record [--every N] [--max-frames N] [--fps N] [--scale N] [--artifacts DIR] <day> [input-path|-]
+       aoc2019 --live [--speed N] <day> [input-path]
+       aoc2019 --generate <day> [--size N] [--seed N]
+       aoc2019 --params <day>
+       aoc2019 --check
+       aoc2019 --time [--warmup N] [--repeats N] [--baseline FILE] [--save-baseline FILE]
+
+input defaults to src/aocDD/input, - reads it from stdin
+--param changes one of the day's puzzle constants, --params lists them
+with their defaults
+--format json prints one object per solution, image answers and the
+pictures days 8 and 11 read their letters from are written as PNG files
+to the artifacts directory (default artifacts)
+--jobs has --all run N days at a time (default one per CPU), still
+reporting them in day order
+--render draws the day's puzzle instead of solving it, PNGs go to
+the artifacts directory with every tile N pixels wide (default 4)
+--record writes the day's simulation to the artifacts directory as a GIF,
+keeping every Nth step (default 1) up to --max-frames (default 500)
+played at --fps frames per second (default 10)
+--live shows the day's program running in the terminal, starting at
+N steps per frame (default 1); space pauses, n steps, + and - change speed
+--generate prints a random input for the day, growing with --size
+(default 10), the same every time for the same --seed (default 0)
+--check compares every day with the answers in answers.txt
+--time runs every day and prints a table of median times,
+flagging days slower than the baseline and failing if there are any
+--log FILTER goes before any of these and picks what the days log to
+stderr, e.g. aoc21=debug for day 21's droid reports (default warn),
+--log-file FILE writes the log to FILE instead";
+
+// a panicking solution counts as a failure
+fn solve(
+    solution: &dyn Solution,
+    part: u32,
+    input: &str,
+    overrides: &Overrides,
+) -> Result<Answer, String> {
+    let day = solution.day();
+    let solved = || solution.solve_with(part, input, overrides);
+    match panic::catch_unwind(AssertUnwindSafe(solved)) {
+        Ok(Ok(Some(Ok(answer)))) => Ok(answer),
+        Ok(Ok(Some(Err(e)))) => Err(format!("invalid input, {}", e)),
+        Ok(Ok(None)) => Err(format!("day {} has no part {}", day, part)),
+        Ok(Err(e)) => Err(e),
+        Err(_) => Err(format!("day {} part {} panicked", day, part)),
+    }
+}
+
+// why a day isn't in this build
+fn compiled_out(day: u32) -> String {
+    match aoc2019::feature(day) {
+        Some(feature) => format!("compiled out, rebuild with --features {}", feature),
+        None => String::from("no solution"),
+    }
+}
+
+fn default_input(day: u32) -> String {
+    format!("src/aoc{:02}/input", day)
+}
+
+fn read_input(solution: &dyn Solution, path: Option<&str>) -> Result<String, String> {
+    let day = solution.day();
+    match path {
+        Some("-") => {
+            let mut contents = String::new();
+            io::stdin()
+                .read_to_string(&mut contents)
+                .map_err(|e| format!("couldn't read stdin: {}", e))?;
+            Ok(contents)
+        }
+        None if solution.input() == Input::None => Ok(String::new()),
+        path => {
+            let path = path.map(String::from).unwrap_or_else(|| default_input(day));
+            fs::read_to_string(&path).map_err(|e| format!("couldn't read {}: {}", path, e))
+        }
+    }
+}
+
+fn parse_number(
+    arg: &str,
+    what: &str,
+    range: std::ops::RangeInclusive<u32>,
+) -> Result<u32, String> {
+    match arg.parse() {
+        Ok(n) if range.contains(&n) => Ok(n),
+        _ => Err(format!(
+            "invalid {} '{}', expected {} to {}",
+            what,
+            arg,
+            range.start(),
+            range.end()
+        )),
+    }
+}
+
+struct Output {
+    format: Format,
+    artifacts: String,
+    render: Option<Target>,
+    scale: u32,
+    record: bool,
+    every: usize,
+    max_frames: usize,
+    fps: u32,
+    live: bool,
+    speed: u32,
+    jobs: usize,
+    params: Vec<(String, String)>,
+    outcomes: Vec<Outcome>,
+}
+
+impl Output {
+    fn new() -> Output {
+        Output {
+            format: Format::Text,
+            artifacts: String::from("artifacts"),
+            render: None,
+            scale: 4,
+            record: false,
+            every: 1,
+            max_frames: 500,
+            fps: 10,
+            live: false,
+            speed: 1,
+            jobs: pool::default_jobs(),
+            params: Vec::new(),
+            outcomes: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, mut outcome: Outcome) {
+        match self.format {
+            Format::Text => outcome.print_text(),
+            Format::Json => {
+                if let Err(e) = outcome.save_artifacts(&self.artifacts) {
+                    eprintln!("{}", e);
+                }
+            }
+        }
+        self.outcomes.push(outcome);
+    }
+
+    // prints the JSON report, true if every solution succeeded
+    fn finish(self) -> bool {
+        if self.format == Format::Json {
+            println!("{}", report::json(&self.outcomes));
+        }
+        self.outcomes.iter().all(|o| o.result.is_ok())
+    }
+}
+
+// runs one part of a day, or every part if none is given
+fn outcomes(
+    solution: &dyn Solution,
+    part: Option<u32>,
+    path: Option<&str>,
+    overrides: &Overrides,
+) -> Vec<Outcome> {
+    let parts = match part {
+        Some(part) => part..=part,
+        None => 1..=solution.parts(),
+    };
+    let input = read_input(solution, path);
+    let mut result = Vec::new();
+    for part in parts {
+        let start = Instant::now();
+        let answer = match &input {
+            Ok(input) => solve(solution, part, input, overrides),
+            Err(e) => Err(e.clone()),
+        };
+        result.push(Outcome {
+            day: solution.day(),
+            part,
+            result: answer,
+            duration: start.elapsed(),
+            artifacts: Vec::new(),
+        });
+    }
+    result
+}
+
+fn run_day(solution: &dyn Solution, part: Option<u32>, path: Option<&str>, output: &mut Output) {
+    for outcome in outcomes(solution, part, path, &output.params.clone()) {
+        output.add(outcome);
+    }
+}
+
+// draws a day's puzzle instead of solving it
+fn run_render(solution: &dyn Solution, path: Option<&str>, output: &Output) -> Result<(), String> {
+    let day = solution.day();
+    let input = read_input(solution, path)?;
+    let picture = match panic::catch_unwind(AssertUnwindSafe(|| solution.render(&input))) {
+        Ok(Some(Ok(picture))) => picture,
+        Ok(Some(Err(e))) => return Err(format!("day {}: invalid input, {}", day, e)),
+        Ok(None) => return Err(format!("day {} has nothing to render", day)),
+        Err(_) => return Err(format!("day {} render panicked", day)),
+    };
+
+    match output.render {
+        Some(Target::Png) => {
+            let dir = &output.artifacts;
+            fs::create_dir_all(dir).map_err(|e| format!("couldn't create {}: {}", dir, e))?;
+            let name = format!("day{:02}.png", day);
+            let path = Path::new(dir).join(name).to_string_lossy().into_owned();
+            picture.save_png(&path, output.scale)?;
+            println!("day {:02}: wrote {}", day, path);
+        }
+        Some(Target::Ansi) => print!("{}", picture.ansi()),
+        Some(Target::Ascii) | None => print!("{}", picture.ascii()),
+    }
+    Ok(())
+}
+
+// records a day's simulation as an animated GIF
+fn run_record(solution: &dyn Solution, path: Option<&str>, output: &Output) -> Result<(), String> {
+    let day = solution.day();
+    let input = read_input(solution, path)?;
+    let mut recorder = Recorder::new(output.every, output.max_frames, output.fps);
+    match panic::catch_unwind(AssertUnwindSafe(|| solution.record(&input, &mut recorder))) {
+        Ok(Some(Ok(()))) => {}
+        Ok(Some(Err(e))) => return Err(format!("day {}: invalid input, {}", day, e)),
+        Ok(None) => return Err(format!("day {} has nothing to record", day)),
+        Err(_) => return Err(format!("day {} recording panicked", day)),
+    }
+
+    let dir = &output.artifacts;
+    fs::create_dir_all(dir).map_err(|e| format!("couldn't create {}: {}", dir, e))?;
+    let name = format!("day{:02}.gif", day);
+    let path = Path::new(dir).join(name).to_string_lossy().into_owned();
+    recorder.save_gif(&path, output.scale)?;
+    println!(
+        "day {:02}: wrote {}, {} frames",
+        day,
+        path,
+        recorder.frames().len()
+    );
     Ok(())
 }
+
+// watches a day's program run, keys are read from stdin so the input can't be
+fn run_live(solution: &dyn Solution, path: Option<&str>, output: &Output) -> Result<(), String> {
+    let day = solution.day();
+    if path == Some("-") {
+        return Err(String::from(
+            "--live reads keys from stdin, give the input as a file",
+        ));
+    }
+    let input = read_input(solution, path)?;
+    let mut session = match panic::catch_unwind(AssertUnwindSafe(|| solution.live(&input))) {
+        Ok(Some(Ok(session))) => session,
+        Ok(Some(Err(e))) => return Err(format!("day {}: invalid input, {}", day, e)),
+        Ok(None) => return Err(format!("day {} has nothing to watch live", day)),
+        Err(_) => return Err(format!("day {} panicked", day)),
+    };
+    let run = || tui::run(day, session.as_mut(), output.speed);
+    match panic::catch_unwind(AssertUnwindSafe(run)) {
+        Ok(result) => result,
+        Err(_) => Err(format!("day {} panicked", day)),
+    }
+}
+
+// days run side by side on `output.jobs` threads, reported in day order
+fn run_all(output: &mut Output) {
+    let days: Vec<u32> = (1..=25).collect();
+    let run = |&day: &u32| match aoc2019::solution(day) {
+        None => Err(compiled_out(day)),
+        Some(solution) if solution.input() == Input::Interactive => {
+            Err(String::from("reads commands from stdin"))
+        }
+        Some(solution) => Ok(outcomes(solution, None, None, &[])),
+    };
+    pool::run(output.jobs, &days, run, |&day, result| match result {
+        Some(Ok(outcomes)) => {
+            for outcome in outcomes {
+                output.add(outcome);
+            }
+        }
+        Some(Err(skipped)) => {
+            if output.format == Format::Text {
+                println!("day {:02}: skipped, {}", day, skipped);
+            }
+        }
+        // solving catches its own panics, this is reading the input
+        None => output.add(Outcome {
+            day,
+            part: 1,
+            result: Err(format!("day {} panicked", day)),
+            duration: Duration::default(),
+            artifacts: Vec::new(),
+        }),
+    });
+}
+
+// compares against answers.txt, days without an input are skipped
+fn run_check() -> bool {
+    let mut wrong = 0;
+    let mut skipped = 0;
+    let expected = answers::expected();
+    for expected in expected.iter() {
+        let (day, part) = (expected.day, expected.part);
+        let solution = match aoc2019::solution(day) {
+            Some(solution) => solution,
+            None if aoc2019::feature(day).is_some() => {
+                println!(
+                    "day {:02} part {}: skipped, {}",
+                    day,
+                    part,
+                    compiled_out(day)
+                );
+                skipped += 1;
+                continue;
+            }
+            None => {
+                println!("day {:02} part {}: no solution", day, part);
+                wrong += 1;
+                continue;
+            }
+        };
+        let input = match read_input(solution, None) {
+            Ok(input) => input,
+            Err(e) => {
+                println!("day {:02} part {}: skipped, {}", day, part, e);
+                skipped += 1;
+                continue;
+            }
+        };
+        let check = || answers::check(solution, part, &input, &expected.answer);
+        match panic::catch_unwind(AssertUnwindSafe(check)) {
+            Ok(Check::Correct) => println!("day {:02} part {}: ok", day, part),
+            Ok(Check::Wrong(got)) => {
+                println!(
+                    "day {:02} part {}: WRONG, expected {} got {}",
+                    day, part, expected.answer, got
+                );
+                wrong += 1;
+            }
+            Ok(Check::Skipped(reason)) => {
+                println!("day {:02} part {}: skipped, {}", day, part, reason);
+                skipped += 1;
+            }
+            Err(_) => {
+                println!("day {:02} part {}: WRONG, panicked", day, part);
+                wrong += 1;
+            }
+        }
+    }
+    println!(
+        "{} correct, {} wrong, {} skipped",
+        expected.len() - wrong - skipped,
+        wrong,
+        skipped
+    );
+    wrong == 0
+}
+
+struct TimeOptions {
+    warmup: usize,
+    repeats: usize,
+    baseline: Option<String>,
+    save_baseline: Option<String>,
+}
+
+impl TimeOptions {
+    fn parse(args: &[String]) -> Result<TimeOptions, String> {
+        let mut options = TimeOptions {
+            warmup: 1,
+            repeats: 5,
+            baseline: None,
+            save_baseline: None,
+        };
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for {}", flag))?;
+            let count = || {
+                value
+                    .parse()
+                    .map_err(|_| format!("invalid value '{}' for {}", value, flag))
+            };
+            match flag.as_str() {
+                "--warmup" => options.warmup = count()?,
+                "--repeats" => options.repeats = count()?,
+                "--baseline" => options.baseline = Some(value.clone()),
+                "--save-baseline" => options.save_baseline = Some(value.clone()),
+                _ => return Err(format!("unknown option {}\n{}", flag, USAGE)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+// prints a random puzzle input, for testing the days beyond their inputs
+fn run_generate(args: &[String]) -> Result<bool, String> {
+    let (day, options) = match args {
+        [day, options @ ..] => (parse_number(day, "day", 1..=25)?, options),
+        [] => return Err(String::from(USAGE)),
+    };
+    let solution =
+        aoc2019::solution(day).ok_or_else(|| format!("day {}: {}", day, compiled_out(day)))?;
+
+    let (mut size, mut seed) = (10, 0);
+    let mut options = options.iter();
+    while let Some(flag) = options.next() {
+        let value = options
+            .next()
+            .ok_or_else(|| format!("missing value for {}", flag))?;
+        match flag.as_str() {
+            "--size" => size = parse_number(value, "size", 0..=10_000)? as usize,
+            "--seed" => {
+                seed = value
+                    .parse()
+                    .map_err(|_| format!("invalid value '{}' for {}", value, flag))?
+            }
+            _ => return Err(format!("unknown option {}\n{}", flag, USAGE)),
+        }
+    }
+
+    let input = solution
+        .generate(size, seed)
+        .ok_or_else(|| format!("day {} has no input generator", day))?;
+    print!("{}", input);
+    Ok(true)
+}
+
+fn run_timed(options: &TimeOptions) -> Result<bool, String> {
+    let baseline = match &options.baseline {
+        Some(path) => Some(Baseline::load(path)?),
+        None => None,
+    };
+
+    for day in aoc2019::compiled_out() {
+        eprintln!("day {:02}: skipped, {}", day, compiled_out(day));
+    }
+
+    let mut success = true;
+    let mut timings = Vec::new();
+    for solution in DAYS {
+        let day = solution.day;
+        if solution.input() == Input::Interactive {
+            continue;
+        }
+        let input = match read_input(solution, None) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("day {:02}: {}", day, e);
+                success = false;
+                continue;
+            }
+        };
+        for part in 1..=solution.parts() {
+            let solve = || solve(solution, part, &input, &[]).map(|answer| answer.to_string());
+            match timing::measure(options.warmup, options.repeats, solve) {
+                Ok((answer, median)) => timings.push(Timing {
+                    day,
+                    part,
+                    answer,
+                    median,
+                }),
+                Err(e) => {
+                    eprintln!("day {:02}: {}", day, e);
+                    success = false;
+                }
+            }
+        }
+    }
+
+    let regressions = timing::report(&timings, baseline.as_ref());
+    if let Some(path) = &options.save_baseline {
+        Baseline::from_timings(&timings).save(path)?;
+    }
+    // a regression fails the run, so a script can catch it
+    Ok(success && regressions == 0)
+}
+
+fn run(args: &[String]) -> Result<bool, String> {
+    let mut output = Output::new();
+    let mut args = args;
+    loop {
+        match args {
+            [flag, value, rest @ ..] if flag == "--format" => {
+                output.format = Format::parse(value)?;
+                args = rest;
+            }
+            [flag, value, rest @ ..] if flag == "--artifacts" => {
+                output.artifacts = value.clone();
+                args = rest;
+            }
+            [flag, value, rest @ ..] if flag == "--render" => {
+                output.render = Some(Target::parse(value)?);
+                args = rest;
+            }
+            [flag, value, rest @ ..] if flag == "--scale" => {
+                output.scale = parse_number(value, "scale", 1..=64)?;
+                args = rest;
+            }
+            [flag, rest @ ..] if flag == "--record" => {
+                output.record = true;
+                args = rest;
+            }
+            [flag, value, rest @ ..] if flag == "--every" => {
+                output.every = parse_number(value, "step interval", 1..=1_000_000)? as usize;
+                args = rest;
+            }
+            [flag, value, rest @ ..] if flag == "--max-frames" => {
+                output.max_frames = parse_number(value, "frame limit", 1..=10_000)? as usize;
+                args = rest;
+            }
+            [flag, value, rest @ ..] if flag == "--fps" => {
+                output.fps = parse_number(value, "frame rate", 1..=100)?;
+                args = rest;
+            }
+            [flag, rest @ ..] if flag == "--live" => {
+                output.live = true;
+                args = rest;
+            }
+            [flag, value, rest @ ..] if flag == "--param" => {
+                output.params.push(params::parse_override(value)?);
+                args = rest;
+            }
+            [flag, value, rest @ ..] if flag == "--jobs" => {
+                output.jobs = parse_number(value, "job count", 1..=256)? as usize;
+                args = rest;
+            }
+            [flag, value, rest @ ..] if flag == "--log" => {
+                log::set_filter(Filter::parse(value)?);
+                args = rest;
+            }
+            [flag, value, rest @ ..] if flag == "--log-file" => {
+                let file =
+                    File::create(value).map_err(|e| format!("couldn't create {}: {}", value, e))?;
+                log::set_file(file);
+                args = rest;
+            }
+            [flag, value, rest @ ..] if flag == "--speed" => {
+                output.speed = parse_number(value, "speed", 1..=tui::MAX_SPEED)?;
+                args = rest;
+            }
+            _ => break,
+        }
+    }
+
+    match args {
+        [flag, ..] if output.render.is_some() && flag.starts_with("--") => {
+            Err(format!("--render draws a single day, not {}", flag))
+        }
+        [flag, ..] if output.record && flag.starts_with("--") => {
+            Err(format!("--record records a single day, not {}", flag))
+        }
+        [flag, ..] if output.live && flag.starts_with("--") => {
+            Err(format!("--live shows a single day, not {}", flag))
+        }
+        [flag, ..] if !output.params.is_empty() && flag.starts_with("--") => {
+            Err(format!("--param changes a single day, not {}", flag))
+        }
+        [flag, day] if flag == "--params" => {
+            let day = parse_number(day, "day", 1..=25)?;
+            let solution = aoc2019::solution(day)
+                .ok_or_else(|| format!("day {}: {}", day, compiled_out(day)))?;
+            let parameters = solution.parameters();
+            if parameters.is_empty() {
+                return Err(format!("day {} has no parameters", day));
+            }
+            for (name, value) in parameters {
+                println!("{}={}", name, value);
+            }
+            Ok(true)
+        }
+        [flag] if flag == "--all" => {
+            run_all(&mut output);
+            Ok(output.finish())
+        }
+        [flag] if flag == "--check" => Ok(run_check()),
+        [flag, options @ ..] if flag == "--time" => run_timed(&TimeOptions::parse(options)?),
+        [flag, rest @ ..] if flag == "--generate" => run_generate(rest),
+        [day, rest @ ..] if rest.len() <= 2 => {
+            let day = parse_number(day, "day", 1..=25)?;
+            let solution = aoc2019::solution(day)
+                .ok_or_else(|| format!("day {}: {}", day, compiled_out(day)))?;
+            let (part, path) = match rest {
+                [] => (None, None),
+                [part] if part.parse::<u32>().is_ok() => {
+                    (Some(parse_number(part, "part", 1..=2)?), None)
+                }
+                [path] => (None, Some(path.as_str())),
+                [part, path] => (
+                    Some(parse_number(part, "part", 1..=2)?),
+                    Some(path.as_str()),
+                ),
+                _ => unreachable!(),
+            };
+            if output.render.is_some() || output.record || output.live {
+                if part.is_some() {
+                    return Err(String::from(
+                        "--render, --record and --live take the whole day, drop the part",
+                    ));
+                }
+                if !output.params.is_empty() {
+                    return Err(String::from(
+                        "--render, --record and --live use the puzzle's own parameters",
+                    ));
+                }
+                if output.live {
+                    run_live(solution, path, &output)?;
+                } else if output.record {
+                    run_record(solution, path, &output)?;
+                } else {
+                    run_render(solution, path, &output)?;
+                }
+                return Ok(true);
+            }
+            run_day(solution, part, path, &mut output);
+            Ok(output.finish())
+        }
+        _ => Err(String::from(USAGE)),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // days running side by side mustn't hold each other up or leave
+    // anything behind that changes another day's answer
+    #[test]
+    fn all_matches_answers() {
+        let mut output = Output::new();
+        output.format = Format::Json;
+        output.jobs = 4;
+        run_all(&mut output);
+
+        for outcome in &output.outcomes {
+            let (day, part) = (outcome.day, outcome.part);
+            // a day with an answer but no input file fails below
+            let expected = match answers::expected_answer(day, part) {
+                Some(expected) => expected,
+                None => continue,
+            };
+            match &outcome.result {
+                Ok(answer) => assert_eq!(answer.to_string(), expected, "day {} part {}", day, part),
+                Err(e) => panic!("day {} part {}: {}", day, part, e),
+            }
+        }
+    }
+}
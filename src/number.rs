@@ -0,0 +1,329 @@
+// Number theory: gcd and lcm, and modular arithmetic for the shuffles of day 22
+// gcd and lcm work on any primitive integer. The modular functions take i128
+// with a positive modulus, return values in 0..m, and don't overflow for any
+// modulus that fits: products too big for i128 fall back to adding doubles.
+use std::ops::{Div, Mul, Rem};
+
+pub trait Integer:
+    Copy + PartialEq + PartialOrd + Rem<Output = Self> + Div<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    fn magnitude(self) -> Self;
+}
+
+macro_rules! integer {
+    ($($t:ty),*; $($u:ty),*) => {
+        $(impl Integer for $t {
+            const ZERO: $t = 0;
+            const ONE: $t = 1;
+            fn magnitude(self) -> $t {
+                self.abs()
+            }
+        })*
+        $(impl Integer for $u {
+            const ZERO: $u = 0;
+            const ONE: $u = 1;
+            fn magnitude(self) -> $u {
+                self
+            }
+        })*
+    };
+}
+
+integer!(i32, i64, i128, isize; u32, u64, u128, usize);
+
+// Euclid's algorithm, never negative, gcd(0, 0) is 0
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a, b);
+    while b != T::ZERO {
+        let t = b;
+        b = a % b;
+        a = t;
+    }
+    a.magnitude()
+}
+
+// 0 if either is 0
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+    (a / gcd(a, b) * b).magnitude()
+}
+
+pub fn gcd_all<T: Integer>(values: &[T]) -> T {
+    values.iter().fold(T::ZERO, |acc, &n| gcd(acc, n))
+}
+
+// 1 for no values
+pub fn lcm_all<T: Integer>(values: &[T]) -> T {
+    values.iter().fold(T::ONE, |acc, &n| lcm(acc, n))
+}
+
+// (g, x, y) with a * x + b * y = g = gcd(a, b)
+pub fn egcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        let t = r;
+        r = old_r - q * r;
+        old_r = t;
+        let t = x;
+        x = old_x - q * x;
+        old_x = t;
+        let t = y;
+        y = old_y - q * y;
+        old_y = t;
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+// the x with a * x = 1 (mod m), None unless a and m are coprime
+pub fn mod_inv(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = egcd(a.rem_euclid(m), m);
+    if g == 1 {
+        Some(x.rem_euclid(m))
+    } else {
+        None
+    }
+}
+
+pub fn add_mod(a: i128, b: i128, m: i128) -> i128 {
+    let (a, b) = (a.rem_euclid(m), b.rem_euclid(m));
+    // a + b could overflow when m is close to i128::MAX
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+pub fn mul_mod(a: i128, b: i128, m: i128) -> i128 {
+    let (mut a, mut b) = (a.rem_euclid(m), b.rem_euclid(m));
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+    let mut result = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+    result
+}
+
+pub fn pow_mod(base: i128, exponent: u128, m: i128) -> i128 {
+    let mut base = base.rem_euclid(m);
+    let mut exponent = exponent;
+    let mut result = 1 % m;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exponent >>= 1;
+    }
+    result
+}
+
+// Miller-Rabin with the primes up to 37 as witnesses, which is exact below
+// 3.3 * 10^24; past that a composite could pass, but only a rare one
+pub fn is_prime(n: i128) -> bool {
+    const WITNESSES: [i128; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    if let Some(&p) = WITNESSES.iter().find(|&&p| n % p == 0) {
+        return n == p;
+    }
+    // n - 1 = d * 2^s with d odd
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    WITNESSES.iter().all(|&a| {
+        let mut x = pow_mod(a, d as u128, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+// Chinese remainder theorem over (residue, modulus) pairs, the moduli don't
+// have to be coprime. Returns (x, lcm of the moduli) with x in 0..lcm, None
+// if the congruences contradict each other or the lcm doesn't fit.
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    let mut x = 0;
+    let mut m = 1;
+    for &(residue, modulus) in congruences {
+        let residue = residue.rem_euclid(modulus);
+        let g = gcd(m, modulus);
+        let difference = residue - x % modulus;
+        if difference % g != 0 {
+            return None;
+        }
+        let step = modulus / g;
+        let combined = (m / g).checked_mul(modulus)?;
+        // x + m * k = residue (mod modulus)
+        let k = mul_mod(difference / g, mod_inv(m / g, step)?, step);
+        x = (x + m * k).rem_euclid(combined);
+        m = combined;
+    }
+    Some((x, m))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::random::Rng;
+
+    fn naive_gcd(a: i64, b: i64) -> i64 {
+        let (a, b) = (a.abs(), b.abs());
+        (1..=a.max(b))
+            .rev()
+            .find(|d| a % d == 0 && b % d == 0)
+            .unwrap_or(0)
+    }
+
+    fn naive_lcm(values: &[u64]) -> u64 {
+        let step = *values.iter().max().unwrap();
+        (1..)
+            .map(|n| n * step)
+            .find(|n| values.iter().all(|v| n % v == 0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(54, -24), 6);
+        assert_eq!(gcd(0u64, 0), 0);
+        assert_eq!(gcd_all(&[12, 18, -30]), 6);
+        assert_eq!(lcm_all::<u64>(&[]), 1);
+        assert_eq!(lcm(0, 5), 0);
+
+        let mut rng = Rng::new(45);
+        for _ in 0..500 {
+            let (a, b) = (rng.range(-200..=200), rng.range(-200..=200));
+            assert_eq!(gcd(a, b), naive_gcd(a, b), "gcd({}, {})", a, b);
+            assert_eq!(gcd(a as i32, b as i32) as i64, naive_gcd(a, b));
+        }
+        for _ in 0..200 {
+            let values: Vec<u64> = (0..rng.range(1..=4))
+                .map(|_| rng.range(1..=30) as u64)
+                .collect();
+            assert_eq!(lcm_all(&values), naive_lcm(&values), "lcm of {:?}", values);
+        }
+    }
+
+    #[test]
+    fn test_egcd() {
+        let mut rng = Rng::new(46);
+        for _ in 0..500 {
+            let a = rng.range(-1_000_000..=1_000_000) as i128;
+            let b = rng.range(-1_000_000..=1_000_000) as i128;
+            let (g, x, y) = egcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g, "egcd({}, {})", a, b);
+        }
+    }
+
+    #[test]
+    fn test_mod_inv() {
+        let mut rng = Rng::new(47);
+        for _ in 0..500 {
+            let m = rng.range(1..=100) as i128;
+            let a = rng.range(-300..=300) as i128;
+            let naive = (0..m).find(|x| (a * x).rem_euclid(m) == 1 % m);
+            assert_eq!(mod_inv(a, m), naive, "inverse of {} mod {}", a, m);
+        }
+        assert_eq!(mod_inv(6, 9), None);
+    }
+
+    #[test]
+    fn test_mul_mod() {
+        let mut rng = Rng::new(48);
+        for _ in 0..500 {
+            // products up to 2^128, past i128 but within u128
+            let m = rng.next_u64().max(1) as i128;
+            let (a, b) = (rng.next_u64() as i128, -(rng.next_u64() as i128));
+            let naive = (a as u128 * (b.rem_euclid(m)) as u128 % m as u128) as i128;
+            assert_eq!(mul_mod(a, b, m), naive, "{} * {} mod {}", a, b, m);
+            assert_eq!(add_mod(a, b, m), (a + b).rem_euclid(m));
+        }
+
+        // near the top of i128 nothing fits, check it multiplies anyway
+        let m = i128::MAX - 1;
+        let a = i128::MAX / 3;
+        assert_eq!(mul_mod(a, 2, m), 2 * a);
+        assert_eq!(mul_mod(a, 3, m), 3 * a - m);
+        assert_eq!(mul_mod(-1, -1, m), 1);
+        assert_eq!(
+            mul_mod(mul_mod(a, a, m), 5, m),
+            mul_mod(a, mul_mod(a, 5, m), m)
+        );
+    }
+
+    #[test]
+    fn test_pow_mod() {
+        let mut rng = Rng::new(49);
+        for _ in 0..300 {
+            let m = rng.range(1..=1_000_000_007) as i128;
+            let base = rng.range(-1_000_000..=1_000_000) as i128;
+            let exponent = rng.range(0..=40) as u128;
+            let naive = (0..exponent).fold(1 % m, |acc, _| (acc * base).rem_euclid(m));
+            assert_eq!(pow_mod(base, exponent, m), naive);
+        }
+        // Fermat's little theorem, i128::MAX = 2^127 - 1 is prime
+        let p = i128::MAX;
+        assert_eq!(pow_mod(3, (p - 1) as u128, p), 1);
+    }
+
+    #[test]
+    fn test_is_prime() {
+        let naive = |n: i128| n >= 2 && (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0);
+        for n in -5..=3000 {
+            assert_eq!(is_prime(n), naive(n), "{}", n);
+        }
+        // Carmichael numbers and a strong pseudoprime to bases 2 to 11
+        for &n in &[561, 41041, 825265, 2152302898747] {
+            assert!(!is_prime(n), "{}", n);
+        }
+        assert!(is_prime(10007));
+        assert!(is_prime(119315717514047));
+        assert!(is_prime(i128::MAX));
+        assert!(!is_prime(119315717514047 * 10007));
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+
+        let mut rng = Rng::new(50);
+        for _ in 0..300 {
+            let moduli: Vec<i128> = (0..rng.range(1..=4))
+                .map(|_| rng.range(1..=40) as i128)
+                .collect();
+            let period = lcm_all(&moduli);
+            let x = rng.range(0..=period as i64 - 1) as i128;
+            let congruences: Vec<(i128, i128)> = moduli.iter().map(|&m| (x % m, m)).collect();
+            assert_eq!(crt(&congruences), Some((x, period)), "{:?}", congruences);
+        }
+    }
+}
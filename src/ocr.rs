@@ -0,0 +1,187 @@
+// Reads the capital letters days 8 and 11 draw their answers in
+// The letters are 4 pixels wide and 6 tall with a blank column between them,
+// so they sit in 5 column cells. Reading starts at the top lit row and the
+// cells are lined up with the first lit column, or a few columns before it
+// when the message starts with I, whose first column is blank. A border of
+// unlit pixels around the message doesn't matter.
+use crate::parse::ParseError;
+use crate::Bitmap;
+
+const WIDTH: usize = 4;
+const HEIGHT: usize = 6;
+
+// rows top to bottom, '#' is lit
+const LETTERS: [(char, [&str; HEIGHT]); 17] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+// the glyph at (left, top), pixels outside the bitmap are unlit
+fn glyph(bitmap: &Bitmap, left: usize, top: usize) -> [String; HEIGHT] {
+    let mut rows: [String; HEIGHT] = Default::default();
+    for (dy, row) in rows.iter_mut().enumerate() {
+        *row = (left..left + WIDTH)
+            .map(|x| {
+                let y = top + dy;
+                if x < bitmap.width && y < bitmap.height && bitmap.get(x, y) {
+                    '#'
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+    }
+    rows
+}
+
+fn letter(rows: &[String; HEIGHT]) -> Option<char> {
+    LETTERS
+        .iter()
+        .find(|(_, pattern)| pattern.iter().zip(rows).all(|(p, r)| p == r))
+        .map(|&(c, _)| c)
+}
+
+// unknown glyphs are reported at their top left pixel, drawn row by row
+pub fn read(bitmap: &Bitmap) -> Result<String, ParseError> {
+    let lit = |x: usize, y: usize| bitmap.get(x, y);
+    let columns: Vec<usize> = (0..bitmap.width)
+        .filter(|&x| (0..bitmap.height).any(|y| lit(x, y)))
+        .collect();
+    let top = (0..bitmap.height).find(|&y| (0..bitmap.width).any(|x| lit(x, y)));
+    let (first, last, top) = match (columns.first(), columns.last(), top) {
+        (Some(&first), Some(&last), Some(top)) => (first, last, top),
+        _ => return Ok(String::new()),
+    };
+
+    // the error from the cells that read furthest if none read through
+    let mut error: Option<ParseError> = None;
+    for start in (first.saturating_sub(WIDTH - 1)..=first).rev() {
+        match read_cells(bitmap, start, last, top) {
+            Ok(result) => return Ok(result),
+            Err(e) => {
+                if error.as_ref().is_none_or(|best| e.column > best.column) {
+                    error = Some(e);
+                }
+            }
+        }
+    }
+    Err(error.unwrap())
+}
+
+// every cell from `start` up to `last`, the last lit column
+fn read_cells(
+    bitmap: &Bitmap,
+    start: usize,
+    last: usize,
+    top: usize,
+) -> Result<String, ParseError> {
+    let mut result = String::new();
+    for left in (start..=last).step_by(WIDTH + 1) {
+        let rows = glyph(bitmap, left, top);
+        match letter(&rows) {
+            Some(c) => result.push(c),
+            None => {
+                return Err(ParseError::new(
+                    top + 1,
+                    left + 1,
+                    &format!("\n{}\n", rows.join("\n")),
+                    "a capital letter",
+                ))
+            }
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bitmap(rows: &[&str]) -> Bitmap {
+        let mut result = Bitmap::new(rows[0].len(), rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                result.set(x, y, c == '#');
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_read() {
+        let message = bitmap(&[
+            "........................",
+            ".#..#..###.####.#.......",
+            ".#..#...#..#....#.......",
+            ".####...#..###..#.......",
+            ".#..#...#..#....#.......",
+            ".#..#...#..#....#.......",
+            ".#..#..###.####.####....",
+        ]);
+        assert_eq!(read(&message), Ok("HIEL".to_string()));
+        assert_eq!(read(&Bitmap::new(5, 6)), Ok(String::new()));
+
+        // I's first column is blank, at the edge or after a margin
+        let message = bitmap(&[
+            ".###.#..#.",
+            "..#..#..#.",
+            "..#..####.",
+            "..#..#..#.",
+            "..#..#..#.",
+            ".###.#..#.",
+        ]);
+        assert_eq!(read(&message), Ok("IH".to_string()));
+        let message = bitmap(&[
+            "...###.#..#",
+            "....#..#..#",
+            "....#..####",
+            "....#..#..#",
+            "....#..#..#",
+            "...###.#..#",
+        ]);
+        assert_eq!(read(&message), Ok("IH".to_string()));
+    }
+
+    #[test]
+    fn test_alphabet() {
+        let letters: String = LETTERS.iter().map(|&(c, _)| c).collect();
+        let rows: Vec<String> = (0..HEIGHT)
+            .map(|y| {
+                let row: Vec<&str> = LETTERS.iter().map(|(_, pattern)| pattern[y]).collect();
+                row.join(".")
+            })
+            .collect();
+        let rows: Vec<&str> = rows.iter().map(|row| row.as_str()).collect();
+        assert_eq!(read(&bitmap(&rows)), Ok(letters));
+    }
+
+    #[test]
+    fn test_unknown() {
+        let message = bitmap(&[
+            "#..#.#...#",
+            "#..#.##.##",
+            "####.#.#.#",
+            "#..#.#...#",
+            "#..#.#...#",
+            "#..#.#...#",
+        ]);
+        let error = read(&message).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 6));
+        assert_eq!(error.text, "\n#...\n##.#\n#.#.\n#...\n#...\n#...\n");
+    }
+}
@@ -0,0 +1,192 @@
+// Puzzle constants a day lets the command line change
+// A day declares its constants once with `params!`, which gives a struct
+// with the puzzle's values as defaults and a way to set fields by name, so
+// `--param phases=4` can run an example without recompiling.
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::parse::ParseError;
+
+// (name, value) pairs as given on the command line
+pub type Overrides = [(String, String)];
+
+// what a day's `solve_*_with` gives: Err for parameters it can't use,
+// otherwise what it makes of the input
+pub type Checked<T> = Result<Result<T, ParseError>, String>;
+
+pub trait Params: Default {
+    // every field's name and value, in the order they're declared
+    fn fields(&self) -> Vec<(&'static str, String)>;
+    fn set(&mut self, name: &str, value: &str) -> Result<(), String>;
+    // Err for the first value the day can't work with
+    fn validate(&self) -> Result<(), String>;
+}
+
+pub fn defaults<P: Params>() -> Vec<(&'static str, String)> {
+    P::default().fields()
+}
+
+// the defaults with the overrides applied, in order
+pub fn with<P: Params>(overrides: &Overrides) -> Result<P, String> {
+    let mut params = P::default();
+    for (name, value) in overrides {
+        params.set(name, value)?;
+    }
+    params.validate()?;
+    Ok(params)
+}
+
+pub fn parse_value<T: FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{}' for {}", value, name))
+}
+
+// for `check`, NaN is never at least anything
+pub fn at_least<T: PartialOrd + Display>(name: &str, value: T, min: T) -> Result<(), String> {
+    if value >= min {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid value '{}' for {}, expected at least {}",
+            value, name, min
+        ))
+    }
+}
+
+// `NAME=VALUE` from the command line
+pub fn parse_override(arg: &str) -> Result<(String, String), String> {
+    match arg.find('=') {
+        Some(idx) if idx > 0 => Ok((arg[..idx].to_string(), arg[idx + 1..].to_string())),
+        _ => Err(format!("invalid parameter '{}', expected NAME=VALUE", arg)),
+    }
+}
+
+// A parameter struct: every field has a type that parses from and prints to
+// a string, and a default after `=`. An optional `check(params) { .. }`
+// after it returns Err for values the day can't use.
+#[allow(unused_macros)]
+macro_rules! params {
+    (
+        pub struct $name:ident { $($field:ident: $t:ty = $default:expr,)* }
+        $(check($params:ident) $check:block)?
+    ) => {
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct $name {
+            $(pub $field: $t,)*
+        }
+
+        impl Default for $name {
+            fn default() -> $name {
+                $name {
+                    $($field: $default,)*
+                }
+            }
+        }
+
+        impl $name {
+            pub fn validate(&self) -> Result<(), String> {
+                $({
+                    fn check($params: &$name) -> Result<(), String> $check
+                    check(self)?;
+                })?
+                Ok(())
+            }
+        }
+
+        impl $crate::params::Params for $name {
+            fn validate(&self) -> Result<(), String> {
+                $name::validate(self)
+            }
+
+            fn fields(&self) -> Vec<(&'static str, String)> {
+                vec![$((stringify!($field), self.$field.to_string()),)*]
+            }
+
+            fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+                match name {
+                    $(stringify!($field) => self.$field = $crate::params::parse_value(name, value)?,)*
+                    _ => {
+                        let names: &[&str] = &[$(stringify!($field)),*];
+                        return Err(format!(
+                            "unknown parameter {}, expected {}",
+                            name,
+                            names.join(", ")
+                        ));
+                    }
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+// unused when no day with parameters is built
+#[allow(unused_imports)]
+pub(crate) use params;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    params! {
+        pub struct Example {
+            width: usize = 25,
+            slope: f64 = 0.65,
+        }
+        check(params) {
+            at_least("width", params.width, 1)
+        }
+    }
+
+    fn overrides(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|&(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_params() {
+        assert_eq!(
+            defaults::<Example>(),
+            vec![
+                ("width", String::from("25")),
+                ("slope", String::from("0.65"))
+            ]
+        );
+
+        let changed: Example = with(&overrides(&[("width", "3"), ("width", "4")])).unwrap();
+        assert_eq!(changed.width, 4);
+        assert_eq!(changed.slope, 0.65);
+
+        assert_eq!(
+            with::<Example>(&overrides(&[("height", "6")])),
+            Err(String::from(
+                "unknown parameter height, expected width, slope"
+            ))
+        );
+        assert_eq!(
+            with::<Example>(&overrides(&[("width", "-1")])),
+            Err(String::from("invalid value '-1' for width"))
+        );
+        assert_eq!(
+            with::<Example>(&overrides(&[("width", "0")])),
+            Err(String::from(
+                "invalid value '0' for width, expected at least 1"
+            ))
+        );
+        assert_eq!(Example::default().validate(), Ok(()));
+        assert!(at_least("slope", f64::NAN, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_parse_override() {
+        assert_eq!(
+            parse_override("phases=4=2"),
+            Ok((String::from("phases"), String::from("4=2")))
+        );
+        assert!(parse_override("phases").is_err());
+        assert!(parse_override("=4").is_err());
+    }
+}
@@ -0,0 +1,89 @@
+// Errors for puzzle input that doesn't have the expected shape
+// Parsers report where the input went wrong and what they wanted there,
+// rather than panicking on it or quietly skipping the line.
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseError {
+    // both start at 1
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: &str, expected: &str) -> ParseError {
+        ParseError {
+            line,
+            column,
+            text: text.to_string(),
+            expected: expected.to_string(),
+        }
+    }
+
+    // `part` has to be a slice of `line`, as split() and captures hand out
+    pub fn within(line_idx: usize, line: &str, part: &str, expected: &str) -> ParseError {
+        ParseError::new(line_idx + 1, column(line, part), part, expected)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, got '{}'",
+            self.line, self.column, self.expected, self.text
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+// column of a slice of line, counted in characters
+pub fn column(line: &str, part: &str) -> usize {
+    let offset = (part.as_ptr() as usize).saturating_sub(line.as_ptr() as usize);
+    line.get(..offset)
+        .map(|before| before.chars().count() + 1)
+        .unwrap_or(1)
+}
+
+// comma separated integers, e.g. an Intcode program; blank lines are ignored
+pub fn integers<T: FromStr>(input: &str) -> Result<Vec<T>, ParseError> {
+    let mut result = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        for field in line.split(',') {
+            let field = field.trim();
+            match field.parse() {
+                Ok(n) => result.push(n),
+                Err(_) => return Err(ParseError::within(idx, line, field, "an integer")),
+            }
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(integers::<i64>("1,-2, 3\n\n"), Ok(vec![1, -2, 3]));
+        let error = integers::<i64>("1,2\n3,x4,5\n").unwrap_err();
+        assert_eq!(error, ParseError::new(2, 3, "x4", "an integer"));
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: expected an integer, got 'x4'"
+        );
+        assert_eq!(
+            integers::<i32>("1,,2").unwrap_err(),
+            ParseError::new(1, 3, "", "an integer")
+        );
+    }
+}
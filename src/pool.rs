@@ -0,0 +1,88 @@
+// Runs jobs on a few threads and hands the results back in order
+// Each worker takes the next job off a shared counter, so a slow day only
+// holds up its own worker. A result is passed on once every job before it is
+// done, so the output reads the same whatever finished first. A job that
+// panics gives None instead of taking the others down with it, but nothing
+// here can stop threads a job starts, so a job has to join its own before
+// returning or they'll slow every job after it.
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+pub fn run<T, R, F, G>(jobs: usize, items: &[T], work: F, mut done: G)
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+    G: FnMut(&T, Option<R>),
+{
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, items.len().max(1)) {
+            let sender = sender.clone();
+            let (next, work) = (&next, &work);
+            scope.spawn(move || loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                if idx >= items.len() {
+                    break;
+                }
+                let result = panic::catch_unwind(AssertUnwindSafe(|| work(&items[idx]))).ok();
+                if sender.send((idx, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        // the workers hold the only senders left, so this ends with them
+        drop(sender);
+
+        let mut finished = BTreeMap::new();
+        let mut wanted = 0;
+        for (idx, result) in receiver {
+            finished.insert(idx, result);
+            while let Some(result) = finished.remove(&wanted) {
+                done(&items[wanted], result);
+                wanted += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_run() {
+        let items: Vec<u64> = (0..12).collect();
+        let mut results = Vec::new();
+        // later jobs finish first
+        run(
+            4,
+            &items,
+            |&n| {
+                thread::sleep(Duration::from_millis(24 - 2 * n));
+                if n == 5 {
+                    panic!("job 5");
+                }
+                n * n
+            },
+            |&n, result| results.push((n, result)),
+        );
+
+        let expected: Vec<(u64, Option<u64>)> = items
+            .iter()
+            .map(|&n| (n, if n == 5 { None } else { Some(n * n) }))
+            .collect();
+        assert_eq!(results, expected);
+
+        run(1, &[] as &[u64], |&n| n, |_, _| panic!("no jobs"));
+    }
+}
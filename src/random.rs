@@ -0,0 +1,72 @@
+// Seeded pseudo-random numbers for generated inputs and randomized tests
+// SplitMix64: a few lines, and a seed gives the same sequence on every
+// platform, so a failing case can be replayed from its seed.
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // uniform enough for tests, the modulo bias is at most range / 2^64
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (low, high) = (*range.start(), *range.end());
+        assert!(low <= high, "empty range");
+        let span = (high as i128 - low as i128 + 1) as u128;
+        (low as i128 + (self.next_u64() as u128 % span) as i128) as i64
+    }
+
+    // an index into something of length n
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "nothing to pick from");
+        (self.next_u64() % n as u64) as usize
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_seeded() {
+        let sequence = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..5).map(|_| rng.next_u64()).collect::<Vec<u64>>()
+        };
+        let (first, again) = (sequence(7), sequence(7));
+        assert_eq!(first, again);
+        assert_ne!(Rng::new(8).next_u64(), first[0]);
+    }
+
+    #[test]
+    fn test_range() {
+        let mut rng = Rng::new(1);
+        let mut seen = [false; 7];
+        for _ in 0..1000 {
+            let n = rng.range(-3..=3);
+            assert!((-3..=3).contains(&n));
+            seen[(n + 3) as usize] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+        assert_eq!(rng.range(i64::MIN..=i64::MIN), i64::MIN);
+        rng.range(i64::MIN..=i64::MAX);
+        assert!(rng.below(3) < 3);
+    }
+}
@@ -0,0 +1,136 @@
+// Animated GIFs of the simulation days
+// A day offers the recorder a picture at every step of its simulation; the
+// recorder keeps every Nth one up to a frame limit and encodes them as GIF.
+// Pictures are only drawn for the steps that get kept.
+use crate::render::Picture;
+#[cfg(feature = "render")]
+use crate::render::Rgb;
+
+pub struct Recorder {
+    // keep one step in every
+    every: usize,
+    max_frames: usize,
+    // only read when writing the GIF
+    #[cfg_attr(not(feature = "render"), allow(dead_code))]
+    fps: u32,
+    offered: usize,
+    // the next step to keep
+    next: usize,
+    frames: Vec<Picture>,
+}
+
+impl Recorder {
+    pub fn new(every: usize, max_frames: usize, fps: u32) -> Recorder {
+        Recorder {
+            every: every.max(1),
+            max_frames,
+            fps: fps.max(1),
+            offered: 0,
+            next: 0,
+            frames: Vec::new(),
+        }
+    }
+
+    // no more frames will be kept, days can stop simulating
+    pub fn is_full(&self) -> bool {
+        self.frames.len() >= self.max_frames
+    }
+
+    pub fn offer<F>(&mut self, picture: F)
+    where
+        F: FnOnce() -> Picture,
+    {
+        if self.offered == self.next && !self.is_full() {
+            self.frames.push(picture());
+            self.next += self.every;
+        }
+        self.offered += 1;
+    }
+
+    pub fn frames(&self) -> &[Picture] {
+        &self.frames
+    }
+
+    // frames smaller than the largest one are drawn from the top left on black
+    #[cfg(feature = "render")]
+    pub fn save_gif(&self, path: &str, scale: u32) -> Result<(), String> {
+        if self.frames.is_empty() {
+            return Err(format!("couldn't write {}: no frames recorded", path));
+        }
+        let width = self.frames.iter().map(Picture::width).max().unwrap() as u32;
+        let height = self.frames.iter().map(Picture::height).max().unwrap() as u32;
+
+        // gif delays count hundredths of a second
+        let delay = num_rational::Ratio::from_integer((100 / self.fps).max(1) as u16);
+        let frames = self.frames.iter().map(|picture| {
+            let buffer = image::ImageBuffer::from_fn(width * scale, height * scale, |x, y| {
+                let (x, y) = ((x / scale) as usize, (y / scale) as usize);
+                let Rgb(r, g, b) = if x < picture.width() && y < picture.height() {
+                    picture.get(x, y).colour
+                } else {
+                    Rgb::BLACK
+                };
+                image::Rgba([r, g, b, 255])
+            });
+            image::Frame::from_parts(buffer, 0, 0, delay)
+        });
+
+        let file =
+            std::fs::File::create(path).map_err(|e| format!("couldn't write {}: {}", path, e))?;
+        image::gif::Encoder::new(file)
+            .encode_frames(frames)
+            .map_err(|e| format!("couldn't write {}: {}", path, e))
+    }
+
+    #[cfg(not(feature = "render"))]
+    pub fn save_gif(&self, path: &str, _scale: u32) -> Result<(), String> {
+        Err(format!(
+            "couldn't write {}: built without the render feature",
+            path
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Bitmap;
+
+    fn picture(width: usize) -> Picture {
+        Picture::from(&Bitmap::new(width, 1))
+    }
+
+    #[test]
+    fn test_offer() {
+        let mut recorder = Recorder::new(2, 3, 10);
+        let mut drawn = 0;
+        for width in 1..=10 {
+            recorder.offer(|| {
+                drawn += 1;
+                picture(width)
+            });
+        }
+        let widths: Vec<usize> = recorder.frames().iter().map(Picture::width).collect();
+        assert_eq!(widths, vec![1, 3, 5]);
+        assert_eq!(drawn, 3);
+        assert!(recorder.is_full());
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn test_save_gif() {
+        let mut recorder = Recorder::new(1, 10, 25);
+        recorder.offer(|| picture(2));
+        recorder.offer(|| picture(3));
+
+        let path = std::env::temp_dir().join("aoc2019-test-record.gif");
+        let path = path.to_string_lossy();
+        recorder.save_gif(&path, 2).unwrap();
+        let bytes = std::fs::read(&*path).unwrap();
+        std::fs::remove_file(&*path).unwrap();
+        assert!(bytes.starts_with(b"GIF89a"));
+
+        let empty = Recorder::new(1, 10, 25);
+        assert!(empty.save_gif(&path, 2).is_err());
+    }
+}
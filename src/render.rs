@@ -0,0 +1,254 @@
+// Pictures of puzzle state as plain text, ANSI colours or PNG
+// A day maps each of its tiles to a `Cell`, a glyph for text and a colour
+// for the terminal and images, and the picture draws itself from those.
+use std::collections::BTreeMap;
+
+use crate::grid::{Bounds, Coordinate, Grid};
+use crate::Bitmap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const GREY: Rgb = Rgb(128, 128, 128);
+    pub const RED: Rgb = Rgb(220, 50, 47);
+    pub const GREEN: Rgb = Rgb(80, 200, 80);
+    pub const BLUE: Rgb = Rgb(70, 130, 230);
+    pub const YELLOW: Rgb = Rgb(240, 200, 40);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    pub colour: Rgb,
+}
+
+impl Cell {
+    pub const fn new(glyph: char, colour: Rgb) -> Cell {
+        Cell { glyph, colour }
+    }
+}
+
+// how to draw a picture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Ascii,
+    Ansi,
+    Png,
+}
+
+impl Target {
+    pub fn parse(name: &str) -> Result<Target, String> {
+        match name {
+            "ascii" => Ok(Target::Ascii),
+            "ansi" => Ok(Target::Ansi),
+            "png" => Ok(Target::Png),
+            _ => Err(format!(
+                "unknown render target '{}', expected ascii, ansi or png",
+                name
+            )),
+        }
+    }
+}
+
+// cells row by row, (0, 0) is the top left
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Picture {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
+
+impl Picture {
+    pub fn from_grid<T, F>(grid: &Grid<T>, cell: F) -> Picture
+    where
+        F: Fn(&T) -> Cell,
+    {
+        Picture {
+            width: grid.width(),
+            height: grid.height(),
+            cells: grid.iter().map(|(_, tile)| cell(tile)).collect(),
+        }
+    }
+
+    // covers the bounding box of the map, tiles missing from it are cell(None)
+    pub fn from_sparse<T, F>(tiles: &BTreeMap<Coordinate, T>, cell: F) -> Picture
+    where
+        F: Fn(Option<&T>) -> Cell,
+    {
+        match Bounds::of(tiles.keys().copied()) {
+            Some(bounds) => Picture::from_sparse_in(tiles, bounds, cell),
+            None => Picture {
+                width: 0,
+                height: 0,
+                cells: Vec::new(),
+            },
+        }
+    }
+
+    // covers the given bounds, e.g. a map's final size while it's still growing
+    pub fn from_sparse_in<T, F>(tiles: &BTreeMap<Coordinate, T>, bounds: Bounds, cell: F) -> Picture
+    where
+        F: Fn(Option<&T>) -> Cell,
+    {
+        let mut cells = Vec::with_capacity(bounds.width() * bounds.height());
+        for y in bounds.min.y..=bounds.max.y {
+            for x in bounds.min.x..=bounds.max.x {
+                cells.push(cell(tiles.get(&Coordinate::new(x, y))));
+            }
+        }
+        Picture {
+            width: bounds.width(),
+            height: bounds.height(),
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Cell {
+        self.cells[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, cell: Cell) {
+        self.cells[y * self.width + x] = cell;
+    }
+
+    fn rows(&self) -> std::slice::Chunks<'_, Cell> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn ascii(&self) -> String {
+        let mut result = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            result.extend(row.iter().map(|cell| cell.glyph));
+            result.push('\n');
+        }
+        result
+    }
+
+    // 24-bit colour glyphs, escape codes only where the colour changes
+    pub fn ansi(&self) -> String {
+        let mut result = String::new();
+        for row in self.rows() {
+            let mut current = None;
+            for cell in row {
+                if current != Some(cell.colour) {
+                    let Rgb(r, g, b) = cell.colour;
+                    result.push_str(&format!("\x1b[38;2;{};{};{}m", r, g, b));
+                    current = Some(cell.colour);
+                }
+                result.push(cell.glyph);
+            }
+            result.push_str("\x1b[0m\n");
+        }
+        result
+    }
+
+    // every cell becomes a scale by scale square of its colour
+    #[cfg(feature = "render")]
+    pub fn save_png(&self, path: &str, scale: u32) -> Result<(), String> {
+        let width = self.width as u32 * scale;
+        let height = self.height as u32 * scale;
+        let image = image::ImageBuffer::from_fn(width, height, |x, y| {
+            let Rgb(r, g, b) = self.get((x / scale) as usize, (y / scale) as usize).colour;
+            image::Rgb([r, g, b])
+        });
+        image
+            .save(path)
+            .map_err(|e| format!("couldn't write {}: {}", path, e))
+    }
+
+    #[cfg(not(feature = "render"))]
+    pub fn save_png(&self, path: &str, _scale: u32) -> Result<(), String> {
+        Err(format!(
+            "couldn't write {}: built without the render feature",
+            path
+        ))
+    }
+}
+
+// lit pixels are white
+impl From<&Bitmap> for Picture {
+    fn from(bitmap: &Bitmap) -> Picture {
+        Picture {
+            width: bitmap.width,
+            height: bitmap.height,
+            cells: bitmap
+                .pixels
+                .iter()
+                .map(|&lit| {
+                    if lit {
+                        Cell::new('#', Rgb::WHITE)
+                    } else {
+                        Cell::new('.', Rgb::BLACK)
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_grid() {
+        let grid = Grid::parse("#.\n.#", "", |c| Some(c == '#')).unwrap();
+        let picture = Picture::from_grid(&grid, |&wall| {
+            if wall {
+                Cell::new('#', Rgb::WHITE)
+            } else {
+                Cell::new(' ', Rgb::BLACK)
+            }
+        });
+        assert_eq!(picture.ascii(), "# \n #\n");
+        assert_eq!(
+            picture.ansi(),
+            "\x1b[38;2;255;255;255m#\x1b[38;2;0;0;0m \x1b[0m\n\
+             \x1b[38;2;0;0;0m \x1b[38;2;255;255;255m#\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_sparse() {
+        let mut tiles = BTreeMap::new();
+        tiles.insert(Coordinate::new(-1, 3), 'a');
+        tiles.insert(Coordinate::new(1, 4), 'b');
+        let picture = Picture::from_sparse(&tiles, |tile| {
+            Cell::new(tile.copied().unwrap_or('.'), Rgb::GREY)
+        });
+        assert_eq!((picture.width(), picture.height()), (3, 2));
+        assert_eq!(picture.ascii(), "a..\n..b\n");
+
+        let empty: BTreeMap<Coordinate, char> = BTreeMap::new();
+        assert_eq!(
+            Picture::from_sparse(&empty, |_| Cell::new('.', Rgb::GREY)).ascii(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_bitmap() {
+        let mut bitmap = Bitmap::new(2, 1);
+        bitmap.set(1, 0, true);
+        let picture = Picture::from(&bitmap);
+        assert_eq!(picture.ascii(), ".#\n");
+        assert_eq!(picture.get(1, 0).colour, Rgb::WHITE);
+    }
+
+    #[test]
+    fn test_target() {
+        assert_eq!(Target::parse("ansi"), Ok(Target::Ansi));
+        assert!(Target::parse("svg").is_err());
+    }
+}
@@ -0,0 +1,171 @@
+// How the runner prints its results
+// Text prints one line per answer as soon as it's known. JSON prints one
+// object per solution once everything ran, keys always in the same order
+// and answers always as strings so the output diffs cleanly between commits.
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use aoc2019::Answer;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Result<Format, String> {
+        match name {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format '{}', expected text or json", name)),
+        }
+    }
+}
+
+pub struct Outcome {
+    pub day: u32,
+    pub part: u32,
+    pub result: Result<Answer, String>,
+    pub duration: Duration,
+    // files written for the answer, e.g. the PNG of an image answer
+    pub artifacts: Vec<String>,
+}
+
+impl Outcome {
+    fn kind(&self) -> Option<&'static str> {
+        match &self.result {
+            Ok(Answer::Integer(_)) => Some("integer"),
+            Ok(Answer::Text(_)) | Ok(Answer::Lettering(..)) => Some("string"),
+            Ok(Answer::Image(_)) => Some("image"),
+            Err(_) => None,
+        }
+    }
+
+    // writes image answers, and the pictures letters were read from, to
+    // dir/dayDD-partP.png
+    pub fn save_artifacts(&mut self, dir: &str) -> Result<(), String> {
+        if let Some(image) = self.result.as_ref().ok().and_then(Answer::image) {
+            fs::create_dir_all(dir).map_err(|e| format!("couldn't create {}: {}", dir, e))?;
+            let name = format!("day{:02}-part{}.png", self.day, self.part);
+            let path = Path::new(dir).join(name).to_string_lossy().into_owned();
+            image.save_png(&path)?;
+            self.artifacts.push(path);
+        }
+        Ok(())
+    }
+
+    pub fn print_text(&self) {
+        match &self.result {
+            Ok(answer) => println!("day {:02} part {}: {}", self.day, self.part, answer),
+            Err(e) => eprintln!("day {:02} part {}: {}", self.day, self.part, e),
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut result = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+fn json_or_null(value: Option<String>) -> String {
+    value
+        .map(|s| json_string(&s))
+        .unwrap_or_else(|| String::from("null"))
+}
+
+pub fn json(outcomes: &[Outcome]) -> String {
+    let objects: Vec<String> = outcomes
+        .iter()
+        .map(|outcome| {
+            let artifacts: Vec<String> = outcome.artifacts.iter().map(|a| json_string(a)).collect();
+            format!(
+                "  {{\"day\": {}, \"part\": {}, \"answer\": {}, \"type\": {}, \"duration_ns\": {}, \"error\": {}, \"artifacts\": [{}]}}",
+                outcome.day,
+                outcome.part,
+                json_or_null(outcome.result.as_ref().ok().map(|a| a.to_string().trim().to_string())),
+                json_or_null(outcome.kind().map(String::from)),
+                outcome.duration.as_nanos(),
+                json_or_null(outcome.result.as_ref().err().cloned()),
+                artifacts.join(", ")
+            )
+        })
+        .collect();
+    format!("[\n{}\n]", objects.join(",\n"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_json() {
+        let outcomes = vec![
+            Outcome {
+                day: 9,
+                part: 1,
+                result: Ok(Answer::Integer(3507134798)),
+                duration: Duration::from_micros(31),
+                artifacts: vec![],
+            },
+            Outcome {
+                day: 6,
+                part: 2,
+                result: Err(String::from("couldn't read \"src/aoc06/input\"")),
+                duration: Duration::default(),
+                artifacts: vec![],
+            },
+        ];
+        assert_eq!(
+            json(&outcomes),
+            r#"[
+  {"day": 9, "part": 1, "answer": "3507134798", "type": "integer", "duration_ns": 31000, "error": null, "artifacts": []},
+  {"day": 6, "part": 2, "answer": null, "type": null, "duration_ns": 0, "error": "couldn't read \"src/aoc06/input\"", "artifacts": []}
+]"#
+        );
+    }
+
+    // day 8's message is read off a picture, which is saved next to the answer
+    #[cfg(feature = "render")]
+    #[test]
+    fn test_save_artifacts() {
+        let (solution, input) = match (aoc2019::solution(8), aoc2019::answers::puzzle_input(8)) {
+            (Some(solution), Some(input)) => (solution, input),
+            _ => return,
+        };
+        let mut outcome = Outcome {
+            day: 8,
+            part: 2,
+            result: Ok(solution.solve(2, &input).unwrap().unwrap()),
+            duration: Duration::default(),
+            artifacts: vec![],
+        };
+        let dir = std::env::temp_dir().join("aoc2019-test-artifacts");
+        outcome.save_artifacts(&dir.to_string_lossy()).unwrap();
+        assert_eq!(outcome.kind(), Some("string"));
+        assert_eq!(outcome.artifacts.len(), 1);
+
+        let path = &outcome.artifacts[0];
+        assert!(path.ends_with("day08-part2.png"), "{}", path);
+        let bytes = fs::read(path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(bytes.starts_with(b"\x89PNG\r\n\x1a\n"));
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("#.\n.#\n"), r##""#.\n.#\n""##);
+        assert_eq!(json_string("a\\b\t"), r#""a\\b\u0009""#);
+    }
+}
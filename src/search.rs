@@ -0,0 +1,263 @@
+// Shortest path searches over a neighbour function
+// Nodes can be anything hashable: a map tile, a portal, or a whole puzzle
+// state. `neighbours` hands out the nodes one step away, with the cost of
+// the step for the weighted searches. A search stops at the first node
+// `goal` accepts, or runs until every reachable node has been seen.
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+// whether to remember how each node was reached, so `path` can rebuild it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Paths {
+    Track,
+    Skip,
+}
+
+#[derive(Debug, Clone)]
+pub struct Search<N, C> {
+    // first node the goal accepted and its cost
+    pub goal: Option<(N, C)>,
+    // cheapest cost found to every node reached
+    pub costs: HashMap<N, C>,
+    // nodes taken off the frontier, for profiling
+    pub expanded: usize,
+    came_from: Option<HashMap<N, N>>,
+}
+
+impl<N, C> Search<N, C>
+where
+    N: Eq + Hash + Clone,
+{
+    fn new(paths: Paths) -> Search<N, C> {
+        Search {
+            goal: None,
+            costs: HashMap::new(),
+            expanded: 0,
+            came_from: match paths {
+                Paths::Track => Some(HashMap::new()),
+                Paths::Skip => None,
+            },
+        }
+    }
+
+    fn reached(&mut self, node: N, cost: C, from: &N) {
+        if let Some(came_from) = &mut self.came_from {
+            came_from.insert(node.clone(), from.clone());
+        }
+        self.costs.insert(node, cost);
+    }
+
+    // start to goal, both included
+    // None if there's no goal or paths weren't tracked
+    pub fn path(&self) -> Option<Vec<N>> {
+        let (goal, _) = self.goal.as_ref()?;
+        self.path_to(goal)
+    }
+
+    // start to any reached node, both included
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        let came_from = self.came_from.as_ref()?;
+        if !self.costs.contains_key(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+        let mut current = node;
+        while let Some(previous) = came_from.get(current) {
+            path.push(previous.clone());
+            current = previous;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+// breadth-first search, every step costs one
+pub fn bfs<N, I, F, G>(start: N, paths: Paths, mut neighbours: F, mut goal: G) -> Search<N, usize>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+    G: FnMut(&N) -> bool,
+{
+    let mut search = Search::new(paths);
+    search.costs.insert(start.clone(), 0);
+
+    let mut queue = VecDeque::new();
+    queue.push_back((start, 0));
+
+    while let Some((node, steps)) = queue.pop_front() {
+        search.expanded += 1;
+        if goal(&node) {
+            search.goal = Some((node, steps));
+            break;
+        }
+
+        for next in neighbours(&node) {
+            if !search.costs.contains_key(&next) {
+                search.reached(next.clone(), steps + 1, &node);
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+    search
+}
+
+// Dijkstra's shortest path algorithm, step costs can't be negative
+pub fn dijkstra<N, C, I, F, G>(start: N, paths: Paths, neighbours: F, goal: G) -> Search<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    F: FnMut(&N) -> I,
+    G: FnMut(&N) -> bool,
+{
+    astar(start, paths, neighbours, |_| C::default(), goal)
+}
+
+// A* search, the heuristic must never overestimate the cost left to the goal
+// and shouldn't drop by more than a step's cost along any step
+pub fn astar<N, C, I, F, H, G>(
+    start: N,
+    paths: Paths,
+    mut neighbours: F,
+    mut heuristic: H,
+    mut goal: G,
+) -> Search<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    F: FnMut(&N) -> I,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+{
+    let mut search = Search::new(paths);
+    let zero = C::default();
+    search.costs.insert(start.clone(), zero);
+
+    // the heap orders indices into `frontier`, so nodes don't have to be Ord;
+    // equal priorities come out in the order they were pushed
+    let mut frontier = vec![Some((start.clone(), zero))];
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((heuristic(&start), 0)));
+
+    while let Some(Reverse((_, index))) = heap.pop() {
+        let (node, cost) = frontier[index].take().unwrap();
+        // we may have already found a better way
+        if search.costs[&node] < cost {
+            continue;
+        }
+
+        search.expanded += 1;
+        if goal(&node) {
+            search.goal = Some((node, cost));
+            break;
+        }
+
+        for (next, step) in neighbours(&node) {
+            let next_cost = cost + step;
+            let known = search.costs.get(&next);
+            if !matches!(known, Some(&best) if best <= next_cost) {
+                search.reached(next.clone(), next_cost, &node);
+                heap.push(Reverse((next_cost + heuristic(&next), frontier.len())));
+                frontier.push(Some((next, next_cost)));
+            }
+        }
+    }
+    search
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::{Coordinate, Grid};
+
+    fn open_tiles(grid: &Grid<bool>, c: Coordinate) -> Vec<(Coordinate, usize)> {
+        grid.neighbours(c)
+            .filter(|&(_, &open)| open)
+            .map(|(next, _)| (next, 1))
+            .collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = Grid::parse("..#\n#..\n...", "", |c| Some(c == '.')).unwrap();
+        let goal = Coordinate::new(0, 2);
+        let found = bfs(
+            Coordinate::new(0, 0),
+            Paths::Track,
+            |&c| open_tiles(&grid, c).into_iter().map(|(next, _)| next),
+            |&c| c == goal,
+        );
+
+        assert_eq!(found.goal, Some((goal, 4)));
+        assert_eq!(
+            found.path(),
+            Some(vec![
+                Coordinate::new(0, 0),
+                Coordinate::new(1, 0),
+                Coordinate::new(1, 1),
+                Coordinate::new(1, 2),
+                Coordinate::new(0, 2),
+            ])
+        );
+
+        // without a goal every open tile gets a distance
+        let everything = bfs(
+            Coordinate::new(0, 0),
+            Paths::Skip,
+            |&c| open_tiles(&grid, c).into_iter().map(|(next, _)| next),
+            |_| false,
+        );
+        assert_eq!(everything.goal, None);
+        assert_eq!(everything.costs.len(), 7);
+        assert_eq!(everything.expanded, 7);
+        assert_eq!(everything.path_to(&goal), None);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let mut graph = HashMap::new();
+        graph.insert('a', vec![('b', 7), ('c', 2)]);
+        graph.insert('b', vec![('d', 1)]);
+        graph.insert('c', vec![('b', 3), ('d', 9)]);
+        graph.insert('d', vec![]);
+        graph.insert('e', vec![('a', 1)]);
+
+        let found = dijkstra('a', Paths::Track, |n| graph[n].clone(), |&n| n == 'd');
+        assert_eq!(found.goal, Some(('d', 6)));
+        assert_eq!(found.path(), Some(vec!['a', 'c', 'b', 'd']));
+
+        let unreachable = dijkstra('a', Paths::Skip, |n| graph[n].clone(), |&n| n == 'e');
+        assert_eq!(unreachable.goal, None);
+        assert_eq!(unreachable.costs.len(), 4);
+    }
+
+    #[test]
+    fn test_astar() {
+        let grid = Grid::new(20, 20, true);
+        let (start, goal) = (Coordinate::new(0, 0), Coordinate::new(5, 5));
+
+        let plain = dijkstra(
+            start,
+            Paths::Skip,
+            |&c| open_tiles(&grid, c),
+            |&c| c == goal,
+        );
+        let guided = astar(
+            start,
+            Paths::Track,
+            |&c| open_tiles(&grid, c),
+            |&c| c.manhattan(goal) as usize,
+            |&c| c == goal,
+        );
+
+        assert_eq!(plain.goal, Some((goal, 10)));
+        assert_eq!(guided.goal, Some((goal, 10)));
+        assert_eq!(guided.path().map(|path| path.len()), Some(11));
+        assert!(guided.expanded < plain.expanded);
+    }
+}
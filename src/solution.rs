@@ -0,0 +1,285 @@
+// Uniform interface over every day's solve_first / solve_second
+// Days return whatever integer type fits their puzzle; `Answer` wraps those,
+// plain strings, pixel images, and letters together with the picture they
+// were read from.
+use std::fmt;
+
+use crate::live::Session;
+use crate::ocr;
+use crate::params::Overrides;
+use crate::parse::ParseError;
+use crate::record::Recorder;
+use crate::render::Picture;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+    Image(Bitmap),
+    // letters read off a picture, the picture is kept so it can be saved
+    Lettering(String, Bitmap),
+}
+
+impl Answer {
+    // reads the letters drawn in `image`
+    pub fn lettering(image: Bitmap) -> Result<Answer, ParseError> {
+        let letters = ocr::read(&image)?;
+        Ok(Answer::Lettering(letters, image))
+    }
+
+    // the picture behind the answer, if there is one
+    pub fn image(&self) -> Option<&Bitmap> {
+        match self {
+            Answer::Image(image) | Answer::Lettering(_, image) => Some(image),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! integer_answer {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Answer {
+                Answer::Integer(n as i128)
+            }
+        })*
+    };
+}
+
+integer_answer!(i32, i64, i128, u16, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+impl From<Bitmap> for Answer {
+    fn from(image: Bitmap) -> Answer {
+        Answer::Image(image)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Image(image) => write!(f, "\n{}", image),
+            Answer::Lettering(letters, _) => write!(f, "{}", letters),
+        }
+    }
+}
+
+// black and white picture, row by row
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Bitmap {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<bool>,
+}
+
+impl Bitmap {
+    pub fn new(width: usize, height: usize) -> Bitmap {
+        Bitmap {
+            width,
+            height,
+            pixels: vec![false; width * height],
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, lit: bool) {
+        self.pixels[y * self.width + x] = lit;
+    }
+
+    // lit pixels are white
+    pub fn save_png(&self, path: &str) -> Result<(), String> {
+        Picture::from(self).save_png(path, 1)
+    }
+}
+
+impl fmt::Display for Bitmap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.pixels.chunks(self.width.max(1)) {
+            let line: String = row.iter().map(|&lit| if lit { '#' } else { '.' }).collect();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+// where a day gets its puzzle input from
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Input {
+    // src/aocDD/input, or a path given on the command line
+    File,
+    // the puzzle input is baked into the solution (day 4)
+    None,
+    // the input file, plus commands typed on stdin while running (day 25)
+    Interactive,
+}
+
+pub trait Solution: Sync {
+    fn day(&self) -> u32;
+    fn input(&self) -> Input;
+    fn parts(&self) -> u32;
+    // None if the day has no such part
+    fn solve(&self, part: u32, input: &str) -> Option<Result<Answer, ParseError>>;
+    // a picture of the puzzle, None if the day has nothing to draw
+    fn render(&self, input: &str) -> Option<Result<Picture, ParseError>>;
+    // offers each step of the day's simulation to the recorder,
+    // None if the day doesn't simulate anything worth watching
+    fn record(&self, input: &str, recorder: &mut Recorder) -> Option<Result<(), ParseError>>;
+    // the day's program started for watching, None if it has nothing to watch
+    fn live(&self, input: &str) -> Option<Result<Box<dyn Session>, ParseError>>;
+    // a random puzzle input that grows with `size`, the same for the same
+    // seed, None if the day has no generator
+    fn generate(&self, size: usize, seed: u64) -> Option<String>;
+    // the constants the day's parts use and their defaults, empty if none
+    fn parameters(&self) -> Vec<(&'static str, String)>;
+    // solve with some of those constants changed, Err if the day doesn't
+    // have one of them or a value doesn't parse
+    fn solve_with(&self, part: u32, input: &str, overrides: &Overrides) -> Result<Tuned, String>;
+}
+
+// one part of a day, from puzzle input to answer
+pub type Part = fn(&str) -> Result<Answer, ParseError>;
+
+pub type Render = fn(&str) -> Result<Picture, ParseError>;
+
+pub type Record = fn(&str, &mut Recorder) -> Result<(), ParseError>;
+
+pub type Live = fn(&str) -> Result<Box<dyn Session>, ParseError>;
+
+pub type Generate = fn(usize, u64) -> String;
+
+// what `solve` gives for a part, or None if the day doesn't have it
+pub type Tuned = Option<Result<Answer, ParseError>>;
+
+// a day's `Params`: the names with their defaults, and both parts solved
+// with some of them overridden
+pub struct Tuning {
+    pub defaults: fn() -> Vec<(&'static str, String)>,
+    pub solve: fn(u32, &str, &Overrides) -> Result<Tuned, String>,
+}
+
+pub struct Day {
+    pub day: u32,
+    pub input: Input,
+    pub first: Part,
+    pub second: Option<Part>,
+    pub render: Option<Render>,
+    pub record: Option<Record>,
+    pub live: Option<Live>,
+    pub generate: Option<Generate>,
+    pub tuning: Option<Tuning>,
+}
+
+impl Solution for Day {
+    fn day(&self) -> u32 {
+        self.day
+    }
+
+    fn input(&self) -> Input {
+        self.input
+    }
+
+    fn parts(&self) -> u32 {
+        if self.second.is_some() {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn solve(&self, part: u32, input: &str) -> Option<Result<Answer, ParseError>> {
+        match part {
+            1 => Some((self.first)(input)),
+            2 => self.second.map(|second| second(input)),
+            _ => None,
+        }
+    }
+
+    fn render(&self, input: &str) -> Option<Result<Picture, ParseError>> {
+        self.render.map(|render| render(input))
+    }
+
+    fn record(&self, input: &str, recorder: &mut Recorder) -> Option<Result<(), ParseError>> {
+        self.record.map(|record| record(input, recorder))
+    }
+
+    fn live(&self, input: &str) -> Option<Result<Box<dyn Session>, ParseError>> {
+        self.live.map(|live| live(input))
+    }
+
+    fn generate(&self, size: usize, seed: u64) -> Option<String> {
+        self.generate.map(|generate| generate(size, seed))
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        self.tuning
+            .as_ref()
+            .map_or_else(Vec::new, |tuning| (tuning.defaults)())
+    }
+
+    fn solve_with(&self, part: u32, input: &str, overrides: &Overrides) -> Result<Tuned, String> {
+        match &self.tuning {
+            Some(tuning) => (tuning.solve)(part, input, overrides),
+            None if overrides.is_empty() => Ok(self.solve(part, input)),
+            None => Err(format!("day {} has no parameters", self.day)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Answer::from(27697279941366i128).to_string(),
+            "27697279941366"
+        );
+        assert_eq!(Answer::from(String::from("CJZHR")).to_string(), "CJZHR");
+
+        let mut image = Bitmap::new(3, 2);
+        image.set(0, 0, true);
+        image.set(2, 1, true);
+        assert!(image.get(2, 1));
+        assert_eq!(Answer::from(image).to_string(), "\n#..\n..#\n");
+    }
+
+    #[test]
+    fn test_day() {
+        let day = Day {
+            day: 25,
+            input: Input::Interactive,
+            first: |input| match input {
+                "" => Err(ParseError::new(1, 1, "", "a command")),
+                _ => Ok(Answer::from(input.len())),
+            },
+            second: None,
+            render: None,
+            record: None,
+            live: None,
+            generate: None,
+            tuning: None,
+        };
+        assert_eq!(day.parts(), 1);
+        assert_eq!(day.solve(1, "abc"), Some(Ok(Answer::Integer(3))));
+        assert!(day.solve(1, "").unwrap().is_err());
+        assert_eq!(day.solve(2, "abc"), None);
+        assert_eq!(day.render("abc"), None);
+        assert!(day.live("abc").is_none());
+        assert_eq!(day.generate(10, 0), None);
+        assert!(day.parameters().is_empty());
+        assert_eq!(day.solve_with(1, "abc", &[]), Ok(day.solve(1, "abc")));
+        let overrides = [(String::from("steps"), String::from("10"))];
+        assert!(day.solve_with(1, "abc", &overrides).is_err());
+    }
+}
@@ -0,0 +1,230 @@
+// Wall time per day and part for the run-all report
+// Each solution runs a few times untimed to warm caches, then `repeats`
+// times; the median is reported. A baseline file of earlier medians, one
+// `day part nanoseconds` line each, is used to flag days that got slower.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::time::{Duration, Instant};
+
+// slower than the baseline by more than this factor counts as a regression
+const REGRESSION_FACTOR: f64 = 1.25;
+// below this, timings are mostly noise
+const MIN_REGRESSION: Duration = Duration::from_millis(1);
+
+pub struct Timing {
+    pub day: u32,
+    pub part: u32,
+    pub answer: String,
+    pub median: Duration,
+}
+
+pub fn median(mut samples: Vec<Duration>) -> Duration {
+    samples.sort();
+    let mid = samples.len() / 2;
+    if samples.is_empty() {
+        Duration::default()
+    } else if samples.len().is_multiple_of(2) {
+        (samples[mid - 1] + samples[mid]) / 2
+    } else {
+        samples[mid]
+    }
+}
+
+// runs solve warmup + repeats times, returns the last answer and the median time
+pub fn measure<F>(warmup: usize, repeats: usize, mut solve: F) -> Result<(String, Duration), String>
+where
+    F: FnMut() -> Result<String, String>,
+{
+    for _ in 0..warmup {
+        solve()?;
+    }
+    let mut samples = Vec::with_capacity(repeats);
+    let mut answer = String::new();
+    for _ in 0..repeats.max(1) {
+        let start = Instant::now();
+        answer = solve()?;
+        samples.push(start.elapsed());
+    }
+    Ok((answer, median(samples)))
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Baseline {
+    medians: BTreeMap<(u32, u32), Duration>,
+}
+
+impl Baseline {
+    pub fn parse(contents: &str) -> Result<Baseline, String> {
+        let mut medians = BTreeMap::new();
+        for (idx, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<u64> = line
+                .split_whitespace()
+                .map(|field| field.parse())
+                .collect::<Result<_, _>>()
+                .map_err(|_| {
+                    format!(
+                        "baseline line {}: expected numbers, got '{}'",
+                        idx + 1,
+                        line
+                    )
+                })?;
+            match fields[..] {
+                [day, part, nanos] => {
+                    medians.insert((day as u32, part as u32), Duration::from_nanos(nanos));
+                }
+                _ => {
+                    return Err(format!(
+                        "baseline line {}: expected 'day part nanoseconds', got '{}'",
+                        idx + 1,
+                        line
+                    ))
+                }
+            }
+        }
+        Ok(Baseline { medians })
+    }
+
+    pub fn load(path: &str) -> Result<Baseline, String> {
+        let contents =
+            fs::read_to_string(path).map_err(|e| format!("couldn't read {}: {}", path, e))?;
+        Baseline::parse(&contents)
+    }
+
+    pub fn from_timings(timings: &[Timing]) -> Baseline {
+        let medians = timings
+            .iter()
+            .map(|t| ((t.day, t.part), t.median))
+            .collect();
+        Baseline { medians }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_string()).map_err(|e| format!("couldn't write {}: {}", path, e))
+    }
+
+    pub fn get(&self, day: u32, part: u32) -> Option<Duration> {
+        self.medians.get(&(day, part)).copied()
+    }
+
+    // how many times slower than the baseline, if it's a regression
+    pub fn regression(&self, timing: &Timing) -> Option<f64> {
+        let before = self.get(timing.day, timing.part)?;
+        if timing.median < MIN_REGRESSION {
+            return None;
+        }
+        let factor = timing.median.as_secs_f64() / before.as_secs_f64().max(1e-9);
+        if factor > REGRESSION_FACTOR {
+            Some(factor)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "# day part median-nanoseconds")?;
+        for ((day, part), median) in self.medians.iter() {
+            writeln!(f, "{} {} {}", day, part, median.as_nanos())?;
+        }
+        Ok(())
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_secs_f64() * 1e6;
+    if micros < 1000.0 {
+        format!("{:.0}µs", micros)
+    } else if micros < 1e6 {
+        format!("{:.2}ms", micros / 1000.0)
+    } else {
+        format!("{:.2}s", micros / 1e6)
+    }
+}
+
+// prints the table, returns the number of regressions
+pub fn report(timings: &[Timing], baseline: Option<&Baseline>) -> usize {
+    let mut regressions = 0;
+    println!(
+        "{:>3} {:>4} {:>20} {:>10} {:>10}",
+        "day", "part", "answer", "median", "baseline"
+    );
+    for timing in timings {
+        let before = baseline.and_then(|b| b.get(timing.day, timing.part));
+        let flag = match baseline.and_then(|b| b.regression(timing)) {
+            Some(factor) => {
+                regressions += 1;
+                format!("  slower x{:.2}", factor)
+            }
+            None => String::new(),
+        };
+        println!(
+            "{:>3} {:>4} {:>20} {:>10} {:>10}{}",
+            timing.day,
+            timing.part,
+            timing.answer,
+            format_duration(timing.median),
+            before
+                .map(format_duration)
+                .unwrap_or_else(|| String::from("-")),
+            flag
+        );
+    }
+    let total = timings.iter().map(|t| t.median).sum();
+    println!("total {}", format_duration(total));
+    if regressions > 0 {
+        println!("{} regressions", regressions);
+    }
+    regressions
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_median() {
+        let ms = Duration::from_millis;
+        assert_eq!(median(vec![ms(5), ms(1), ms(3)]), ms(3));
+        assert_eq!(median(vec![ms(4), ms(1), ms(2), ms(9)]), ms(3));
+        assert_eq!(median(vec![]), ms(0));
+    }
+
+    #[test]
+    fn test_measure() {
+        let mut calls = 0;
+        let (answer, _) = measure(2, 3, || {
+            calls += 1;
+            Ok(calls.to_string())
+        })
+        .unwrap();
+        assert_eq!(answer, "5");
+        assert!(measure(1, 1, || Err(String::from("failed"))).is_err());
+    }
+
+    #[test]
+    fn test_baseline() {
+        let timing = |day, millis| Timing {
+            day,
+            part: 1,
+            answer: String::new(),
+            median: Duration::from_millis(millis),
+        };
+        let baseline = Baseline::from_timings(&[timing(18, 100), timing(19, 100)]);
+        let parsed = Baseline::parse(&baseline.to_string()).unwrap();
+        assert_eq!(parsed, baseline);
+
+        assert_eq!(parsed.regression(&timing(18, 110)), None);
+        assert_eq!(parsed.regression(&timing(19, 200)), Some(2.0));
+        // not in the baseline
+        assert_eq!(parsed.regression(&timing(20, 200)), None);
+
+        assert!(Baseline::parse("18 1").is_err());
+        assert!(Baseline::parse("18 one 5").is_err());
+    }
+}
@@ -0,0 +1,187 @@
+// Watching a day's program run in the terminal
+// stty takes the terminal out of line mode so keys arrive one at a time, a
+// thread forwards them, and every frame redraws the screen with ANSI escapes.
+// Ctrl-C is read as a key too, so the terminal always gets restored.
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use aoc2019::live::Session;
+
+const FRAME: Duration = Duration::from_millis(50);
+pub const MAX_SPEED: u32 = 4096;
+const HELP: &str = "space pause, n step, + faster, - slower, q quit";
+
+#[derive(Debug, PartialEq, Eq)]
+struct Controls {
+    paused: bool,
+    // steps per frame while running
+    speed: u32,
+    // single steps asked for while paused
+    pending: u32,
+    quit: bool,
+}
+
+impl Controls {
+    fn new(speed: u32) -> Controls {
+        Controls {
+            paused: false,
+            speed,
+            pending: 0,
+            quit: false,
+        }
+    }
+
+    fn key(&mut self, key: u8) {
+        match key {
+            b' ' | b'p' => self.paused = !self.paused,
+            b'n' | b'.' if self.paused => self.pending += 1,
+            b'+' | b'=' => self.speed = (self.speed * 2).min(MAX_SPEED),
+            b'-' | b'_' => self.speed = (self.speed / 2).max(1),
+            // ctrl-c
+            b'q' | 3 => self.quit = true,
+            _ => {}
+        }
+    }
+
+    // how many steps to run this frame
+    fn steps(&mut self) -> u32 {
+        if self.paused {
+            std::mem::replace(&mut self.pending, 0)
+        } else {
+            self.speed
+        }
+    }
+}
+
+fn stty(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()
+        .map_err(|e| format!("couldn't run stty: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from("--live needs a terminal"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// the terminal settings to go back to, restored even if the day panics
+struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    fn enter() -> Result<RawMode, String> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "-isig", "min", "1"])?;
+        // alternate screen, hidden cursor
+        print!("\x1b[?1049h\x1b[?25l");
+        Ok(RawMode { saved })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+// the reading thread blocks on stdin until the process exits
+fn keys() -> Receiver<u8> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for byte in io::stdin().lock().bytes() {
+            match byte {
+                Ok(byte) if sender.send(byte).is_ok() => {}
+                _ => break,
+            }
+        }
+    });
+    receiver
+}
+
+fn draw(day: u32, session: &dyn Session, controls: &Controls, steps: usize, halted: bool) {
+    let state = if halted {
+        String::from("halted")
+    } else if controls.paused {
+        String::from("paused")
+    } else {
+        format!("{} steps per frame", controls.speed)
+    };
+
+    let mut screen = String::from("\x1b[H");
+    screen.push_str(&session.picture().ansi().replace('\n', "\x1b[K\n"));
+    screen.push_str(&format!(
+        "day {:02}, step {}, {}\x1b[K\n{}\x1b[K\n{}\x1b[J",
+        day,
+        steps,
+        state,
+        session.status(),
+        HELP
+    ));
+    print!("{}", screen);
+    let _ = io::stdout().flush();
+}
+
+// runs until q, the last picture stays up once the program halts
+pub fn run(day: u32, session: &mut dyn Session, speed: u32) -> Result<(), String> {
+    let _raw = RawMode::enter()?;
+    let keys = keys();
+    let mut controls = Controls::new(speed);
+    let mut steps = 0;
+    let mut halted = false;
+
+    while !controls.quit {
+        let start = Instant::now();
+        for key in keys.try_iter() {
+            controls.key(key);
+        }
+        for _ in 0..controls.steps() {
+            if halted || !session.step() {
+                halted = true;
+                break;
+            }
+            steps += 1;
+        }
+
+        draw(day, session, &controls, steps, halted);
+        if let Some(left) = FRAME.checked_sub(start.elapsed()) {
+            thread::sleep(left);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_controls() {
+        let mut controls = Controls::new(4);
+        assert_eq!(controls.steps(), 4);
+
+        controls.key(b'+');
+        assert_eq!(controls.steps(), 8);
+        for _ in 0..4 {
+            controls.key(b'-');
+        }
+        assert_eq!(controls.steps(), 1);
+
+        // steps only count while paused
+        controls.key(b'n');
+        controls.key(b' ');
+        controls.key(b'n');
+        controls.key(b'n');
+        assert_eq!(controls.steps(), 2);
+        assert_eq!(controls.steps(), 0);
+
+        controls.key(3);
+        assert!(controls.quit);
+    }
+}